@@ -1,36 +1,183 @@
 //! Tauri commands for server and tunnel management
 
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
 use std::time::Duration;
 
-use crate::common::DEFAULT_PORT;
+use crate::config;
+use crate::diagnostics;
+use crate::docker;
+use crate::log_file;
+use crate::logging;
 use crate::server;
 use crate::tailscale;
 use crate::remote_access;
 use crate::tunnel;
+use crate::updater;
+use crate::RemoteAccessWatcherState;
+use crate::ScanCacheState;
+use crate::ScanState;
 use crate::ServerState;
 use crate::TunnelState;
-use tauri::State;
+use crate::UpdateState;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Error type for command results
 type CommandResult<T> = Result<T, String>;
 
+/// Structured error for server and tunnel commands
+///
+/// Serializes as `{ "kind": "PortInUse", "message": "..." }` so the frontend
+/// can branch on `kind` while still showing `message` to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// The port is already bound by another process
+    PortInUse(u16),
+    /// Another process took the port between the check and the spawn
+    PortRaced(u16),
+    /// The service is not running
+    NotRunning(String),
+    /// The service is already running
+    AlreadyRunning(String),
+    /// Not allowed to bind the port or spawn the process
+    PermissionDenied(String),
+    /// The process could not be started
+    SpawnFailed(String),
+    /// The process could not be stopped
+    StopFailed(String),
+    /// A parameter was rejected before doing any work
+    InvalidInput(String),
+    /// The operation did not finish in time
+    Timeout(String),
+    /// A required external tool or service is missing or not running
+    NotAvailable(String),
+}
+
+impl CommandError {
+    /// Stable identifier for the error kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CommandError::PortInUse(_) => "PortInUse",
+            CommandError::PortRaced(_) => "PortRaced",
+            CommandError::NotRunning(_) => "NotRunning",
+            CommandError::AlreadyRunning(_) => "AlreadyRunning",
+            CommandError::PermissionDenied(_) => "PermissionDenied",
+            CommandError::SpawnFailed(_) => "SpawnFailed",
+            CommandError::StopFailed(_) => "StopFailed",
+            CommandError::InvalidInput(_) => "InvalidInput",
+            CommandError::Timeout(_) => "Timeout",
+            CommandError::NotAvailable(_) => "NotAvailable",
+        }
+    }
+
+    /// Classify an error from binding `port`
+    pub fn from_bind_error(port: u16, error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::AddrInUse => CommandError::PortInUse(port),
+            std::io::ErrorKind::PermissionDenied => {
+                CommandError::PermissionDenied(format!("Cannot bind port {port}: {error}"))
+            }
+            _ => CommandError::SpawnFailed(format!("Cannot check port: {error}")),
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::PortInUse(port) => write!(f, "Port {port} is already in use"),
+            CommandError::PortRaced(port) => {
+                write!(f, "Port {port} was taken by another process while the server was starting")
+            }
+            CommandError::NotRunning(what) => write!(f, "{what} is not running"),
+            CommandError::AlreadyRunning(what) => write!(f, "{what} is already running"),
+            CommandError::PermissionDenied(msg)
+            | CommandError::SpawnFailed(msg)
+            | CommandError::StopFailed(msg)
+            | CommandError::InvalidInput(msg)
+            | CommandError::Timeout(msg)
+            | CommandError::NotAvailable(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl serde::Serialize for CommandError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("CommandError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// Ensures no managed server is running on `port`
+///
+/// # Errors
+///
+/// Returns [`CommandError::AlreadyRunning`] if the port is taken
+pub fn ensure_port_unmanaged<H>(servers: &HashMap<u16, H>, port: u16) -> Result<(), CommandError> {
+    if servers.contains_key(&port) {
+        return Err(CommandError::AlreadyRunning(format!("Server on port {port}")));
+    }
+    Ok(())
+}
+
+/// Removes the managed server running on `port`
+///
+/// # Errors
+///
+/// Returns [`CommandError::NotRunning`] if no server runs on that port
+pub fn take_server<H>(servers: &mut HashMap<u16, H>, port: u16) -> Result<H, CommandError> {
+    servers
+        .remove(&port)
+        .ok_or_else(|| CommandError::NotRunning(format!("Server on port {port}")))
+}
+
+/// Picks the managed server on `port`, or the one on the lowest port
+pub fn select_server<H>(servers: &HashMap<u16, H>, port: Option<u16>) -> Option<&H> {
+    match port {
+        Some(port) => servers.get(&port),
+        None => servers.keys().min().and_then(|port| servers.get(port)),
+    }
+}
+
 /// Starts the backend server on the specified port
 ///
+/// Several servers can run at once, one per port.
+///
+/// The server is restarted up to `max_restarts` times (default 3) if it
+/// crashes, emitting [`server::SERVER_RESTARTED_EVENT`] after each restart.
+/// `extra_env` is passed to the server process; `PORT` and `DB_PATH` can't
+/// be overridden. In development mode `package_manager` (default: the
+/// `package_manager` config key) runs the `dev` script. Ports below 1024 are rejected unless
+/// `allow_privileged_port` is set.
+///
 /// # Errors
 ///
-/// Returns an error if the server is already running or fails to start
+/// Returns an error if a server is already running on the port or fails to start
 #[tauri::command]
 pub async fn start_server(
+    app: AppHandle,
     state: State<'_, ServerState>,
     port: u16,
-) -> CommandResult<String> {
-    crate::common::validate_port(port)?;
+    max_restarts: Option<u32>,
+    extra_env: Option<HashMap<String, String>>,
+    package_manager: Option<server::PackageManager>,
+    allow_privileged_port: Option<bool>,
+) -> Result<String, CommandError> {
+    let allow_privileged_port = allow_privileged_port.unwrap_or(false);
+    crate::common::validate_port_allowing_privileged(port, allow_privileged_port)
+        .map_err(CommandError::InvalidInput)?;
+    let extra_env = extra_env.unwrap_or_default();
+    server::validate_env_keys(&extra_env).map_err(CommandError::InvalidInput)?;
 
-    let mut server_state = state.0.lock().await;
+    let server_state = state.0.lock().await;
 
-    if server_state.is_some() {
-        return Err("Server is already running".to_string());
-    }
+    ensure_port_unmanaged(&server_state, port)?;
 
     // Check if port is already in use by an external server
     use std::net::TcpListener;
@@ -44,36 +191,380 @@ pub async fn start_server(
             if e.kind() == std::io::ErrorKind::AddrInUse {
                 // Port is in use by an external process - this is actually OK
                 // We just can't manage it, but we should report the server as running
+                remember_server_port(port);
                 return Ok(format!("Server already running on port {port}"));
             } else {
                 // Some other error (permission denied, network issue, etc.)
-                return Err(format!("Cannot check port: {}", e));
+                return Err(CommandError::from_bind_error(port, &e));
             }
         }
     }
 
     // Port is available, start our managed server
-    let handle = server::start(port).map_err(|e| e)?;
-    *server_state = Some(handle);
+    let mut config = server::ServerConfig::new(port);
+    if let Some(max_restarts) = max_restarts {
+        config.max_restarts = max_restarts;
+    }
+    config.extra_env = extra_env;
+    config.package_manager = package_manager.unwrap_or_else(|| config::load().package_manager);
+    config.allow_privileged_port = allow_privileged_port;
+    let handle = server::start(config, move |restarted| {
+        let _ = app.emit(server::SERVER_RESTARTED_EVENT, restarted);
+    })
+    .map_err(CommandError::SpawnFailed)?;
+    // Status and stop commands shouldn't wait on the verification below
+    drop(server_state);
+
+    // The port was free when checked, but something may have bound it since
+    let verified = verify_port_owner(
+        port,
+        PORT_OWNER_VERIFY_TIMEOUT,
+        PORT_OWNER_POLL_INTERVAL,
+        handle.logs(),
+        || handle.process().is_some(),
+    )
+    .await;
+
+    let mut server_state = state.0.lock().await;
+    if let Err(e) = verified.and_then(|()| ensure_port_unmanaged(&server_state, port)) {
+        drop(server_state);
+        if let Err(stop_error) = server::stop(handle).await {
+            tracing::warn!(target: "Server", "Failed to stop server after losing port {port}: {stop_error}");
+        }
+        return Err(e);
+    }
+
+    server_state.insert(port, handle);
+    remember_server_port(port);
     Ok(format!("Server started on port {port}"))
 }
 
-/// Stops the backend server
+/// Validates everything [`start_server`] needs without starting anything
+///
+/// Checks the port number and that the port is free. What runs the server
+/// depends on the mode, as in [`start_server`]: in production, that Node.js
+/// can be found and the server script exists; in development, that the
+/// package manager (default: the `package_manager` config key) can be found
+/// and the server package exists. The report includes the resolved paths.
+#[tauri::command]
+pub async fn precheck_server_start(
+    state: State<'_, ServerState>,
+    port: u16,
+    allow_privileged_port: Option<bool>,
+    package_manager: Option<server::PackageManager>,
+) -> CommandResult<ServerStartPrecheck> {
+    let port_valid =
+        crate::common::validate_port_allowing_privileged(port, allow_privileged_port.unwrap_or(false));
+    let port_available = match port_valid {
+        Ok(()) => Some(port_availability(&*state.0.lock().await, port)),
+        Err(_) => None,
+    };
+
+    let launch = if server::is_development_mode() {
+        let package_manager = package_manager.unwrap_or_else(|| config::load().package_manager);
+        LaunchChecks::Development {
+            command: package_manager.find_command(),
+            server_dir: server::find_dev_server_dir(),
+        }
+    } else {
+        LaunchChecks::Production {
+            node: crate::common::find_node_executable(),
+            server_script: server::find_server_script(),
+        }
+    };
+
+    Ok(ServerStartPrecheck::from_checks(ServerStartChecks {
+        port,
+        port_valid,
+        port_available,
+        launch,
+    }))
+}
+
+/// Whether a new server could bind `port`
+///
+/// Unlike [`start_server`], a port held by an external server counts as taken.
+///
+/// # Errors
+///
+/// Returns why the port can't be used
+pub fn port_availability<H>(servers: &HashMap<u16, H>, port: u16) -> Result<(), String> {
+    ensure_port_unmanaged(servers, port).map_err(|e| e.to_string())?;
+    std::net::TcpListener::bind(format!("0.0.0.0:{port}"))
+        .map(drop)
+        .map_err(|e| CommandError::from_bind_error(port, &e).to_string())
+}
+
+/// Something [`start_server`] needs before it can spawn the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartPrecondition {
+    /// The port number is allowed
+    ValidPort,
+    /// Nothing listens on the port yet
+    PortAvailable,
+    /// A Node.js executable was found
+    NodeFound,
+    /// The server script exists
+    ServerScriptFound,
+    /// The package manager running the dev server was found
+    PackageManagerFound,
+    /// The server package with the `dev` script exists
+    DevServerFound,
+}
+
+/// Result of one precondition check
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PreconditionCheck {
+    pub precondition: StartPrecondition,
+    pub ok: bool,
+    /// Why it failed; `None` when the check passed
+    pub error: Option<String>,
+}
+
+/// Results of the individual checks behind [`precheck_server_start`]
+pub struct ServerStartChecks {
+    /// The port the server would use
+    pub port: u16,
+    /// Port number validation
+    pub port_valid: Result<(), String>,
+    /// Port availability; `None` if the port was invalid and not checked
+    pub port_available: Option<Result<(), String>>,
+    /// What would run the server
+    pub launch: LaunchChecks,
+}
+
+/// Checks for what runs the server, which differs by mode
+pub enum LaunchChecks {
+    /// Node.js runs the bundled server script
+    Production {
+        /// Resolved Node.js executable
+        node: Result<String, String>,
+        /// Resolved server script
+        server_script: Result<std::path::PathBuf, String>,
+    },
+    /// A package manager runs the server package's `dev` script
+    Development {
+        /// Resolved package manager command
+        command: Result<String, String>,
+        /// Resolved server package directory
+        server_dir: Result<std::path::PathBuf, String>,
+    },
+}
+
+/// Whether [`start_server`] would be able to start, with a check per precondition
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ServerStartPrecheck {
+    pub ready: bool,
+    pub port: u16,
+    /// Whether the dev server would run instead of the bundled script
+    pub development: bool,
+    /// Node.js executable that would run the server
+    pub node_path: Option<String>,
+    /// Server script that would be run
+    pub server_script: Option<String>,
+    /// Package manager command that would run the dev server
+    pub package_manager_path: Option<String>,
+    /// Server package whose `dev` script would be run
+    pub dev_server_dir: Option<String>,
+    pub checks: Vec<PreconditionCheck>,
+}
+
+impl ServerStartPrecheck {
+    /// Builds the report from check results
+    pub fn from_checks(checks: ServerStartChecks) -> Self {
+        let check = |precondition, error: Option<&String>| PreconditionCheck {
+            precondition,
+            ok: error.is_none(),
+            error: error.cloned(),
+        };
+
+        let mut results = vec![check(StartPrecondition::ValidPort, checks.port_valid.as_ref().err())];
+        if let Some(port_available) = &checks.port_available {
+            results.push(check(StartPrecondition::PortAvailable, port_available.as_ref().err()));
+        }
+        let path_string = |path: std::path::PathBuf| path.to_string_lossy().to_string();
+
+        let mut precheck = Self {
+            ready: false,
+            port: checks.port,
+            development: false,
+            node_path: None,
+            server_script: None,
+            package_manager_path: None,
+            dev_server_dir: None,
+            checks: Vec::new(),
+        };
+        match checks.launch {
+            LaunchChecks::Production { node, server_script } => {
+                results.push(check(StartPrecondition::NodeFound, node.as_ref().err()));
+                results.push(check(StartPrecondition::ServerScriptFound, server_script.as_ref().err()));
+                precheck.node_path = node.ok();
+                precheck.server_script = server_script.ok().map(path_string);
+            }
+            LaunchChecks::Development { command, server_dir } => {
+                results.push(check(StartPrecondition::PackageManagerFound, command.as_ref().err()));
+                results.push(check(StartPrecondition::DevServerFound, server_dir.as_ref().err()));
+                precheck.development = true;
+                precheck.package_manager_path = command.ok();
+                precheck.dev_server_dir = server_dir.ok().map(path_string);
+            }
+        }
+
+        precheck.ready = results.iter().all(|check| check.ok);
+        precheck.checks = results;
+        precheck
+    }
+}
+
+/// How long [`start_server`] watches a new server to make sure it owns its port
+pub const PORT_OWNER_VERIFY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time between ownership checks while verifying
+const PORT_OWNER_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Who holds a port a new server is meant to bind, judged from our child
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortOccupant {
+    /// Our server is running and has announced that it's listening
+    Ours,
+    /// Something else holds the port and our server couldn't bind it
+    Foreign,
+    /// Not decided yet, e.g. the server is still booting
+    Unknown,
+}
+
+/// Judges who holds a port from whether it's bound and our child's state
+///
+/// `logs` is the child's output so far. Held while our child is alive and
+/// has announced it's listening means it's ours; held after our child
+/// exited, or after it reported `EADDRINUSE`, means we lost the port.
+pub fn port_occupant(port_held: bool, child_running: bool, logs: &[String]) -> PortOccupant {
+    if !port_held {
+        return PortOccupant::Unknown;
+    }
+    if !child_running || logs.iter().any(|line| line.contains("EADDRINUSE")) {
+        return PortOccupant::Foreign;
+    }
+    if logs.iter().any(|line| server::parse_listening_port(line).is_some()) {
+        PortOccupant::Ours
+    } else {
+        PortOccupant::Unknown
+    }
+}
+
+/// Watches a freshly spawned server until it's clear it owns `port`
+///
+/// Gives up quietly after `timeout`, since a slow server isn't necessarily a
+/// lost race. `logs` is the server's output and `child_running` reports
+/// whether our process is still alive.
+///
+/// # Errors
+///
+/// Returns [`CommandError::PortRaced`] if something else holds the port and
+/// our process exited or failed with `EADDRINUSE`
+pub async fn verify_port_owner<F>(
+    port: u16,
+    timeout: Duration,
+    interval: Duration,
+    logs: &server::LogBuffer,
+    child_running: F,
+) -> Result<(), CommandError>
+where
+    F: Fn() -> bool,
+{
+    let verify = async {
+        loop {
+            let port_held = std::net::TcpListener::bind(format!("0.0.0.0:{port}")).is_err();
+            match port_occupant(port_held, child_running(), &logs.lines()) {
+                PortOccupant::Ours => return Ok(()),
+                PortOccupant::Foreign => return Err(CommandError::PortRaced(port)),
+                PortOccupant::Unknown => tokio::time::sleep(interval).await,
+            }
+        }
+    };
+
+    tokio::time::timeout(timeout, verify).await.unwrap_or(Ok(()))
+}
+
+/// Persists `port` so status checks find the server after a restart
+fn remember_server_port(port: u16) {
+    if let Err(e) = server::save_last_port(port) {
+        tracing::warn!(target: "Server", "Failed to remember server port: {e}");
+    }
+}
+
+/// Stops the backend server on `port`, or all managed servers if `port` is `None`
 ///
 /// # Errors
 ///
 /// Returns an error if the server is not running or fails to stop
 #[tauri::command]
-pub async fn stop_server(state: State<'_, ServerState>) -> CommandResult<String> {
+pub async fn stop_server(state: State<'_, ServerState>, port: Option<u16>) -> Result<String, CommandError> {
     let mut server_state = state.0.lock().await;
 
-    // Use if let instead of unwrap to avoid race condition
-    if let Some(handle) = server_state.take() {
-        server::stop(handle).await.map_err(|e| e)?;
+    if let Some(port) = port {
+        let handle = take_server(&mut server_state, port)?;
+        server::stop(handle).await.map_err(CommandError::StopFailed)?;
+        return Ok(format!("Server on port {port} stopped"));
+    }
+
+    if server_state.is_empty() {
+        return Err(CommandError::NotRunning("Server".to_string()));
+    }
+
+    let mut errors = Vec::new();
+    for (_, handle) in server_state.drain() {
+        if let Err(e) = server::stop(handle).await {
+            errors.push(e);
+        }
+    }
+
+    if errors.is_empty() {
         Ok("Server stopped".to_string())
     } else {
-        Err("Server is not running".to_string())
+        Err(CommandError::StopFailed(errors.join("; ")))
+    }
+}
+
+/// Kills the Node.js process listening on `port`, e.g. a server orphaned by a crash
+///
+/// Refuses to kill anything unless every listener is a Node.js process
+/// (see [`server::KILLABLE_PROCESS_NAMES`]).
+///
+/// # Returns
+///
+/// Returns the processes that were killed
+///
+/// # Errors
+///
+/// Returns an error if nothing listens on the port, a listener isn't
+/// Node.js, or a process can't be killed
+#[tauri::command]
+pub async fn kill_process_on_port(port: u16) -> Result<Vec<server::KilledProcess>, CommandError> {
+    crate::common::validate_port_allowing_privileged(port, true).map_err(CommandError::InvalidInput)?;
+
+    let pids = server::listening_pids(port).map_err(CommandError::StopFailed)?;
+    if pids.is_empty() {
+        return Err(CommandError::NotRunning(format!("Process on port {port}")));
+    }
+
+    let processes: Vec<server::KilledProcess> = pids
+        .into_iter()
+        .filter_map(|pid| server::process_name(pid).map(|name| server::KilledProcess { pid, name }))
+        .collect();
+    if let Some(process) = processes.iter().find(|p| !server::is_killable_process(&p.name)) {
+        return Err(CommandError::PermissionDenied(format!(
+            "Port {port} is held by {} (pid {}), which is not a Node.js process",
+            process.name, process.pid
+        )));
     }
+
+    for process in &processes {
+        server::kill_process(process.pid).map_err(CommandError::StopFailed)?;
+        tracing::info!(target: "Server", "Killed {} (pid {}) on port {port}", process.name, process.pid);
+    }
+    Ok(processes)
 }
 
 /// Gets the current server status
@@ -82,28 +573,28 @@ pub async fn stop_server(state: State<'_, ServerState>) -> CommandResult<String>
 ///
 /// Returns an error if failed to read the server state
 #[tauri::command]
-pub async fn get_server_status(state: State<'_, ServerState>) -> CommandResult<ServerStatus> {
+pub async fn get_server_status(state: State<'_, ServerState>) -> CommandResult<Vec<ServerStatus>> {
     let server_state = state.0.lock().await;
 
-    // Check if we have a managed server
-    if server_state.is_some() {
-        let port = server_state.as_ref().map(|h| h.port).unwrap_or(DEFAULT_PORT);
-        println!("[get_server_status] Managed server found, port: {}", port);
-        return Ok(ServerStatus { running: true, port });
+    // Check if we have managed servers
+    if !server_state.is_empty() {
+        let statuses = managed_server_statuses(&server_state);
+        tracing::debug!(
+            target: "Server",
+            "Managed servers found, ports: {:?}",
+            statuses.iter().map(|status| status.port).collect::<Vec<_>>()
+        );
+        return Ok(statuses);
     }
 
-    // Check if an external server is running on the default port
-    use std::net::TcpListener;
-    let port = DEFAULT_PORT;
+    // Check if an external server is running on the last known port
+    let port = server::last_known_port();
 
     // Try multiple times with longer delay to handle server startup race condition
     for attempt in 0..10 {
-        // Use 0.0.0.0 to check all interfaces (server listens on 0.0.0.0)
-        let port_in_use = TcpListener::bind(format!("0.0.0.0:{port}")).is_err();
-
-        if port_in_use {
-            println!("[get_server_status] Port {} in use (attempt {})", port, attempt + 1);
-            return Ok(ServerStatus { running: true, port });
+        if port_in_use(port) {
+            tracing::debug!(target: "Server", "Port {port} in use (attempt {})", attempt + 1);
+            return Ok(vec![ServerStatus::external(port, true)]);
         }
 
         // Wait longer before retrying (up to 1 second)
@@ -112,176 +603,1204 @@ pub async fn get_server_status(state: State<'_, ServerState>) -> CommandResult<S
         }
     }
 
-    println!("[get_server_status] Port {} not in use after retries", port);
-    Ok(ServerStatus {
-        running: false,
-        port,
+    tracing::debug!(target: "Server", "Port {port} not in use after retries");
+    Ok(vec![ServerStatus::external(port, false)])
+}
+
+/// Statuses of the managed servers, sorted by port
+fn managed_server_statuses(servers: &HashMap<u16, server::ServerHandle>) -> Vec<ServerStatus> {
+    let now = std::time::Instant::now();
+    let mut statuses: Vec<ServerStatus> = servers
+        .values()
+        .map(|h| ServerStatus::managed(h.port, h.started_at(), now))
+        .collect();
+    statuses.sort_unstable_by_key(|status| status.port);
+    statuses
+}
+
+/// Whether something listens on `port` on any interface (the server binds 0.0.0.0)
+fn port_in_use(port: u16) -> bool {
+    std::net::TcpListener::bind(format!("0.0.0.0:{port}")).is_err()
+}
+
+/// Like [`get_server_status`], but probes the last known port only once
+///
+/// For snapshots, where waiting out a server that is still starting isn't worth it.
+async fn server_status_once(state: &ServerState) -> CommandResult<Vec<ServerStatus>> {
+    let servers = state.0.lock().await;
+    if !servers.is_empty() {
+        return Ok(managed_server_statuses(&servers));
+    }
+    drop(servers);
+
+    let port = server::last_known_port();
+    Ok(vec![ServerStatus::external(port, port_in_use(port))])
+}
+
+/// Gets the buffered server logs
+///
+/// Returns the output of the server started with [`start_server`] on `port`
+/// (or the lowest managed port), or of the server started at launch when
+/// none is managed.
+///
+/// # Errors
+///
+/// Returns an error if failed to read the server state
+#[tauri::command]
+pub async fn get_server_logs(state: State<'_, ServerState>, port: Option<u16>) -> CommandResult<Vec<String>> {
+    let server_state = state.0.lock().await;
+    Ok(match select_server(&server_state, port) {
+        Some(handle) => handle.logs().lines(),
+        None => crate::window::startup_server_logs().lines(),
     })
 }
 
-/// Gets the server logs
+/// Gets the last `n` buffered server log lines
 ///
 /// # Errors
 ///
-/// Returns an error if log reading fails (not yet implemented)
+/// Returns an error if failed to read the server state
 #[tauri::command]
-pub async fn get_server_logs() -> CommandResult<Vec<String>> {
-    Ok(vec!["Server logging not yet implemented".to_string()])
+pub async fn get_server_logs_tail(
+    state: State<'_, ServerState>,
+    n: usize,
+    port: Option<u16>,
+) -> CommandResult<Vec<String>> {
+    let server_state = state.0.lock().await;
+    Ok(match select_server(&server_state, port) {
+        Some(handle) => handle.logs().tail(n),
+        None => crate::window::startup_server_logs().tail(n),
+    })
 }
 
-/// Status information for the server
-#[derive(serde::Serialize)]
-pub struct ServerStatus {
-    /// Whether the server is currently running
-    pub running: bool,
-    /// The port the server is running on
-    pub port: u16,
+/// Sets the most verbose backend log level that is recorded
+///
+/// # Errors
+///
+/// Returns an error if `level` is not one of error, warn, info, debug, trace
+#[tauri::command]
+pub async fn set_log_level(level: String) -> CommandResult<String> {
+    let level = logging::parse_level(&level)?;
+    logging::set_level(level)?;
+    Ok(format!("Log level set to {level}"))
 }
 
-// Tunnel commands
+/// Gets recent backend log events, oldest first
+#[tauri::command]
+pub async fn get_recent_logs() -> CommandResult<Vec<logging::LogEntry>> {
+    Ok(logging::recent_logs())
+}
 
-/// Starts a local tunnel for remote access
+/// Writes a diagnostics bundle for bug reports to `path`
+///
+/// The JSON file holds the panic log, the server log, recent backend logs,
+/// version info and environment checks. Tunnel passwords, auth URLs and
+/// other secrets are redacted.
+///
+/// # Returns
+///
+/// Returns the path written
 ///
 /// # Errors
 ///
-/// Returns an error if the tunnel is already running or fails to start
+/// Returns an error if the file can't be written
 #[tauri::command]
-pub async fn start_tunnel(
-    state: State<'_, TunnelState>,
-    port: u16,
+pub async fn collect_diagnostics(
+    server_state: State<'_, ServerState>,
+    tunnel_state: State<'_, TunnelState>,
+    path: String,
 ) -> CommandResult<String> {
-    crate::common::validate_port(port)?;
+    let path = std::path::PathBuf::from(path);
+    if path.as_os_str().is_empty() || path.is_dir() {
+        return Err(format!("Invalid diagnostics path: {}", path.display()));
+    }
 
-    let mut tunnel_state = state.0.lock().await;
+    let tunnel_password = match tunnel_state.0.lock().await.as_ref() {
+        Some(handle) => tunnel::get_password(handle).await,
+        None => None,
+    };
+    let report = diagnostics::DiagnosticsReport::collect(
+        get_version_info().await,
+        validate_environment(server_state).await,
+        logging::recent_logs(),
+    );
+    diagnostics::write_report(&path, &report, &tunnel_password.into_iter().collect::<Vec<_>>())?;
+
+    tracing::info!(target: "Desktop", "Diagnostics written to {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
 
-    if tunnel_state.is_some() {
-        return Err("Tunnel is already running".to_string());
-    }
+/// Waits until the server on `port` answers the configured health path
+/// (`/health` by default), or `timeout_ms` passes
+///
+/// Ports below 1024 are rejected unless `allow_privileged_port` is set, as in
+/// [`start_server`].
+///
+/// # Errors
+///
+/// Returns an error if the port is invalid
+#[tauri::command]
+pub async fn wait_for_server_ready(
+    port: u16,
+    timeout_ms: u64,
+    allow_privileged_port: Option<bool>,
+) -> CommandResult<server::ServerReadiness> {
+    crate::common::validate_port_allowing_privileged(port, allow_privileged_port.unwrap_or(false))?;
+    let health_path = config::load().health_path;
+    Ok(server::wait_for_ready(port, &health_path, Duration::from_millis(timeout_ms)).await)
+}
+
+/// Gets the path of the file server output is persisted to
+///
+/// Older output is kept alongside it in `server.log.1`, `server.log.2`.
+///
+/// # Errors
+///
+/// Returns an error if the home directory cannot be determined
+#[tauri::command]
+pub async fn get_log_file_path() -> CommandResult<String> {
+    Ok(log_file::log_file_path()?.to_string_lossy().to_string())
+}
 
-    let handle = tunnel::start(port).map_err(|e| e)?;
+/// Opens the data directory (`~/.side-ide`) in the OS file manager
+///
+/// The directory is created first if it doesn't exist yet. Returns its path.
+///
+/// # Errors
+///
+/// Returns an error if the directory can't be created or opened
+#[tauri::command]
+pub async fn open_data_directory(app: AppHandle) -> CommandResult<String> {
+    let data_dir = crate::common::data_dir()?;
+    open_app_directory(&app, &data_dir, &data_dir)
+}
 
-    // Wait a bit for URL to be captured
-    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+/// Opens the server log directory (`~/.side-ide/logs`) in the OS file manager
+///
+/// The directory is created first if it doesn't exist yet. Returns its path.
+///
+/// # Errors
+///
+/// Returns an error if the directory can't be created or opened
+#[tauri::command]
+pub async fn open_server_logs_directory(app: AppHandle) -> CommandResult<String> {
+    open_app_directory(&app, &log_file::log_dir()?, &crate::common::data_dir()?)
+}
 
-    let url = tunnel::get_url(&handle).await;
+/// Takes the deep link the app was launched with, if it hasn't been taken yet
+///
+/// The frontend calls this once it has loaded; later links arrive as
+/// [`crate::window::DEEP_LINK_EVENT`].
+#[tauri::command]
+pub async fn take_launch_deep_links() -> CommandResult<Vec<crate::window::DeepLink>> {
+    Ok(crate::window::take_launch_deep_links())
+}
 
-    *tunnel_state = Some(handle);
+/// Creates `dir` inside `root` if needed and opens it with the system opener
+/// (Explorer, Finder or `xdg-open`)
+fn open_app_directory(app: &AppHandle, dir: &std::path::Path, root: &std::path::Path) -> CommandResult<String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let dir = crate::common::ensure_dir_within(dir, root)?;
+    let path = dir.to_string_lossy().to_string();
+    app.opener()
+        .open_path(path.clone(), None::<&str>)
+        .map_err(|e| format!("Failed to open {path}: {e}"))?;
+    Ok(path)
+}
 
-    // Return success even if URL is not yet available
-    // Frontend can poll get_tunnel_status for the URL
-    Ok(url.unwrap_or_else(|| "Tunnel starting...".to_string()))
+/// Gets the persisted server settings
+#[tauri::command]
+pub async fn get_server_settings() -> CommandResult<server::ServerSettings> {
+    Ok(server::load_settings())
 }
 
-/// Stops the local tunnel
+/// Sets (or clears with `None`) the custom server script path
 ///
 /// # Errors
 ///
-/// Returns an error if the tunnel is not running or fails to stop
+/// Returns an error if the path is not an existing `.js` file or the
+/// settings can't be saved
 #[tauri::command]
-pub async fn stop_tunnel(state: State<'_, TunnelState>) -> CommandResult<String> {
-    let mut tunnel_state = state.0.lock().await;
-
-    // Use if let instead of unwrap to avoid race condition
-    if let Some(handle) = tunnel_state.take() {
-        tunnel::stop(handle).await.map_err(|e| e)?;
-        Ok("Tunnel stopped".to_string())
-    } else {
-        Err("Tunnel is not running".to_string())
+pub async fn set_server_path(path: Option<String>) -> CommandResult<server::ServerSettings> {
+    let server_path = path.map(std::path::PathBuf::from);
+    if let Some(p) = &server_path {
+        server::validate_server_script(p)?;
     }
+
+    let settings = server::ServerSettings { server_path };
+    server::save_settings(&settings)?;
+    Ok(settings)
 }
 
-/// Gets the current tunnel status
+/// Resource usage of the managed server process
+#[derive(serde::Serialize)]
+pub struct ServerResourceUsage {
+    /// OS process id
+    pub pid: u32,
+    /// CPU usage in percent (may exceed 100 on multi-core systems)
+    pub cpu_percent: f32,
+    /// Resident memory in bytes
+    pub memory_bytes: u64,
+    /// Seconds since the process was spawned
+    pub uptime_secs: u64,
+}
+
+/// Gets CPU and memory usage of the managed server process on `port` (or
+/// the lowest managed port)
 ///
 /// # Errors
 ///
-/// Returns an error if failed to read the tunnel state
+/// Returns an error if no server is managed, the process has exited, or the
+/// process can't be found
 #[tauri::command]
-pub async fn get_tunnel_status(state: State<'_, TunnelState>) -> CommandResult<TunnelStatus> {
-    let tunnel_state = state.0.lock().await;
-    let running = tunnel_state.is_some();
-    let url = if let Some(handle) = tunnel_state.as_ref() {
-        tunnel::get_url(handle).await
-    } else {
-        None
+pub async fn get_server_resource_usage(
+    state: State<'_, ServerState>,
+    port: Option<u16>,
+) -> CommandResult<ServerResourceUsage> {
+    let process = {
+        let server_state = state.0.lock().await;
+        let handle = select_server(&server_state, port).ok_or_else(|| "Server is not running".to_string())?;
+        handle.process().ok_or_else(|| "Server process has exited".to_string())?
     };
-    // Password is always None for localtunnel (uses IP-based verification)
-    Ok(TunnelStatus { running, url, password: None })
+
+    let pid = sysinfo::Pid::from_u32(process.pid);
+    let mut system = sysinfo::System::new();
+
+    // CPU usage is measured between two refreshes
+    if !system.refresh_process(pid) {
+        return Err(format!("Server process {} not found", process.pid));
+    }
+    tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+    system.refresh_process(pid);
+
+    let proc_info = system
+        .process(pid)
+        .ok_or_else(|| format!("Server process {} not found", process.pid))?;
+
+    Ok(ServerResourceUsage {
+        pid: process.pid,
+        cpu_percent: proc_info.cpu_usage(),
+        memory_bytes: proc_info.memory(),
+        uptime_secs: server::uptime_secs(process.started_at, std::time::Instant::now()),
+    })
 }
 
-/// Status information for the tunnel
+/// Status information for the server
 #[derive(serde::Serialize)]
-pub struct TunnelStatus {
-    /// Whether the tunnel is currently running
+pub struct ServerStatus {
+    /// Whether the server is currently running
     pub running: bool,
-    /// The public URL of the tunnel (if available)
-    pub url: Option<String>,
-    /// The password for accessing the tunnel (if available)
-    pub password: Option<String>,
-}
+    /// The port the server is running on
+    pub port: u16,
+    /// Whether this app started the server (and so can stop it)
+    pub managed: bool,
+    /// Seconds since the server was started; `None` unless managed
+    pub uptime_secs: Option<u64>,
+}
+
+impl ServerStatus {
+    /// Status of a server we hold a handle for, started at `started_at`
+    pub fn managed(port: u16, started_at: std::time::Instant, now: std::time::Instant) -> Self {
+        Self {
+            running: true,
+            port,
+            managed: true,
+            uptime_secs: Some(server::uptime_secs(started_at, now)),
+        }
+    }
+
+    /// Status of a port we don't manage, running if something has bound it
+    pub fn external(port: u16, port_in_use: bool) -> Self {
+        Self {
+            running: port_in_use,
+            port,
+            managed: false,
+            uptime_secs: None,
+        }
+    }
+}
+
+// Tunnel commands
+
+/// Tunnel log lines included in a start timeout error
+const TUNNEL_ERROR_LOG_LINES: usize = 5;
+
+/// Starts a tunnel for remote access
+///
+/// Uses localtunnel unless another `provider` is requested; `subdomain`
+/// requests a stable localtunnel URL. Waits up to `url_timeout_secs`
+/// (default 15) for the public URL. The tunnel is
+/// respawned if it stops responding, emitting [`tunnel::TUNNEL_RECONNECTED_EVENT`].
+///
+/// # Errors
+///
+/// Returns an error if the tunnel is already running, fails to start or
+/// doesn't report a URL in time
+#[tauri::command]
+pub async fn start_tunnel(
+    app: AppHandle,
+    state: State<'_, TunnelState>,
+    port: u16,
+    provider: Option<tunnel::TunnelProvider>,
+    subdomain: Option<String>,
+    url_timeout_secs: Option<u64>,
+) -> Result<String, CommandError> {
+    crate::common::validate_port(port).map_err(CommandError::InvalidInput)?;
+    if let Some(subdomain) = &subdomain {
+        tunnel::validate_subdomain(subdomain).map_err(CommandError::InvalidInput)?;
+    }
+
+    let tunnel_state = state.0.lock().await;
+
+    if tunnel_state.is_some() {
+        return Err(CommandError::AlreadyRunning("Tunnel".to_string()));
+    }
+
+    let mut config = tunnel::TunnelConfig::new(port);
+    config.provider = provider.unwrap_or_default();
+    config.subdomain = subdomain;
+    if let Some(secs) = url_timeout_secs {
+        config.url_timeout = Duration::from_secs(secs);
+    }
+    let url_timeout = config.url_timeout;
+    let handle = tunnel::start(config, move |reconnected| {
+        let _ = app.emit(tunnel::TUNNEL_RECONNECTED_EVENT, reconnected);
+    })
+    .map_err(CommandError::SpawnFailed)?;
+    // Status and stop commands shouldn't wait on the URL
+    drop(tunnel_state);
+
+    match tunnel::wait_for_url(&handle).await {
+        Some(url) => {
+            let mut tunnel_state = state.0.lock().await;
+            if tunnel_state.is_some() {
+                // Another tunnel was started while this one was coming up
+                drop(tunnel_state);
+                if let Err(e) = tunnel::stop(handle).await {
+                    tracing::warn!(target: "Tunnel", "Failed to stop duplicate tunnel: {e}");
+                }
+                return Err(CommandError::AlreadyRunning("Tunnel".to_string()));
+            }
+            *tunnel_state = Some(handle);
+            Ok(url)
+        }
+        None => {
+            // Include the tunnel's own diagnostics, which are lost once it's stopped
+            let mut message = format!(
+                "Tunnel did not report a public URL within {} seconds",
+                url_timeout.as_secs()
+            );
+            let recent = tunnel::logs(&handle).tail(TUNNEL_ERROR_LOG_LINES);
+            if !recent.is_empty() {
+                message.push_str(":\n");
+                message.push_str(&recent.join("\n"));
+            }
+
+            // Killing the process closes its pipes, which ends the reader tasks
+            if let Err(e) = tunnel::stop(handle).await {
+                tracing::error!(target: "Tunnel", "Failed to stop tunnel after timeout: {e}");
+            }
+            Err(CommandError::Timeout(message))
+        }
+    }
+}
+
+/// Stops the local tunnel
+///
+/// # Errors
+///
+/// Returns an error if the tunnel is not running or fails to stop
+#[tauri::command]
+pub async fn stop_tunnel(state: State<'_, TunnelState>) -> Result<String, CommandError> {
+    let mut tunnel_state = state.0.lock().await;
+
+    // Use if let instead of unwrap to avoid race condition
+    if let Some(handle) = tunnel_state.take() {
+        tunnel::stop(handle).await.map_err(CommandError::StopFailed)?;
+        Ok("Tunnel stopped".to_string())
+    } else {
+        Err(CommandError::NotRunning("Tunnel".to_string()))
+    }
+}
+
+/// Gets the current tunnel status
+///
+/// # Errors
+///
+/// Returns an error if failed to read the tunnel state
+#[tauri::command]
+pub async fn get_tunnel_status(state: State<'_, TunnelState>) -> CommandResult<TunnelStatus> {
+    let tunnel_state = state.0.lock().await;
+    let running = tunnel_state.is_some();
+    let (url, password, requires_password, healthy, uptime_secs) = if let Some(handle) = tunnel_state.as_ref() {
+        (
+            tunnel::get_url(handle).await,
+            tunnel::get_password(handle).await,
+            tunnel::requires_password(handle),
+            tunnel::is_healthy(handle),
+            Some(server::uptime_secs(tunnel::started_at(handle), std::time::Instant::now())),
+        )
+    } else {
+        (None, None, false, false, None)
+    };
+    Ok(TunnelStatus { running, url, password, requires_password, healthy, uptime_secs })
+}
+
+/// Gets the buffered tunnel process output (stdout and stderr)
+///
+/// # Returns
+///
+/// Returns an empty list if the tunnel is not running
+///
+/// # Errors
+///
+/// Returns an error if failed to read the tunnel state
+#[tauri::command]
+pub async fn get_tunnel_logs(state: State<'_, TunnelState>) -> CommandResult<Vec<String>> {
+    let tunnel_state = state.0.lock().await;
+    Ok(tunnel_state
+        .as_ref()
+        .map(|handle| tunnel::logs(handle).lines())
+        .unwrap_or_default())
+}
+
+/// Gets the password visitors must enter on the localtunnel reminder page
+///
+/// # Returns
+///
+/// Returns None if the tunnel is not running or no password is known yet
+///
+/// # Errors
+///
+/// Returns an error if failed to read the tunnel state
+#[tauri::command]
+pub async fn get_tunnel_password(state: State<'_, TunnelState>) -> CommandResult<Option<String>> {
+    let tunnel_state = state.0.lock().await;
+    match tunnel_state.as_ref() {
+        Some(handle) => Ok(tunnel::get_password(handle).await),
+        None => Ok(None),
+    }
+}
+
+/// Status information for the tunnel
+#[derive(serde::Serialize)]
+pub struct TunnelStatus {
+    /// Whether the tunnel is currently running
+    pub running: bool,
+    /// The public URL of the tunnel (if available)
+    pub url: Option<String>,
+    /// The password for accessing the tunnel (if available)
+    pub password: Option<String>,
+    /// Whether visitors see localtunnel's reminder page and need the password
+    pub requires_password: bool,
+    /// Whether the last health check through the tunnel succeeded
+    pub healthy: bool,
+    /// Seconds since the tunnel was started; `None` when not running
+    pub uptime_secs: Option<u64>,
+}
 
 // Tailscale commands (Remote Access)
 
 /// Gets the current Tailscale status summary for Remote Access.
 ///
-/// This shells out to `tailscale status --json` when the CLI is available.
+/// This shells out to `tailscale status --json` when the CLI is available.
+#[tauri::command]
+pub async fn get_tailscale_status() -> CommandResult<tailscale::TailscaleStatusSummary> {
+    Ok(tailscale::get_status_summary().await)
+}
+
+/// Searches for the `tailscale` CLI again, e.g. after installing it mid-session.
+///
+/// Returns the resolved path, or `None` if it is still not installed.
+#[tauri::command]
+pub async fn refresh_tailscale_path() -> CommandResult<Option<String>> {
+    Ok(tailscale::refresh_tailscale_command())
+}
+
+/// Starts a Tailscale login (`tailscale up`).
+///
+/// The returned status carries the `auth_url` to open in the browser, or the
+/// current status when this device is already logged in.
+#[tauri::command]
+pub async fn tailscale_login() -> CommandResult<tailscale::TailscaleStatusSummary> {
+    tailscale::login().await
+}
+
+/// Logs this device out of Tailscale (`tailscale logout`).
+#[tauri::command]
+pub async fn tailscale_logout() -> CommandResult<tailscale::TailscaleStatusSummary> {
+    tailscale::logout().await
+}
+
+/// Gets Remote Access status (Tailscale + Serve + Desktop settings).
+#[tauri::command]
+pub async fn get_remote_access_status() -> CommandResult<remote_access::RemoteAccessStatus> {
+    Ok(remote_access::get_status().await)
+}
+
+/// Gets the shareable Remote Access HTTPS URL, or None if Serve is off.
+#[tauri::command]
+pub async fn get_remote_access_url() -> CommandResult<Option<String>> {
+    Ok(remote_access::get_status().await.share_url())
+}
+
+/// Gets Desktop Remote Access settings.
+#[tauri::command]
+pub async fn get_remote_access_settings() -> CommandResult<remote_access::RemoteAccessSettings> {
+    Ok(remote_access::load_settings().await)
+}
+
+/// Sets Desktop Remote Access settings.
+#[tauri::command]
+pub async fn set_remote_access_settings(auto_start: bool) -> CommandResult<String> {
+    let settings = remote_access::RemoteAccessSettings { auto_start };
+    remote_access::save_settings(&settings).await?;
+    Ok("Remote Access settings saved".to_string())
+}
+
+/// Checks whether Remote Access can be enabled.
+///
+/// Reports Tailscale installation, login, MagicDNS and HTTPS certificates,
+/// with a remediation for each failing item.
+#[tauri::command]
+pub async fn check_remote_access_prerequisites() -> CommandResult<remote_access::RemoteAccessReadiness> {
+    Ok(remote_access::check_prerequisites().await)
+}
+
+/// Start HTTPS Remote Access via `tailscale serve`.
+///
+/// Serves on `serve_port` when given (otherwise 443, falling back to 8443),
+/// under `mount_path` (e.g. `/ide`) when given. Refuses with a clear error
+/// when [`check_remote_access_prerequisites`] reports a problem.
+#[tauri::command]
+pub async fn start_remote_access_https(
+    port: u16,
+    serve_port: Option<u16>,
+    mount_path: Option<String>,
+) -> CommandResult<String> {
+    crate::common::validate_port(port)?;
+    remote_access::start_https(port, serve_port, mount_path).await?;
+    Ok(format!("Remote Access enabled (HTTPS -> localhost:{port})"))
+}
+
+/// Stop Remote Access (`tailscale serve reset`).
+#[tauri::command]
+pub async fn stop_remote_access() -> CommandResult<String> {
+    remote_access::stop().await?;
+    Ok("Remote Access disabled".to_string())
+}
+
+/// Starts polling the serve status in the background.
+///
+/// Emits `remote-access-status-changed` whenever `(serve_enabled, serve_url)`
+/// changes, so the UI doesn't have to poll.
+#[tauri::command]
+pub async fn start_remote_access_watcher(
+    app: AppHandle,
+    state: State<'_, RemoteAccessWatcherState>,
+) -> CommandResult<String> {
+    let mut watcher = state.0.lock().await;
+    if watcher.is_some() {
+        return Ok("Remote Access watcher already running".to_string());
+    }
+
+    let token = tokio_util::sync::CancellationToken::new();
+    *watcher = Some(token.clone());
+
+    tauri::async_runtime::spawn(async move {
+        remote_access::watch_serve_status(token, remote_access::WATCH_INTERVAL, |status| {
+            let _ = app.emit(remote_access::STATUS_CHANGED_EVENT, status);
+        })
+        .await;
+    });
+
+    Ok("Remote Access watcher started".to_string())
+}
+
+/// Stops the Remote Access status watcher.
+#[tauri::command]
+pub async fn stop_remote_access_watcher(
+    state: State<'_, RemoteAccessWatcherState>,
+) -> CommandResult<String> {
+    match state.0.lock().await.take() {
+        Some(token) => {
+            token.cancel();
+            Ok("Remote Access watcher stopped".to_string())
+        }
+        None => Err("Remote Access watcher is not running".to_string()),
+    }
+}
+
+// Config commands
+
+/// Gets the value of config `key`
+///
+/// # Errors
+///
+/// Returns an error if `key` is unknown
+#[tauri::command]
+pub async fn config_get(key: String) -> CommandResult<serde_json::Value> {
+    config::load().get(&key)
+}
+
+/// Validates and stores config `key`, returning the stored value
+///
+/// # Errors
+///
+/// Returns an error if `key` is unknown, `value` is invalid or the config
+/// can't be saved
+#[tauri::command]
+pub async fn config_set(key: String, value: serde_json::Value) -> CommandResult<serde_json::Value> {
+    let mut app_config = config::load();
+    app_config.set(&key, value)?;
+    config::save(&app_config)?;
+    app_config.get(&key)
+}
+
+/// Gets every config value
+#[tauri::command]
+pub async fn config_list() -> CommandResult<config::AppConfig> {
+    Ok(config::load())
+}
+
+// Aggregate status
+
+/// What [`SystemStatus`] reports about the environment
+///
+/// Just enough to tell whether the server can start; [`check_environment`]
+/// has the details.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct EnvironmentSummary {
+    /// Output of `node --version`, if Node.js is installed
+    pub node_version: Option<String>,
+    /// Whether that Node.js is new enough to run the server
+    pub node_meets_minimum: bool,
+    /// The configured package manager
+    pub package_manager: server::PackageManager,
+    /// Whether the configured package manager is installed
+    pub package_manager_found: bool,
+}
+
+/// Checks Node.js and the configured package manager
+async fn environment_summary() -> CommandResult<EnvironmentSummary> {
+    let package_manager = config::load().package_manager;
+    let node = check_command_version("node", &["--version"]).await;
+    let package_manager_found = tokio::task::spawn_blocking(move || package_manager.find_command().is_ok())
+        .await
+        .map_err(|e| format!("Failed to look up {}: {e}", package_manager.executable_name()))?;
+
+    Ok(EnvironmentSummary {
+        node_meets_minimum: node_meets_minimum(node.version.as_deref()),
+        node_version: node.version,
+        package_manager,
+        package_manager_found,
+    })
+}
+
+/// Snapshot of server, tunnel, Remote Access and environment status
+///
+/// A section is None if gathering it failed; `errors` says why.
+#[derive(serde::Serialize)]
+pub struct SystemStatus {
+    /// Same as [`get_server_status`], from a single port probe
+    pub server: Option<Vec<ServerStatus>>,
+    /// Same as [`get_tunnel_status`]
+    pub tunnel: Option<TunnelStatus>,
+    /// Same as [`get_remote_access_status`]
+    pub remote_access: Option<remote_access::RemoteAccessStatus>,
+    /// Node.js and package manager summary
+    pub environment: Option<EnvironmentSummary>,
+    /// Failures as `section: message`
+    pub errors: Vec<String>,
+}
+
+impl SystemStatus {
+    /// Combines the section results, keeping the successful ones
+    pub fn from_results(
+        server: CommandResult<Vec<ServerStatus>>,
+        tunnel: CommandResult<TunnelStatus>,
+        remote_access: CommandResult<remote_access::RemoteAccessStatus>,
+        environment: CommandResult<EnvironmentSummary>,
+    ) -> Self {
+        let mut errors = Vec::new();
+        let mut section = |name: &str, error: String| errors.push(format!("{name}: {error}"));
+
+        let server = server.map_err(|e| section("server", e)).ok();
+        let tunnel = tunnel.map_err(|e| section("tunnel", e)).ok();
+        let remote_access = remote_access.map_err(|e| section("remote_access", e)).ok();
+        let environment = environment.map_err(|e| section("environment", e)).ok();
+
+        SystemStatus {
+            server,
+            tunnel,
+            remote_access,
+            environment,
+            errors,
+        }
+    }
+}
+
+/// Gathers server, tunnel, Remote Access and environment status concurrently
+///
+/// Saves the frontend four round-trips on dashboard load. A failing section
+/// is left empty rather than failing the whole call. Nothing is retried, so
+/// an idle system answers quickly.
+#[tauri::command]
+pub async fn get_system_status(
+    server_state: State<'_, ServerState>,
+    tunnel_state: State<'_, TunnelState>,
+) -> CommandResult<SystemStatus> {
+    let (server, tunnel, remote_access, environment) = tokio::join!(
+        server_status_once(&server_state),
+        get_tunnel_status(tunnel_state),
+        get_remote_access_status(),
+        environment_summary(),
+    );
+    Ok(SystemStatus::from_results(server, tunnel, remote_access, environment))
+}
+
+// Shutdown
+
+/// Time each subsystem gets to stop in [`shutdown_all`]
+///
+/// Longer than [`server::SHUTDOWN_GRACE_PERIOD`] so a server that ignores
+/// the polite signal still gets killed before we give up on it.
+pub const SHUTDOWN_STEP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of stopping one subsystem
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ShutdownStep {
+    pub ok: bool,
+    pub message: String,
+}
+
+impl ShutdownStep {
+    /// Runs `step`, counting it as failed if it takes longer than `timeout`
+    pub async fn run<F>(step: F, timeout: Duration) -> Self
+    where
+        F: Future<Output = CommandResult<String>>,
+    {
+        match tokio::time::timeout(timeout, step).await {
+            Ok(result) => result.into(),
+            Err(_) => Err(format!("Timed out after {timeout:?}")).into(),
+        }
+    }
+}
+
+impl From<CommandResult<String>> for ShutdownStep {
+    fn from(result: CommandResult<String>) -> Self {
+        match result {
+            Ok(message) => ShutdownStep { ok: true, message },
+            Err(message) => ShutdownStep { ok: false, message },
+        }
+    }
+}
+
+/// Collects step outcomes by subsystem, logging the ones that failed
+pub fn shutdown_report(
+    steps: impl IntoIterator<Item = (&'static str, ShutdownStep)>,
+) -> BTreeMap<String, ShutdownStep> {
+    steps
+        .into_iter()
+        .inspect(|(name, step)| {
+            if !step.ok {
+                tracing::error!(target: "Desktop", "Failed to stop {name} on shutdown: {}", step.message);
+            }
+        })
+        .map(|(name, step)| (name.to_string(), step))
+        .collect()
+}
+
+async fn shutdown_servers(app: &AppHandle) -> CommandResult<String> {
+    let mut stopped = 0;
+    let mut errors = Vec::new();
+
+    match crate::window::stop_startup_server().await {
+        Ok(true) => stopped += 1,
+        Ok(false) => {}
+        Err(e) => errors.push(e),
+    }
+    if let Some(state) = app.try_state::<ServerState>() {
+        let handles: Vec<_> = state.0.lock().await.drain().collect();
+        for (_, handle) in handles {
+            match server::stop(handle).await {
+                Ok(()) => stopped += 1,
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(format!("Stopped {stopped} server(s)"))
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+async fn shutdown_tunnel(app: &AppHandle) -> CommandResult<String> {
+    let handle = match app.try_state::<TunnelState>() {
+        Some(state) => state.0.lock().await.take(),
+        None => None,
+    };
+    match handle {
+        Some(handle) => tunnel::stop(handle).await.map(|()| "Tunnel stopped".to_string()),
+        None => Ok("Tunnel not running".to_string()),
+    }
+}
+
+async fn shutdown_remote_access() -> CommandResult<String> {
+    // Leave serve mappings the user set up outside the app alone
+    if !remote_access::enabled_by_app() {
+        return Ok("Remote Access not enabled by the app".to_string());
+    }
+    remote_access::stop().await?;
+    Ok("Remote Access disabled".to_string())
+}
+
+/// Stops servers, the tunnel and Remote Access concurrently
+///
+/// Shared by [`shutdown_all`] and the window close handler. Failures are
+/// logged and reported, never propagated, so one stuck subsystem can't keep
+/// the others running.
+pub async fn shutdown_everything(app: &AppHandle) -> BTreeMap<String, ShutdownStep> {
+    let (server, tunnel, remote_access) = tokio::join!(
+        ShutdownStep::run(shutdown_servers(app), SHUTDOWN_STEP_TIMEOUT),
+        ShutdownStep::run(shutdown_tunnel(app), SHUTDOWN_STEP_TIMEOUT),
+        ShutdownStep::run(shutdown_remote_access(), SHUTDOWN_STEP_TIMEOUT),
+    );
+    shutdown_report([("server", server), ("tunnel", tunnel), ("remote_access", remote_access)])
+}
+
+/// Stops everything the app started, e.g. before quitting
+///
+/// Returns the outcome per subsystem (`server`, `tunnel`, `remote_access`).
+#[tauri::command]
+pub async fn shutdown_all(app: AppHandle) -> CommandResult<BTreeMap<String, ShutdownStep>> {
+    Ok(shutdown_everything(&app).await)
+}
+
+// Environment check commands
+
+/// Searches for node and npm again, e.g. after installing Node.js mid-session.
+#[tauri::command]
+pub async fn refresh_command_paths() -> CommandResult<String> {
+    crate::common::refresh_command_paths();
+    Ok("Command paths refreshed".to_string())
+}
+
+/// How long a `--version` probe may take before the tool is reported unavailable
+pub const COMMAND_VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Oldest Node.js major version the server runs on
+pub const MIN_NODE_MAJOR: u32 = 18;
+
+/// Parses the major version from `node --version` output (e.g. `v20.1.0`)
+pub fn parse_node_major(version: &str) -> Option<u32> {
+    let version = version.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let (major, rest) = version.split_once('.')?;
+    // Require at least major.minor so stray numbers aren't mistaken for versions
+    rest.split('.').next()?.parse::<u32>().ok()?;
+    major.parse().ok()
+}
+
+/// Whether a `node --version` string meets [`MIN_NODE_MAJOR`]
+///
+/// Missing or unparsable versions don't meet the minimum.
+pub fn node_meets_minimum(version: Option<&str>) -> bool {
+    version
+        .and_then(parse_node_major)
+        .is_some_and(|major| major >= MIN_NODE_MAJOR)
+}
+
+/// Checks the environment for required tools (Node.js, npm, pnpm, bun, deno)
+///
+/// Also reports whether the installed Node.js meets [`MIN_NODE_MAJOR`].
+/// The version probes run concurrently, so the check takes as long as the
+/// slowest tool rather than the sum of all of them.
+///
+/// # Errors
+///
+/// Returns an error if environment check fails
+#[tauri::command]
+pub async fn check_environment() -> CommandResult<EnvironmentInfo> {
+    let (node_info, npm_info, pnpm_info, bun_info, deno_info) = tokio::join!(
+        check_command_version("node", &["--version"]),
+        check_command_version("npm", &["--version"]),
+        check_command_version("pnpm", &["--version"]),
+        check_command_version("bun", &["--version"]),
+        check_command_version("deno", &["--version"]),
+    );
+
+    Ok(EnvironmentInfo {
+        node_meets_minimum: node_meets_minimum(node_info.version.as_deref()),
+        minimum_node_version: format!("v{MIN_NODE_MAJOR}.0.0"),
+        node: node_info,
+        npm: npm_info,
+        pnpm: pnpm_info,
+        bun: bun_info,
+        deno: deno_info,
+    })
+}
+
+/// Gets the app, Tauri, server and Node.js versions in one call
+///
+/// Meant for diagnostics and bug reports; fields that can't be determined are None.
+#[tauri::command]
+pub async fn get_version_info() -> CommandResult<VersionInfo> {
+    let node = check_command_version("node", &["--version"]).await;
+    Ok(VersionInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        server_version: server::server_version(),
+        node_version: node.version,
+    })
+}
+
+/// Checks for a newer version of the app
+///
+/// # Returns
+///
+/// Returns None if the running version is the latest
+///
+/// # Errors
+///
+/// Returns an error if the update check fails
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> CommandResult<Option<updater::UpdateInfo>> {
+    updater::check_for_updates(&app).await
+}
+
+/// Downloads and installs the available update
+///
+/// Progress is reported through `update-progress` events; the download can
+/// be aborted with [`cancel_update`].
+///
+/// # Errors
+///
+/// Returns an error if an update is already downloading, no update is
+/// available, the download is cancelled or the install fails
+#[tauri::command]
+pub async fn download_and_install(app: AppHandle, state: State<'_, UpdateState>) -> CommandResult<()> {
+    let token = {
+        let mut current = state.0.lock().await;
+        if current.as_ref().is_some_and(|token| !token.is_cancelled()) {
+            return Err("An update is already downloading".to_string());
+        }
+        let token = tokio_util::sync::CancellationToken::new();
+        *current = Some(token.clone());
+        token
+    };
+
+    let result = updater::download_and_install(&app, token.clone()).await;
+
+    // A cancelled token was already taken by `cancel_update`
+    if !token.is_cancelled() {
+        *state.0.lock().await = None;
+    }
+    result
+}
+
+/// Cancels the update download in progress
+///
+/// # Errors
+///
+/// Returns an error if no update is downloading
+#[tauri::command]
+pub async fn cancel_update(state: State<'_, UpdateState>) -> CommandResult<String> {
+    match state.0.lock().await.take() {
+        Some(token) => {
+            token.cancel();
+            Ok("Update cancelled".to_string())
+        }
+        None => Err("No update is downloading".to_string()),
+    }
+}
+
+/// Gets the persisted updater settings
+#[tauri::command]
+pub async fn get_updater_settings() -> CommandResult<updater::UpdaterSettings> {
+    Ok(updater::load_settings())
+}
+
+/// Sets whether and how often to check for updates in the background
+///
+/// # Errors
+///
+/// Returns an error if the check interval is out of range or the settings
+/// can't be saved
+#[tauri::command]
+pub async fn set_updater_settings(
+    settings: updater::UpdaterSettings,
+) -> CommandResult<updater::UpdaterSettings> {
+    settings.validate()?;
+    updater::save_settings(&settings)?;
+    Ok(settings)
+}
+
+/// Skips `version` so background checks stop announcing it
+///
+/// # Errors
+///
+/// Returns an error if the version is empty or the settings can't be saved
 #[tauri::command]
-pub async fn get_tailscale_status() -> CommandResult<tailscale::TailscaleStatusSummary> {
-    Ok(tailscale::get_status_summary().await)
+pub async fn dismiss_update(version: String) -> CommandResult<updater::UpdaterSettings> {
+    let version = version.trim();
+    if version.is_empty() {
+        return Err("Version must not be empty".to_string());
+    }
+
+    let mut settings = updater::load_settings();
+    settings.dismiss(version);
+    updater::save_settings(&settings)?;
+    Ok(settings)
 }
 
-/// Gets Remote Access status (Tailscale + Serve + Desktop settings).
+/// Sets the release channel updates are taken from
+///
+/// # Errors
+///
+/// Returns an error if the channel is not `stable` or `beta`, or the
+/// settings can't be saved
 #[tauri::command]
-pub async fn get_remote_access_status() -> CommandResult<remote_access::RemoteAccessStatus> {
-    Ok(remote_access::get_status().await)
+pub async fn set_update_channel(channel: String) -> CommandResult<updater::UpdaterSettings> {
+    let channel: updater::UpdateChannel = channel.parse()?;
+    let settings = updater::UpdaterSettings {
+        channel,
+        ..updater::load_settings()
+    };
+    updater::save_settings(&settings)?;
+    Ok(settings)
 }
 
-/// Gets Desktop Remote Access settings.
-#[tauri::command]
-pub async fn get_remote_access_settings() -> CommandResult<remote_access::RemoteAccessSettings> {
-    Ok(remote_access::load_settings().await)
+/// How serious an [`EnvironmentIssue`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// The server can't run until this is fixed
+    Error,
+    /// The server may run, but something is likely to go wrong
+    Warning,
+    /// Worth knowing; no action required
+    Info,
 }
 
-/// Sets Desktop Remote Access settings.
-#[tauri::command]
-pub async fn set_remote_access_settings(auto_start: bool) -> CommandResult<String> {
-    let settings = remote_access::RemoteAccessSettings { auto_start };
-    remote_access::save_settings(&settings).await?;
-    Ok("Remote Access settings saved".to_string())
+/// A problem found by [`validate_environment`], with a suggested fix
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct EnvironmentIssue {
+    /// How serious the issue is
+    pub severity: IssueSeverity,
+    /// What is wrong
+    pub message: String,
+    /// What the user can do about it
+    pub fix_hint: String,
 }
 
-/// Start HTTPS Remote Access via `tailscale serve`.
-#[tauri::command]
-pub async fn start_remote_access_https(port: u16) -> CommandResult<String> {
-    crate::common::validate_port(port)?;
-    remote_access::start_https(port).await?;
-    Ok(format!("Remote Access enabled (HTTPS -> localhost:{port})"))
+impl EnvironmentIssue {
+    fn new(severity: IssueSeverity, message: String, fix_hint: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message,
+            fix_hint: fix_hint.into(),
+        }
+    }
 }
 
-/// Stop Remote Access (`tailscale serve reset`).
-#[tauri::command]
-pub async fn stop_remote_access() -> CommandResult<String> {
-    remote_access::stop().await?;
-    Ok("Remote Access disabled".to_string())
+/// Results of the individual checks behind [`validate_environment`]
+pub struct EnvironmentChecks {
+    /// Tool availability from [`check_environment`]
+    pub environment: EnvironmentInfo,
+    /// The port the server will use
+    pub port: u16,
+    /// Whether `port` is free (or used by a server we manage)
+    pub port_available: bool,
+    /// Expected location of the bundled server, if it is missing
+    pub missing_server_bundle: Option<std::path::PathBuf>,
 }
 
-// Environment check commands
+/// Turns check results into a list of issues; empty when everything is fine
+pub fn environment_issues(checks: &EnvironmentChecks) -> Vec<EnvironmentIssue> {
+    let mut issues = Vec::new();
+    let env = &checks.environment;
+
+    if !env.node.available {
+        issues.push(EnvironmentIssue::new(
+            IssueSeverity::Error,
+            "Node.js not found".to_string(),
+            format!(
+                "Install Node.js {} or newer from https://nodejs.org/",
+                env.minimum_node_version
+            ),
+        ));
+    } else if !env.node_meets_minimum {
+        issues.push(EnvironmentIssue::new(
+            IssueSeverity::Error,
+            format!(
+                "Node.js {} is too old",
+                env.node.version.as_deref().unwrap_or("(unknown version)")
+            ),
+            format!(
+                "Upgrade Node.js to {} or newer from https://nodejs.org/",
+                env.minimum_node_version
+            ),
+        ));
+    }
+
+    if !env.npm.available {
+        issues.push(EnvironmentIssue::new(
+            IssueSeverity::Warning,
+            "npm not found".to_string(),
+            "Reinstall Node.js from https://nodejs.org/, which includes npm",
+        ));
+    }
 
-/// Checks the environment for required tools (Node.js, npm, bun)
+    if !checks.port_available {
+        issues.push(EnvironmentIssue::new(
+            IssueSeverity::Warning,
+            format!("Port {} is in use", checks.port),
+            "Choose another port, or stop the program using it",
+        ));
+    }
+
+    if let Some(path) = &checks.missing_server_bundle {
+        issues.push(EnvironmentIssue::new(
+            IssueSeverity::Info,
+            format!("Bundled server not found at {}", path.display()),
+            "It will be downloaded on first start; reinstall the app to use an offline copy",
+        ));
+    }
+
+    issues
+}
+
+/// Checks tools, the server port and the server bundle in one call
+///
+/// Returns an empty list when the environment is ready.
 ///
 /// # Errors
 ///
-/// Returns an error if environment check fails
+/// Returns an error if the server state cannot be read
 #[tauri::command]
-pub async fn check_environment() -> CommandResult<EnvironmentInfo> {
-    let node_info = check_command_version("node", &["--version"]).await;
-    let npm_info = check_command_version("npm", &["--version"]).await;
-    let bun_info = check_command_version("bun", &["--version"]).await;
+pub async fn validate_environment(
+    state: State<'_, ServerState>,
+) -> CommandResult<Vec<EnvironmentIssue>> {
+    let environment = check_environment().await?;
+    let port = config::load().default_port;
+    let port_available = state.0.lock().await.contains_key(&port)
+        || std::net::TcpListener::bind(format!("0.0.0.0:{port}")).is_ok();
+    let missing_server_bundle = if server::is_development_mode() {
+        None
+    } else {
+        server::get_server_path().ok().filter(|path| !path.exists())
+    };
 
-    Ok(EnvironmentInfo {
-        node: node_info,
-        npm: npm_info,
-        bun: bun_info,
-    })
+    Ok(environment_issues(&EnvironmentChecks {
+        environment,
+        port,
+        port_available,
+        missing_server_bundle,
+    }))
 }
 
 /// Checks if a specific port is available
@@ -311,8 +1830,29 @@ pub struct EnvironmentInfo {
     pub node: CommandInfo,
     /// npm availability and version
     pub npm: CommandInfo,
+    /// pnpm availability and version
+    pub pnpm: CommandInfo,
     /// bun availability and version
     pub bun: CommandInfo,
+    /// deno availability and version
+    pub deno: CommandInfo,
+    /// Whether the installed Node.js is new enough to run the server
+    pub node_meets_minimum: bool,
+    /// The oldest supported Node.js version (e.g. `v18.0.0`)
+    pub minimum_node_version: String,
+}
+
+/// Versions of the app and the components it runs
+#[derive(serde::Serialize)]
+pub struct VersionInfo {
+    /// The desktop app version
+    pub app_version: String,
+    /// The Tauri runtime version
+    pub tauri_version: String,
+    /// The server version from its `package.json`, if readable
+    pub server_version: Option<String>,
+    /// The output of `node --version`, if Node.js is installed
+    pub node_version: Option<String>,
 }
 
 /// Information about a command-line tool
@@ -337,13 +1877,30 @@ pub struct PortStatus {
 
 /// Checks the version of a command-line tool
 async fn check_command_version(command: &str, args: &[&str]) -> CommandInfo {
-    match tokio::process::Command::new(command)
+    check_command_version_with_timeout(command, args, COMMAND_VERSION_TIMEOUT).await
+}
+
+/// Runs `command args` and reports it unavailable if it doesn't finish within `timeout`
+///
+/// The child is killed on timeout so a hung tool doesn't leak a process.
+pub async fn check_command_version_with_timeout(
+    command: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> CommandInfo {
+    let output = tokio::process::Command::new(command)
         .args(args)
-        .output()
-        .await
-    {
-        Ok(output) => {
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    match tokio::time::timeout(timeout, output).await {
+        Ok(Ok(output)) => {
+            // Some tools (e.g. deno) print extra lines after the version
             let version = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .unwrap_or_default()
                 .trim()
                 .to_string();
             CommandInfo {
@@ -351,7 +1908,7 @@ async fn check_command_version(command: &str, args: &[&str]) -> CommandInfo {
                 version: if version.is_empty() { None } else { Some(version) },
             }
         }
-        Err(_) => CommandInfo {
+        Ok(Err(_)) | Err(_) => CommandInfo {
             available: false,
             version: None,
         },
@@ -368,13 +1925,59 @@ pub struct DetectedServer {
     pub port: u16,
     pub status: String,
     pub type_: String,
+    /// S-IDE version reported by the identity endpoint
+    pub version: Option<String>,
+    /// Loopback family the server answered on
+    pub address_family: crate::scanner::AddressFamily,
+}
+
+/// Endpoint an S-IDE server answers with its identity
+pub const IDENTITY_ENDPOINT: &str = "/api/identity";
+
+/// `app` value reported by S-IDE servers on the identity endpoint
+const SIDE_IDE_APP_ID: &str = "s-ide";
+
+/// Server type assigned to servers that identify as S-IDE
+const SIDE_IDE_SERVER_TYPE: &str = "side-ide";
+
+/// Body returned by the identity endpoint
+#[derive(Debug, serde::Deserialize)]
+pub struct ServerIdentity {
+    pub app: String,
+    pub version: Option<String>,
+}
+
+/// Parse an identity response, returning it only if it identifies S-IDE
+pub fn parse_identity_response(body: &str) -> Option<ServerIdentity> {
+    serde_json::from_str::<ServerIdentity>(body)
+        .ok()
+        .filter(|identity| identity.app == SIDE_IDE_APP_ID)
+}
+
+/// Most server probes [`scan_local_servers`] runs at once
+const MAX_CONCURRENT_SERVER_PROBES: usize = 16;
+
+/// Runs `probe` on every item, at most `limit` at a time, keeping the `Some` results
+///
+/// Results come back in completion order, not input order.
+pub async fn probe_bounded<T, R, F, Fut>(items: Vec<T>, limit: usize, probe: F) -> Vec<R>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = Option<R>>,
+{
+    use futures_util::StreamExt;
+
+    futures_util::stream::iter(items)
+        .map(probe)
+        .buffer_unordered(limit.max(1))
+        .filter_map(std::future::ready)
+        .collect()
+        .await
 }
 
 /// Scan localhost for running servers
 #[tauri::command]
 pub async fn scan_local_servers() -> CommandResult<Vec<DetectedServer>> {
-    let mut servers = Vec::new();
-
     // Common development ports to scan
     let ports_to_scan = vec![
         (3000, "dev"),
@@ -387,98 +1990,258 @@ pub async fn scan_local_servers() -> CommandResult<Vec<DetectedServer>> {
         (9000, "dev"),
     ];
 
-    // Scan ports in parallel
-    let mut scan_tasks = Vec::new();
-    for (port, default_type) in ports_to_scan {
-        scan_tasks.push(tokio::spawn(probe_server(port, default_type)));
+    // Scan ports in parallel, bounded so a longer port list stays cheap
+    let servers = probe_bounded(ports_to_scan, MAX_CONCURRENT_SERVER_PROBES, |(port, default_type)| {
+        probe_server(port, default_type)
+    })
+    .await;
+
+    Ok(servers)
+}
+
+/// Probe a single port on both loopbacks to detect a server
+///
+/// A dual-stack listener answers on both and is reported once, over IPv4.
+async fn probe_server(port: u16, default_type: &str) -> Option<DetectedServer> {
+    use crate::scanner::AddressFamily;
+
+    let connects = |host: &'static str| async move {
+        matches!(
+            tokio::time::timeout(
+                Duration::from_millis(200),
+                tokio::net::TcpStream::connect(format!("{}:{}", host, port)),
+            )
+            .await,
+            Ok(Ok(_))
+        )
+    };
+    let (host, family) = match tokio::join!(connects("127.0.0.1"), connects("[::1]")) {
+        (true, true) => ("127.0.0.1", AddressFamily::Dual),
+        (true, false) => ("127.0.0.1", AddressFamily::Ipv4),
+        (false, true) => ("[::1]", AddressFamily::Ipv6),
+        (false, false) => return None,
+    };
+
+    // Port is open, try to get server info
+    fetch_server_info(host, port, default_type, family).await
+}
+
+/// How long each request made while identifying a server may take
+const SERVER_INFO_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// GET request for server detection, on the shared client
+fn server_info_request(url: &str) -> reqwest::RequestBuilder {
+    crate::common::http_client().get(url).timeout(SERVER_INFO_TIMEOUT)
+}
+
+/// Fetch detailed server information via HTTP
+///
+/// `host` is a URL host, so IPv6 literals come bracketed.
+async fn fetch_server_info(
+    host: &str,
+    port: u16,
+    default_type: &str,
+    address_family: crate::scanner::AddressFamily,
+) -> Option<DetectedServer> {
+    let base_url = format!("http://{}:{}", host, port);
+
+    // An S-IDE server identifies itself regardless of the port it runs on
+    if let Some(identity) = fetch_server_identity(&base_url).await {
+        let page = inspect_root_page(&base_url).await;
+        return Some(DetectedServer {
+            name: page.title.unwrap_or_else(|| SIDE_IDE_SERVER_TYPE.to_string()),
+            url: base_url,
+            port,
+            status: "running".to_string(),
+            type_: SIDE_IDE_SERVER_TYPE.to_string(),
+            version: identity.version,
+            address_family,
+        });
     }
 
-    // Collect results
-    for task in scan_tasks {
-        if let Ok(Some(server)) = task.await {
-            servers.push(server);
-        }
+    // Try /health endpoint first, then fall back to the root endpoint
+    let health_ok = matches!(
+        server_info_request(&format!("{}/health", base_url)).send().await,
+        Ok(resp) if resp.status().is_success()
+    );
+    let responding = health_ok
+        || matches!(server_info_request(&base_url).send().await, Ok(resp) if resp.status().is_success());
+    if !responding {
+        return None;
     }
 
-    Ok(servers)
+    let page = inspect_root_page(&base_url).await;
+    Some(DetectedServer {
+        name: page.title.unwrap_or_else(|| default_type.to_string()),
+        url: base_url,
+        port,
+        status: "running".to_string(),
+        type_: page.framework.unwrap_or(default_type).to_string(),
+        version: None,
+        address_family,
+    })
 }
 
-/// Probe a single port to detect a server
-async fn probe_server(port: u16, default_type: &str) -> Option<DetectedServer> {
-    use std::time::Duration;
+/// Query the identity endpoint; `None` if it's missing or not an S-IDE body
+async fn fetch_server_identity(base_url: &str) -> Option<ServerIdentity> {
+    let resp = server_info_request(&format!("{}{}", base_url, IDENTITY_ENDPOINT)).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    let body = resp.text().await.ok()?;
+    parse_identity_response(&body)
+}
 
-    // Try to connect with timeout
-    match tokio::time::timeout(
-        Duration::from_millis(200),
-        tokio::net::TcpStream::connect(&format!("127.0.0.1:{}", port))
-    ).await {
-        Ok(_) => {
-            // Port is open, try to get server info
-            fetch_server_info(port, default_type).await
-        }
-        Err(_) => None,
+/// What a server's root page reveals about it
+#[derive(Default)]
+struct PageInfo {
+    /// HTML `<title>`
+    title: Option<String>,
+    /// Framework from [`classify_framework`]
+    framework: Option<&'static str>,
+}
+
+/// Fetch the root page and read its title and framework
+async fn inspect_root_page(base_url: &str) -> PageInfo {
+    let Ok(resp) = server_info_request(base_url).send().await else {
+        return PageInfo::default();
+    };
+    let header = |name: &str| {
+        resp.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let powered_by = header("x-powered-by");
+    let server = header("server");
+    let html = resp.text().await.unwrap_or_default();
+
+    PageInfo {
+        title: extract_title_from_html(&html),
+        framework: classify_framework(powered_by.as_deref(), server.as_deref(), &html),
     }
 }
 
-/// Fetch detailed server information via HTTP
-async fn fetch_server_info(port: u16, default_type: &str) -> Option<DetectedServer> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(500))
-        .build()
-        .ok()?;
-
-    let base_url = format!("http://127.0.0.1:{}", port);
-
-    // Try /health endpoint first
-    if let Ok(resp) = client.get(&format!("{}/health", base_url)).send().await {
-        if resp.status().is_success() {
-            return Some(DetectedServer {
-                name: detect_server_name(&base_url, &client).await.unwrap_or_else(|| default_type.to_string()),
-                url: base_url,
-                port,
-                status: "running".to_string(),
-                type_: default_type.to_string(),
-            });
-        }
-    }
-
-    // Fallback: try root endpoint
-    if let Ok(resp) = client.get(&base_url).send().await {
-        if resp.status().is_success() {
-            return Some(DetectedServer {
-                name: detect_server_name(&base_url, &client).await.unwrap_or_else(|| default_type.to_string()),
-                url: base_url,
-                port,
-                status: "running".to_string(),
-                type_: default_type.to_string(),
-            });
-        }
-    }
-
-    None
-}
-
-/// Detect server name from HTML or response
-async fn detect_server_name(base_url: &str, client: &reqwest::Client) -> Option<String> {
-    // Try to get server name from HTML title
-    if let Ok(resp) = client.get(base_url).send().await {
-        if let Ok(html) = resp.text().await {
-            if let Some(title) = extract_title_from_html(&html) {
-                return Some(title);
+/// Asset paths that only a given framework's pages reference
+const FRAMEWORK_PAGE_MARKERS: &[(&str, &str)] = &[
+    ("/_next/", "nextjs"),
+    ("/_nuxt/", "nuxt"),
+    ("/@vite/client", "vite"),
+];
+
+/// `X-Powered-By` / `Server` header substrings (lowercase) and their framework
+const FRAMEWORK_HEADER_MARKERS: &[(&str, &str)] = &[
+    ("next.js", "nextjs"),
+    ("nuxt", "nuxt"),
+    ("express", "express"),
+    ("werkzeug", "flask"),
+];
+
+/// Classify the web framework behind a response, e.g. `nextjs`, `vite` or `express`
+///
+/// Page markers win over headers because dev servers are often mounted
+/// inside another server (Vite as Express middleware still references
+/// `/@vite/client`). Returns None when nothing matches, leaving the caller's
+/// port-based guess in place.
+pub fn classify_framework(powered_by: Option<&str>, server: Option<&str>, html: &str) -> Option<&'static str> {
+    if let Some(&(_, framework)) = FRAMEWORK_PAGE_MARKERS.iter().find(|(marker, _)| html.contains(marker)) {
+        return Some(framework);
+    }
+
+    [powered_by, server].into_iter().flatten().find_map(|value| {
+        let value = value.to_ascii_lowercase();
+        FRAMEWORK_HEADER_MARKERS
+            .iter()
+            .find(|(marker, _)| value.contains(marker))
+            .map(|&(_, framework)| framework)
+    })
+}
+
+/// Extract title from HTML
+///
+/// Tolerates attributes on the tag and any letter case, decodes common HTML
+/// entities and collapses whitespace. Returns None if there is no title or
+/// it is blank.
+pub fn extract_title_from_html(html: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets valid for `html`
+    let lower = html.to_ascii_lowercase();
+
+    let mut search_from = 0;
+    let tag_end = loop {
+        let after_name = search_from + lower[search_from..].find("<title")? + "<title".len();
+        // Skip tags that merely start with "title", e.g. <titlebar>
+        match lower.as_bytes().get(after_name) {
+            Some(b'>' | b' ' | b'\t' | b'\r' | b'\n') => break after_name,
+            _ => search_from = after_name,
+        }
+    };
+    let start = tag_end + lower[tag_end..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+
+    let title = decode_html_entities(&html[start..end])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Longest entity name (between `&` and `;`) worth trying to decode
+const MAX_ENTITY_LEN: usize = 8;
+
+/// Decode named (`&amp;`, `&nbsp;`, ...) and numeric (`&#39;`, `&#x27;`) entities
+///
+/// Anything unrecognised is left as written.
+fn decode_html_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest
+            .find(';')
+            .filter(|&semi| semi <= MAX_ENTITY_LEN + 1)
+            .and_then(|semi| decode_entity(&rest[1..semi]).map(|c| (c, semi)));
+        match entity {
+            Some((c, semi)) => {
+                decoded.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
             }
         }
     }
-    None
+
+    decoded.push_str(rest);
+    decoded
 }
 
-/// Extract title from HTML
-fn extract_title_from_html(html: &str) -> Option<String> {
-    html.split("<title>")
-        .nth(1)
-        .and_then(|s| s.split("</title>").next())
-        .map(|s| s.trim().to_string())
+/// Character for an entity name without the `&` and `;`
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
 }
 
+/// How long the MCP status request may take
+const MCP_STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Get MCP servers from a specific server
 #[tauri::command]
 pub async fn get_mcp_servers(server_url: String) -> CommandResult<Vec<MCPStatus>> {
@@ -501,15 +2264,11 @@ pub async fn get_mcp_servers(server_url: String) -> CommandResult<Vec<MCPStatus>
         return Err("Only http/https schemes are allowed".to_string());
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(2000))
-        .build()
-        .map_err(|e| format!("Failed to create client: {}", e))?;
-
     let mcp_url = format!("{}/api/mcp-status", server_url.trim_end_matches('/'));
 
-    let response = client
+    let response = crate::common::http_client()
         .get(&mcp_url)
+        .timeout(MCP_STATUS_TIMEOUT)
         .send()
         .await
         .map_err(|e| format!("Failed to fetch MCP servers: {}", e))?;
@@ -534,28 +2293,445 @@ pub struct MCPStatus {
     pub capabilities: Vec<String>,
 }
 
+/// Hard limit on a connectivity test, redirects included
+pub const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most redirects a connectivity test follows
+pub const CONNECTIVITY_MAX_REDIRECTS: usize = 2;
+
+/// Client for [`test_connectivity`], which needs its own redirect limit
+static CONNECTIVITY_CLIENT: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+
+fn connectivity_client() -> &'static reqwest::Client {
+    CONNECTIVITY_CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(CONNECTIVITY_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::limited(CONNECTIVITY_MAX_REDIRECTS))
+            // A fresh connection each time, so latency includes the handshake
+            .pool_max_idle_per_host(0)
+            .user_agent(concat!("S-IDE-Desktop/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// Outcome of [`test_connectivity`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ConnectivityResult {
+    /// Whether the URL answered with a 2xx status
+    pub reachable: bool,
+    /// HTTP status of the final response, if one arrived
+    pub status_code: Option<u16>,
+    /// Time until the response headers arrived (or the request failed)
+    pub latency_ms: u64,
+    /// Why the URL isn't reachable; `None` when it is
+    pub error: Option<String>,
+}
+
+/// Checks that `url` is an http(s) URL with a host
+///
+/// Unlike [`get_mcp_servers`], any host is allowed, since the point is
+/// reaching a remote tunnel or tailnet URL.
+///
+/// # Errors
+///
+/// Returns an error if the URL can't be parsed, has no host, or uses
+/// another scheme
+pub fn validate_connectivity_url(url: &str) -> Result<url::Url, String> {
+    let parsed: url::Url = url.trim().parse().map_err(|e| format!("Invalid URL format: {e}"))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err("Only http/https schemes are allowed".to_string());
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err("URL must include a host".to_string());
+    }
+    Ok(parsed)
+}
+
+/// Times a GET to `url` and reports how it went
+pub async fn check_connectivity(url: url::Url) -> ConnectivityResult {
+    let started = std::time::Instant::now();
+    let response = connectivity_client().get(url).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match response {
+        Ok(resp) if resp.status().is_success() => ConnectivityResult {
+            reachable: true,
+            status_code: Some(resp.status().as_u16()),
+            latency_ms,
+            error: None,
+        },
+        Ok(resp) => ConnectivityResult {
+            reachable: false,
+            status_code: Some(resp.status().as_u16()),
+            latency_ms,
+            error: Some(format!("Server responded with HTTP {}", resp.status())),
+        },
+        Err(e) => ConnectivityResult {
+            reachable: false,
+            status_code: e.status().map(|status| status.as_u16()),
+            latency_ms,
+            error: Some(describe_request_error(&e)),
+        },
+    }
+}
+
+/// User-facing reason a request failed, naming the underlying cause
+fn describe_request_error(error: &reqwest::Error) -> String {
+    if error.is_timeout() {
+        return format!("Timed out after {}s", CONNECTIVITY_TIMEOUT.as_secs());
+    }
+    if error.is_redirect() {
+        return format!("Too many redirects (more than {CONNECTIVITY_MAX_REDIRECTS})");
+    }
+
+    // reqwest's own message just says the request failed; the cause is deeper
+    let mut cause: &dyn std::error::Error = error;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    if error.is_connect() {
+        format!("Connection failed: {cause}")
+    } else {
+        format!("Request failed: {cause}")
+    }
+}
+
+/// Tests whether this device can reach `url`, e.g. a tunnel or Tailscale URL
+///
+/// Does a single GET, following at most [`CONNECTIVITY_MAX_REDIRECTS`]
+/// redirects, and gives up after [`CONNECTIVITY_TIMEOUT`]. Only a 2xx
+/// response counts as reachable.
+///
+/// # Errors
+///
+/// Returns an error if the URL is invalid or not http/https
+#[tauri::command]
+pub async fn test_connectivity(url: String) -> CommandResult<ConnectivityResult> {
+    let url = validate_connectivity_url(&url)?;
+    Ok(check_connectivity(url).await)
+}
+
 // Advanced scanning commands with nmap-style capabilities
 
 /// Advanced scan with OS detection and version detection
 /// Uses pistol-rs for port scanning with optional nmap subprocess fallback
+///
+/// `port_spec` accepts nmap-style expressions (e.g. `"8000-8100,9000"`) and
+/// takes precedence over the explicit `ports` list.
+///
+/// Emits [`crate::scanner::SCAN_PROGRESS_EVENT`] after each batch of probes.
+/// When `scan_id` is given the scan can be aborted with [`cancel_scan`].
+///
+/// Results are cached for `cache_ttl_secs` (default 30s); a repeat scan with
+/// the same ports and flags within that window returns the cached results
+/// with `cached: true`. Pass `0` to bypass the cache.
+///
+/// nmap is stopped after `nmap_timeout_secs` (default 120s).
+///
+/// `timeout_ms` (10-10000, default 200) is how long each port gets to accept
+/// a connection. Raise it for slow or distant hosts, whose open ports would
+/// otherwise be reported closed, at the cost of a slower scan. `parallelism`
+/// (1-1000, default 100) is how many ports are probed at once; higher is
+/// faster but opens more sockets at a time and is more likely to trip
+/// rate limiting or an IDS. Neither applies to nmap scans.
+///
+/// The results are wrapped in a [`crate::scanner::ScanSummary`] with the
+/// scan's duration and port counts.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn scan_local_servers_advanced(
+    app: AppHandle,
+    scan_state: State<'_, ScanState>,
+    cache_state: State<'_, ScanCacheState>,
+    scan_id: Option<String>,
     ports: Option<Vec<u16>>,
+    port_spec: Option<String>,
     os_detection: bool,
     version_detection: bool,
     use_nmap: bool,
-) -> CommandResult<Vec<crate::scanner::ScanResult>> {
+    cache_ttl_secs: Option<u64>,
+    nmap_timeout_secs: Option<u64>,
+    include_ipv6: Option<bool>,
+    timeout_ms: Option<u64>,
+    parallelism: Option<usize>,
+) -> CommandResult<crate::scanner::ScanSummary> {
+    let started = std::time::Instant::now();
+    let tuned = crate::scanner::ScanOptions::default().with_tuning(timeout_ms, parallelism)?;
+    let ports = match port_spec {
+        Some(spec) => Some(crate::scanner::parse_port_spec(&spec)?),
+        None => ports,
+    };
+    let ports_scanned = crate::scanner::scanned_port_count(ports.as_deref());
+    let summarize = |results| crate::scanner::ScanSummary::new(results, ports_scanned, started.elapsed());
+
+    let use_nmap = use_nmap && crate::scanner::is_nmap_available();
+    // nmap is only pointed at the IPv4 loopback
+    let include_ipv6 = include_ipv6.unwrap_or(false) && !use_nmap;
+    let cache_ttl = cache_ttl_secs
+        .map(Duration::from_secs)
+        .unwrap_or(crate::scanner::DEFAULT_SCAN_CACHE_TTL);
+    let cache_host = if include_ipv6 { "127.0.0.1,::1" } else { "127.0.0.1" };
+    let cache_key = crate::scanner::ScanCacheKey::new(
+        cache_host,
+        ports.as_deref(),
+        os_detection,
+        version_detection,
+        use_nmap,
+    )
+    .with_timeout(tuned.timeout);
+    if let Some(cached) = cache_state.0.lock().await.get(&cache_key, cache_ttl) {
+        return Ok(summarize(cached));
+    }
+
     // Use nmap if requested and available
-    if use_nmap && crate::scanner::is_nmap_available() {
-        return crate::scanner::scan_with_nmap("127.0.0.1", ports, os_detection, version_detection).await;
+    if use_nmap {
+        let results = crate::scanner::scan_with_nmap(
+            "127.0.0.1",
+            ports,
+            os_detection,
+            version_detection,
+            &[],
+            nmap_timeout(nmap_timeout_secs),
+        )
+        .await?;
+        cache_state.0.lock().await.insert(cache_key, &results);
+        return Ok(summarize(results));
     }
 
+    // Register a cancellation token so the scan can be aborted by id
+    let cancel_token = match &scan_id {
+        Some(id) => {
+            let mut scans = scan_state.0.lock().await;
+            if scans.contains_key(id) {
+                return Err(format!("Scan {id} is already running"));
+            }
+            let token = tokio_util::sync::CancellationToken::new();
+            scans.insert(id.clone(), token.clone());
+            Some(token)
+        }
+        None => None,
+    };
+
     // Use pistol-rs based scanner
-    crate::scanner::scan_localhost(ports, os_detection, version_detection).await
+    let options = crate::scanner::ScanOptions {
+        ports,
+        os_detection,
+        version_detection,
+        cancel_token,
+        ..tuned
+    };
+    let result = crate::scanner::scan_localhost(
+        options,
+        include_ipv6,
+        |progress| {
+            let _ = app.emit(crate::scanner::SCAN_PROGRESS_EVENT, progress);
+        },
+    )
+    .await;
+
+    let cancelled = match &scan_id {
+        Some(id) => scan_state
+            .0
+            .lock()
+            .await
+            .remove(id)
+            .is_some_and(|token| token.is_cancelled()),
+        None => false,
+    };
+
+    // Partial results from a cancelled scan are not worth caching
+    if let Ok(results) = &result {
+        if !cancelled {
+            cache_state.0.lock().await.insert(cache_key, results);
+        }
+    }
+
+    result.map(summarize)
+}
+
+/// nmap time budget requested by the frontend, or the default
+fn nmap_timeout(secs: Option<u64>) -> Duration {
+    secs.map(Duration::from_secs).unwrap_or(crate::scanner::DEFAULT_NMAP_TIMEOUT)
+}
+
+/// Local address to scan from, as requested by the frontend
+///
+/// The address must belong to this machine; otherwise every probe would
+/// fail to bind and the scan would come back silently wrong.
+fn parse_bind_addr(bind_addr: Option<String>) -> CommandResult<Option<std::net::IpAddr>> {
+    bind_addr
+        .map(|addr| {
+            let ip = addr
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid bind address: {addr}"))?;
+            crate::scanner::validate_bind_addr(ip)?;
+            Ok(ip)
+        })
+        .transpose()
+}
+
+/// Clears all cached scan results
+#[tauri::command]
+pub async fn clear_scan_cache(state: State<'_, ScanCacheState>) -> CommandResult<String> {
+    state.0.lock().await.clear();
+    Ok("Scan cache cleared".to_string())
+}
+
+/// Cancels an in-progress advanced scan
+///
+/// The scan stops before its next batch and returns a partial result.
+///
+/// # Errors
+///
+/// Returns an error if no scan with the given id is running
+#[tauri::command]
+pub async fn cancel_scan(state: State<'_, ScanState>, scan_id: String) -> CommandResult<String> {
+    let scans = state.0.lock().await;
+
+    if let Some(token) = scans.get(&scan_id) {
+        token.cancel();
+        Ok(format!("Scan {scan_id} cancelled"))
+    } else {
+        Err(format!("No active scan with id {scan_id}"))
+    }
+}
+
+/// Advanced scan of an arbitrary host (IP address or hostname)
+///
+/// The host is resolved before scanning and the resolved IP is reported in
+/// `ScanResult.host` so the UI can show what was actually probed.
+/// `rate_limit` caps probes per second; nmap scans ignore it and are
+/// stopped after `nmap_timeout_secs` (default 120s) instead.
+/// `extra_nmap_flags` (e.g. `--script=http-title`) are checked against an
+/// allowlist and rejected up front, even if nmap isn't used.
+/// `bind_addr` sends probes from that local address (not used by nmap).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn scan_host_advanced(
+    host: String,
+    ports: Option<Vec<u16>>,
+    os_detection: bool,
+    version_detection: bool,
+    use_nmap: bool,
+    rate_limit: Option<u32>,
+    nmap_timeout_secs: Option<u64>,
+    extra_nmap_flags: Option<Vec<String>>,
+    bind_addr: Option<String>,
+) -> CommandResult<Vec<crate::scanner::ScanResult>> {
+    let extra_nmap_flags = extra_nmap_flags.unwrap_or_default();
+    crate::scanner::validate_nmap_flags(&extra_nmap_flags)?;
+    let bind_addr = parse_bind_addr(bind_addr)?;
+    let ip = crate::scanner::resolve_host(&host).await?;
+
+    if use_nmap && crate::scanner::is_nmap_available() {
+        let timeout = nmap_timeout(nmap_timeout_secs);
+        return crate::scanner::scan_with_nmap(&ip, ports, os_detection, version_detection, &extra_nmap_flags, timeout)
+            .await;
+    }
+
+    let options = crate::scanner::ScanOptions {
+        ports,
+        os_detection,
+        version_detection,
+        rate_limit,
+        bind_addr,
+        ..Default::default()
+    };
+    crate::scanner::scan_host(&ip, &options).await
+}
+
+/// Scan a host, streaming open ports as events instead of returning them
+///
+/// Emits [`crate::scanner::SCAN_PORT_FOUND_EVENT`] for each open port as soon
+/// as it is found, then [`crate::scanner::SCAN_COMPLETE_EVENT`] with the
+/// totals, which are also returned. Both payloads carry the host.
+#[tauri::command]
+pub async fn scan_host_streaming(
+    app: AppHandle,
+    host: String,
+    ports: Option<Vec<u16>>,
+    port_spec: Option<String>,
+    rate_limit: Option<u32>,
+    bind_addr: Option<String>,
+) -> CommandResult<crate::scanner::ScanComplete> {
+    let ports = match port_spec {
+        Some(spec) => Some(crate::scanner::parse_port_spec(&spec)?),
+        None => ports,
+    };
+    let ip = crate::scanner::resolve_host(&host).await?;
+    let options = crate::scanner::ScanOptions {
+        ports,
+        rate_limit,
+        bind_addr: parse_bind_addr(bind_addr)?,
+        ..Default::default()
+    };
+
+    let complete = crate::scanner::scan_host_streaming(&ip, &options, |found| {
+        let _ = app.emit(crate::scanner::SCAN_PORT_FOUND_EVENT, found);
+    })
+    .await?;
+    let _ = app.emit(crate::scanner::SCAN_COMPLETE_EVENT, complete.clone());
+    Ok(complete)
+}
+
+/// Scan every host in a CIDR subnet (e.g. `192.168.1.0/24`)
+///
+/// Returns one result per responsive host. Subnets larger than a /16 are rejected.
+/// `rate_limit` caps probes per second across the whole subnet, and
+/// `max_open_sockets` (default 500) caps probes in flight at once.
+/// `bind_addr` sends probes from that local address.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn scan_subnet(
+    cidr: String,
+    ports: Option<Vec<u16>>,
+    os_detection: bool,
+    version_detection: bool,
+    ping_first: bool,
+    rate_limit: Option<u32>,
+    max_open_sockets: Option<usize>,
+    bind_addr: Option<String>,
+) -> CommandResult<Vec<crate::scanner::ScanResult>> {
+    let options = crate::scanner::ScanOptions {
+        ports,
+        os_detection,
+        version_detection,
+        ping_first,
+        rate_limit,
+        max_open_sockets: max_open_sockets.unwrap_or(crate::scanner::DEFAULT_MAX_OPEN_SOCKETS),
+        bind_addr: parse_bind_addr(bind_addr)?,
+        ..Default::default()
+    };
+    crate::scanner::scan_subnet(cidr, options).await
+}
+
+/// Check if nmap is available on the system, with its version and an
+/// install hint for this platform
+///
+/// The result is cached; see [`refresh_nmap_status`].
+#[tauri::command]
+pub async fn check_nmap_available() -> CommandResult<crate::scanner::NmapStatus> {
+    Ok(crate::scanner::nmap_status())
+}
+
+/// Looks for nmap again, e.g. after the user installed it mid-session
+#[tauri::command]
+pub async fn refresh_nmap_status() -> CommandResult<crate::scanner::NmapStatus> {
+    Ok(crate::scanner::refresh_nmap_status())
 }
 
-/// Check if nmap is available on the system
+/// Lists running Docker containers with their published ports
+///
+/// # Errors
+///
+/// Returns [`CommandError::NotAvailable`] if Docker isn't installed or its
+/// daemon isn't running
 #[tauri::command]
-pub async fn check_nmap_available() -> CommandResult<bool> {
-    Ok(crate::scanner::is_nmap_available())
+pub async fn list_docker_containers() -> Result<Vec<docker::DockerContainer>, CommandError> {
+    docker::list_containers().await.map_err(|e| match e {
+        docker::DockerError::NotAvailable(_) => CommandError::NotAvailable(e.to_string()),
+        docker::DockerError::Failed(msg) => CommandError::SpawnFailed(msg),
+    })
 }
@@ -5,6 +5,8 @@
 
 use crate::commands::*;
 use crate::common;
+use crate::server;
+use crate::test_support::HttpResponder;
 
 #[cfg(test)]
 mod tests {
@@ -36,12 +38,40 @@ mod tests {
         assert!(common::validate_port(1023).is_err());
     }
 
+    #[test]
+    fn test_validate_port_allowing_privileged() {
+        // Strict branch matches validate_port
+        assert!(common::validate_port_allowing_privileged(443, false).is_err());
+        assert!(common::validate_port_allowing_privileged(8787, false).is_ok());
+
+        // Permissive branch accepts privileged ports
+        assert!(common::validate_port_allowing_privileged(80, true).is_ok());
+        assert!(common::validate_port_allowing_privileged(443, true).is_ok());
+        assert!(common::validate_port_allowing_privileged(8787, true).is_ok());
+
+        // Port 0 is rejected either way
+        assert!(common::validate_port_allowing_privileged(0, false).is_err());
+        assert_eq!(
+            common::validate_port_allowing_privileged(0, true),
+            Err("Port 0 is not valid".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_server_ready_allows_privileged_port_on_request() {
+        assert!(wait_for_server_ready(80, 0, None).await.is_err());
+        assert!(wait_for_server_ready(0, 0, Some(true)).await.is_err());
+        assert!(wait_for_server_ready(80, 0, Some(true)).await.is_ok());
+    }
+
     #[test]
     fn test_server_status_structure() {
         // Test that ServerStatus can be created and serialized
         let status = ServerStatus {
             running: true,
             port: 8787,
+            managed: true,
+            uptime_secs: Some(42),
         };
 
         assert_eq!(status.running, true);
@@ -51,6 +81,36 @@ mod tests {
         let json = serde_json::to_string(&status).unwrap();
         assert!(json.contains("\"running\":true"));
         assert!(json.contains("\"port\":8787"));
+        assert!(json.contains("\"managed\":true"));
+        assert!(json.contains("\"uptime_secs\":42"));
+    }
+
+    #[test]
+    fn test_server_status_managed_vs_external() {
+        let started_at = std::time::Instant::now();
+        let managed = ServerStatus::managed(8787, started_at, started_at);
+        assert!(managed.running && managed.managed);
+
+        let external = ServerStatus::external(8787, true);
+        assert!(external.running);
+        assert!(!external.managed);
+
+        let stopped = ServerStatus::external(8787, false);
+        assert!(!stopped.running);
+        assert!(!stopped.managed);
+        assert_eq!(stopped.uptime_secs, None);
+    }
+
+    #[test]
+    fn test_server_status_uptime_from_fixed_start() {
+        use std::time::{Duration, Instant};
+
+        let started_at = Instant::now();
+        let status = ServerStatus::managed(8787, started_at, started_at + Duration::from_secs(2 * 3600 + 5));
+        assert_eq!(status.uptime_secs, Some(7205));
+
+        let status = ServerStatus::managed(8787, started_at, started_at + Duration::from_millis(999));
+        assert_eq!(status.uptime_secs, Some(0));
     }
 
     #[test]
@@ -60,12 +120,18 @@ mod tests {
             running: true,
             url: Some("https://example.com".to_string()),
             password: None,
+            requires_password: false,
+            healthy: true,
+            uptime_secs: Some(60),
         };
 
         let status_without_url = TunnelStatus {
             running: false,
             url: None,
             password: None,
+            requires_password: false,
+            healthy: false,
+            uptime_secs: None,
         };
 
         assert_eq!(status_with_url.running, true);
@@ -74,6 +140,43 @@ mod tests {
         assert!(status_without_url.url.is_none());
     }
 
+    #[test]
+    fn test_tunnel_status_serializes_password() {
+        let status = TunnelStatus {
+            running: true,
+            url: Some("https://abc123.loca.lt".to_string()),
+            password: Some("203.0.113.7".to_string()),
+            requires_password: true,
+            healthy: true,
+            uptime_secs: Some(7200),
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "running": true,
+                "url": "https://abc123.loca.lt",
+                "password": "203.0.113.7",
+                "requires_password": true,
+                "healthy": true,
+                "uptime_secs": 7200
+            })
+        );
+
+        let stopped = TunnelStatus {
+            running: false,
+            url: None,
+            password: None,
+            requires_password: false,
+            healthy: false,
+            uptime_secs: None,
+        };
+        let json = serde_json::to_value(&stopped).unwrap();
+        assert!(json["password"].is_null());
+        assert!(json["uptime_secs"].is_null());
+    }
+
     #[test]
     fn test_environment_info_structure() {
         // Test that EnvironmentInfo can hold command info
@@ -95,7 +198,11 @@ mod tests {
         let env_info = EnvironmentInfo {
             node: node_info.clone(),
             npm: npm_info.clone(),
+            pnpm: npm_info.clone(),
             bun: bun_info.clone(),
+            deno: npm_info.clone(),
+            node_meets_minimum: true,
+            minimum_node_version: "v18.0.0".to_string(),
         };
 
         assert_eq!(env_info.node.available, true);
@@ -110,6 +217,81 @@ mod tests {
         assert!(json.contains("\"available\":true"));
     }
 
+    #[test]
+    fn test_environment_info_serializes_all_tools() {
+        let info = |version: Option<&str>| CommandInfo {
+            available: version.is_some(),
+            version: version.map(str::to_string),
+        };
+
+        let env_info = EnvironmentInfo {
+            node: info(Some("v20.11.0")),
+            npm: info(Some("10.2.4")),
+            pnpm: info(None),
+            bun: info(Some("1.1.0")),
+            deno: info(Some("deno 1.42.0 (release, x86_64-unknown-linux-gnu)")),
+            node_meets_minimum: true,
+            minimum_node_version: "v18.0.0".to_string(),
+        };
+
+        let json = serde_json::to_value(&env_info).unwrap();
+        assert_eq!(json["node"]["version"], "v20.11.0");
+        assert_eq!(json["npm"]["version"], "10.2.4");
+        assert_eq!(json["pnpm"], serde_json::json!({ "available": false, "version": null }));
+        assert_eq!(json["bun"]["version"], "1.1.0");
+        assert_eq!(json["deno"]["available"], true);
+    }
+
+    #[tokio::test]
+    async fn test_check_environment_populates_all_tools() {
+        // Tools may or may not be installed here; every entry must still be filled in
+        let env_info = check_environment().await.unwrap();
+
+        for tool in [&env_info.node, &env_info.npm, &env_info.pnpm, &env_info.bun, &env_info.deno] {
+            if !tool.available {
+                assert!(tool.version.is_none());
+            }
+        }
+        assert_eq!(
+            env_info.node_meets_minimum,
+            node_meets_minimum(env_info.node.version.as_deref())
+        );
+        assert_eq!(env_info.minimum_node_version, format!("v{MIN_NODE_MAJOR}.0.0"));
+
+        let json = serde_json::to_value(&env_info).unwrap();
+        for key in ["node", "npm", "pnpm", "bun", "deno"] {
+            assert!(json[key].get("available").is_some(), "missing {key}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_command_version_times_out() {
+        use std::time::{Duration, Instant};
+
+        let started = Instant::now();
+        let info = check_command_version_with_timeout("sleep", &["5"], Duration::from_millis(200)).await;
+
+        assert!(!info.available);
+        assert!(info.version.is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_node_minimum_version() {
+        assert_eq!(parse_node_major("v20.1.0"), Some(20));
+        assert!(node_meets_minimum(Some("v20.1.0")));
+        assert!(node_meets_minimum(Some(&format!("v{MIN_NODE_MAJOR}.0.0"))));
+
+        assert_eq!(parse_node_major("v16.0.0"), Some(16));
+        assert!(!node_meets_minimum(Some("v16.0.0")));
+
+        assert_eq!(parse_node_major("not a version"), None);
+        assert!(!node_meets_minimum(Some("not a version")));
+        assert!(!node_meets_minimum(Some("v")));
+        assert!(!node_meets_minimum(None));
+    }
+
     #[test]
     fn test_port_status_structure() {
         // Test PortStatus structure
@@ -143,6 +325,8 @@ mod tests {
             port: 3000,
             status: "running".to_string(),
             type_: "dev".to_string(),
+            version: None,
+            address_family: crate::scanner::AddressFamily::Ipv4,
         };
 
         assert_eq!(server.name, "Test Server");
@@ -152,6 +336,27 @@ mod tests {
         assert_eq!(server.type_, "dev");
     }
 
+    #[test]
+    fn test_parse_identity_response_well_formed() {
+        let identity = parse_identity_response(r#"{ "app": "s-ide", "version": "2.1.7" }"#)
+            .expect("S-IDE identity should parse");
+
+        assert_eq!(identity.app, "s-ide");
+        assert_eq!(identity.version.as_deref(), Some("2.1.7"));
+        assert_eq!(IDENTITY_ENDPOINT, "/api/identity");
+    }
+
+    #[test]
+    fn test_parse_identity_response_malformed() {
+        // Not JSON (e.g. an HTML page from another dev server)
+        assert!(parse_identity_response("<html><title>Vite</title></html>").is_none());
+        // JSON from a different app
+        assert!(parse_identity_response(r#"{ "app": "other", "version": "1.0.0" }"#).is_none());
+        // JSON without an app field
+        assert!(parse_identity_response(r#"{ "version": "1.0.0" }"#).is_none());
+        assert!(parse_identity_response("").is_none());
+    }
+
     #[test]
     fn test_mcp_status_structure() {
         // Test MCPStatus structure
@@ -214,4 +419,564 @@ mod tests {
         assert!(validate_mcp_url("ftp://localhost:8787").is_err());
         assert!(validate_mcp_url("not-a-url").is_err());
     }
+
+    #[test]
+    fn test_command_error_serializes_kind_and_message() {
+        let cases = [
+            (CommandError::PortInUse(8787), "PortInUse", "Port 8787 is already in use"),
+            (
+                CommandError::PortRaced(8787),
+                "PortRaced",
+                "Port 8787 was taken by another process while the server was starting",
+            ),
+            (CommandError::NotRunning("Server".to_string()), "NotRunning", "Server is not running"),
+            (CommandError::AlreadyRunning("Tunnel".to_string()), "AlreadyRunning", "Tunnel is already running"),
+            (CommandError::PermissionDenied("denied".to_string()), "PermissionDenied", "denied"),
+            (CommandError::SpawnFailed("npx not found".to_string()), "SpawnFailed", "npx not found"),
+            (CommandError::StopFailed("kill failed".to_string()), "StopFailed", "kill failed"),
+            (CommandError::InvalidInput("Port 0 is not valid".to_string()), "InvalidInput", "Port 0 is not valid"),
+            (CommandError::Timeout("timed out".to_string()), "Timeout", "timed out"),
+            (CommandError::NotAvailable("Docker not available".to_string()), "NotAvailable", "Docker not available"),
+        ];
+
+        for (error, kind, message) in cases {
+            let json = serde_json::to_value(&error).unwrap();
+            assert_eq!(json, serde_json::json!({ "kind": kind, "message": message }));
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_command_error_from_bind_error() {
+        use std::io::{Error, ErrorKind};
+
+        assert_eq!(
+            CommandError::from_bind_error(8787, &Error::from(ErrorKind::AddrInUse)),
+            CommandError::PortInUse(8787)
+        );
+        assert_eq!(
+            CommandError::from_bind_error(80, &Error::from(ErrorKind::PermissionDenied)).kind(),
+            "PermissionDenied"
+        );
+        assert_eq!(
+            CommandError::from_bind_error(8787, &Error::from(ErrorKind::Other)).kind(),
+            "SpawnFailed"
+        );
+    }
+
+    #[test]
+    fn test_server_map_insert_and_remove() {
+        use std::collections::HashMap;
+
+        let mut servers: HashMap<u16, &str> = HashMap::new();
+        assert!(ensure_port_unmanaged(&servers, 8787).is_ok());
+        servers.insert(8787, "first");
+        assert!(ensure_port_unmanaged(&servers, 8788).is_ok());
+        servers.insert(8788, "second");
+
+        assert_eq!(select_server(&servers, None), Some(&"first"));
+        assert_eq!(select_server(&servers, Some(8788)), Some(&"second"));
+        assert_eq!(select_server(&servers, Some(9000)), None);
+
+        assert_eq!(take_server(&mut servers, 8787), Ok("first"));
+        assert_eq!(select_server(&servers, None), Some(&"second"));
+        assert_eq!(
+            take_server(&mut servers, 8787),
+            Err(CommandError::NotRunning("Server on port 8787".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_server_map_rejects_duplicate_port() {
+        use std::collections::HashMap;
+
+        let servers = HashMap::from([(8787u16, "running")]);
+        let err = ensure_port_unmanaged(&servers, 8787).unwrap_err();
+
+        assert_eq!(err.kind(), "AlreadyRunning");
+        assert_eq!(err.to_string(), "Server on port 8787 is already running");
+    }
+
+    fn green_checks() -> EnvironmentChecks {
+        let info = |version: &str| CommandInfo {
+            available: true,
+            version: Some(version.to_string()),
+        };
+        EnvironmentChecks {
+            environment: EnvironmentInfo {
+                node: info("v20.11.0"),
+                npm: info("10.2.4"),
+                pnpm: info("9.0.0"),
+                bun: info("1.1.0"),
+                deno: info("deno 1.42.0"),
+                node_meets_minimum: true,
+                minimum_node_version: "v18.0.0".to_string(),
+            },
+            port: 8787,
+            port_available: true,
+            missing_server_bundle: None,
+        }
+    }
+
+    #[test]
+    fn test_green_environment_has_no_issues() {
+        assert!(environment_issues(&green_checks()).is_empty());
+    }
+
+    #[test]
+    fn test_missing_node_is_an_error() {
+        let mut checks = green_checks();
+        checks.environment.node = CommandInfo { available: false, version: None };
+        checks.environment.node_meets_minimum = false;
+
+        let issues = environment_issues(&checks);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert!(issues[0].fix_hint.contains("nodejs.org"));
+    }
+
+    #[test]
+    fn test_old_node_is_an_error() {
+        let mut checks = green_checks();
+        checks.environment.node.version = Some("v16.20.0".to_string());
+        checks.environment.node_meets_minimum = false;
+
+        let issues = environment_issues(&checks);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert!(issues[0].message.contains("v16.20.0"));
+        assert!(issues[0].fix_hint.contains("v18.0.0"));
+    }
+
+    #[test]
+    fn test_missing_npm_and_busy_port_are_warnings() {
+        let mut checks = green_checks();
+        checks.environment.npm = CommandInfo { available: false, version: None };
+        checks.port_available = false;
+
+        let issues = environment_issues(&checks);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|issue| issue.severity == IssueSeverity::Warning));
+        assert!(issues[1].message.contains("8787"));
+    }
+
+    #[test]
+    fn test_missing_server_bundle_is_info() {
+        let mut checks = green_checks();
+        checks.missing_server_bundle = Some(std::path::PathBuf::from("/app/resources/server/index.js"));
+
+        let issues = environment_issues(&checks);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Info);
+
+        let json = serde_json::to_value(&issues[0]).unwrap();
+        assert_eq!(json["severity"], "info");
+    }
+
+    fn passing_start_checks() -> ServerStartChecks {
+        ServerStartChecks {
+            port: 8787,
+            port_valid: Ok(()),
+            port_available: Some(Ok(())),
+            launch: LaunchChecks::Production {
+                node: Ok("/usr/local/bin/node".to_string()),
+                server_script: Ok(std::path::PathBuf::from("/app/resources/server/index.js")),
+            },
+        }
+    }
+
+    fn failed(precheck: &ServerStartPrecheck) -> Vec<StartPrecondition> {
+        precheck.checks.iter().filter(|c| !c.ok).map(|c| c.precondition).collect()
+    }
+
+    #[test]
+    fn test_precheck_ready_reports_resolved_paths() {
+        let precheck = ServerStartPrecheck::from_checks(passing_start_checks());
+
+        assert!(precheck.ready);
+        assert!(!precheck.development);
+        assert_eq!(precheck.checks.len(), 4);
+        assert_eq!(precheck.node_path.as_deref(), Some("/usr/local/bin/node"));
+        assert_eq!(precheck.server_script.as_deref(), Some("/app/resources/server/index.js"));
+        assert_eq!(precheck.package_manager_path, None);
+    }
+
+    #[test]
+    fn test_precheck_invalid_port_skips_availability() {
+        let mut checks = passing_start_checks();
+        checks.port = 80;
+        checks.port_valid = common::validate_port_allowing_privileged(80, false);
+        checks.port_available = None;
+
+        let precheck = ServerStartPrecheck::from_checks(checks);
+        assert!(!precheck.ready);
+        assert_eq!(failed(&precheck), vec![StartPrecondition::ValidPort]);
+        assert!(!precheck.checks.iter().any(|c| c.precondition == StartPrecondition::PortAvailable));
+    }
+
+    #[test]
+    fn test_precheck_port_in_use() {
+        use std::collections::HashMap;
+
+        let listener = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let no_servers: HashMap<u16, ()> = HashMap::new();
+
+        let mut checks = passing_start_checks();
+        checks.port_available = Some(port_availability(&no_servers, port));
+
+        let precheck = ServerStartPrecheck::from_checks(checks);
+        assert!(!precheck.ready);
+        assert_eq!(failed(&precheck), vec![StartPrecondition::PortAvailable]);
+        assert_eq!(precheck.checks[1].error, Some(format!("Port {port} is already in use")));
+    }
+
+    #[test]
+    fn test_port_availability_rejects_managed_port() {
+        use std::collections::HashMap;
+
+        let servers = HashMap::from([(8787u16, "running")]);
+        assert_eq!(
+            port_availability(&servers, 8787),
+            Err("Server on port 8787 is already running".to_string())
+        );
+    }
+
+    #[test]
+    fn test_precheck_missing_node() {
+        let mut checks = passing_start_checks();
+        checks.launch = LaunchChecks::Production {
+            node: Err("Node.js not found".to_string()),
+            server_script: Ok(std::path::PathBuf::from("/app/resources/server/index.js")),
+        };
+
+        let precheck = ServerStartPrecheck::from_checks(checks);
+        assert!(!precheck.ready);
+        assert_eq!(failed(&precheck), vec![StartPrecondition::NodeFound]);
+        assert_eq!(precheck.node_path, None);
+    }
+
+    #[test]
+    fn test_precheck_missing_server_script() {
+        let mut checks = passing_start_checks();
+        checks.launch = LaunchChecks::Production {
+            node: Ok("/usr/local/bin/node".to_string()),
+            server_script: Err("Server executable not found at: /app/index.js".to_string()),
+        };
+
+        let precheck = ServerStartPrecheck::from_checks(checks);
+        assert!(!precheck.ready);
+        assert_eq!(failed(&precheck), vec![StartPrecondition::ServerScriptFound]);
+        assert_eq!(precheck.server_script, None);
+
+        let json = serde_json::to_value(&precheck.checks[3]).unwrap();
+        assert_eq!(json["precondition"], "server_script_found");
+    }
+
+    #[test]
+    fn test_precheck_development_checks_package_manager() {
+        let mut checks = passing_start_checks();
+        checks.launch = LaunchChecks::Development {
+            command: Err("pnpm not found in PATH. Please install it from https://pnpm.io/installation".to_string()),
+            server_dir: Ok(std::path::PathBuf::from("/repo/apps/server")),
+        };
+
+        let precheck = ServerStartPrecheck::from_checks(checks);
+        assert!(!precheck.ready);
+        assert!(precheck.development);
+        assert_eq!(failed(&precheck), vec![StartPrecondition::PackageManagerFound]);
+        assert_eq!(
+            precheck.checks[2].error.as_deref(),
+            Some("pnpm not found in PATH. Please install it from https://pnpm.io/installation")
+        );
+        assert_eq!(precheck.dev_server_dir.as_deref(), Some("/repo/apps/server"));
+        assert!(!precheck.checks.iter().any(|c| c.precondition == StartPrecondition::NodeFound));
+        assert_eq!(precheck.node_path, None);
+    }
+
+    #[test]
+    fn test_system_status_serialization_shape() {
+        let tunnel = TunnelStatus {
+            running: false,
+            url: None,
+            password: None,
+            requires_password: false,
+            healthy: false,
+            uptime_secs: None,
+        };
+        let status = SystemStatus::from_results(
+            Ok(vec![ServerStatus::managed(8787, std::time::Instant::now(), std::time::Instant::now())]),
+            Ok(tunnel),
+            Err("tailscale not found".to_string()),
+            Err("probe failed".to_string()),
+        );
+        let summary = EnvironmentSummary {
+            node_version: Some("v20.11.0".to_string()),
+            node_meets_minimum: true,
+            package_manager: server::PackageManager::Pnpm,
+            package_manager_found: false,
+        };
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "node_version": "v20.11.0",
+                "node_meets_minimum": true,
+                "package_manager": "pnpm",
+                "package_manager_found": false,
+            })
+        );
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["server"][0]["port"], 8787);
+        assert_eq!(json["server"][0]["managed"], true);
+        assert_eq!(json["tunnel"]["running"], false);
+        assert!(json["remote_access"].is_null());
+        assert!(json["environment"].is_null());
+        assert_eq!(
+            json["errors"],
+            serde_json::json!(["remote_access: tailscale not found", "environment: probe failed"])
+        );
+    }
+
+    #[test]
+    fn test_shutdown_report_keeps_every_outcome() {
+        let report = shutdown_report([
+            ("server", ShutdownStep::from(Ok("Stopped 1 server(s)".to_string()))),
+            ("tunnel", ShutdownStep::from(Err("kill failed".to_string()))),
+            ("remote_access", ShutdownStep::from(Ok("Remote Access disabled".to_string()))),
+        ]);
+
+        assert_eq!(report.len(), 3);
+        assert!(report["server"].ok);
+        assert!(report["remote_access"].ok);
+        assert_eq!(
+            report["tunnel"],
+            ShutdownStep {
+                ok: false,
+                message: "kill failed".to_string()
+            }
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["tunnel"]["ok"], false);
+        assert_eq!(json["server"]["message"], "Stopped 1 server(s)");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_step_times_out() {
+        use std::time::Duration;
+
+        let step = ShutdownStep::run(std::future::pending(), Duration::from_millis(50)).await;
+        assert!(!step.ok);
+        assert_eq!(step.message, "Timed out after 50ms");
+
+        let step = ShutdownStep::run(async { Ok("done".to_string()) }, Duration::from_secs(10)).await;
+        assert!(step.ok);
+    }
+
+    #[test]
+    fn test_classify_framework_from_page_markers() {
+        let next_page = r#"<html><head><script src="/_next/static/chunks/main.js"></script></head></html>"#;
+        assert_eq!(classify_framework(Some("Next.js"), None, next_page), Some("nextjs"));
+
+        let vite_page = r#"<script type="module" src="/@vite/client"></script><div id="app"></div>"#;
+        assert_eq!(classify_framework(None, None, vite_page), Some("vite"));
+        // Vite mounted as Express middleware is still a Vite dev server
+        assert_eq!(classify_framework(Some("Express"), None, vite_page), Some("vite"));
+    }
+
+    #[test]
+    fn test_classify_framework_from_headers() {
+        assert_eq!(classify_framework(Some("Express"), None, "<h1>Hello</h1>"), Some("express"));
+        assert_eq!(classify_framework(Some("Next.js"), None, ""), Some("nextjs"));
+        assert_eq!(classify_framework(None, Some("Werkzeug/3.0.1 Python/3.12.1"), ""), Some("flask"));
+        // Nothing recognisable leaves the port-based guess in place
+        assert_eq!(classify_framework(None, Some("nginx/1.25.3"), "<title>App</title>"), None);
+    }
+
+    #[test]
+    fn test_extract_title_from_html_plain() {
+        assert_eq!(
+            extract_title_from_html("<html><head><title>Hello</title></head></html>").as_deref(),
+            Some("Hello")
+        );
+    }
+
+    #[test]
+    fn test_extract_title_from_html_attributes_and_entities() {
+        assert_eq!(
+            extract_title_from_html(r#"<head><TITLE class="x">A &amp; B</TITLE></head>"#).as_deref(),
+            Some("A & B")
+        );
+        assert_eq!(
+            extract_title_from_html("<title lang=\"en\">\n  Tom&#39;s   &lt;App&gt;\n</title>").as_deref(),
+            Some("Tom's <App>")
+        );
+        // Unknown entities and stray ampersands are kept as written
+        assert_eq!(
+            extract_title_from_html("<title>R&D &bogus; &#x1F600;</title>").as_deref(),
+            Some("R&D &bogus; \u{1F600}")
+        );
+    }
+
+    #[test]
+    fn test_extract_title_from_html_missing() {
+        assert_eq!(extract_title_from_html("<html><body><h1>No title</h1></body></html>"), None);
+        assert_eq!(extract_title_from_html("<titlebar>Not a title</titlebar>"), None);
+        assert_eq!(extract_title_from_html("<title>   </title>"), None);
+        assert_eq!(extract_title_from_html("<title>Unclosed"), None);
+    }
+
+    #[tokio::test]
+    async fn test_probe_bounded_probes_every_item_within_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let ports: Vec<u16> = (3000..3040).collect();
+
+        let mut probed = probe_bounded(ports.clone(), 4, |port| {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                // Odd ports have nothing listening
+                (port % 2 == 0).then_some(port)
+            }
+        })
+        .await;
+
+        probed.sort_unstable();
+        let expected: Vec<u16> = ports.into_iter().filter(|p| p % 2 == 0).collect();
+        assert_eq!(probed, expected);
+        assert_eq!(calls.load(Ordering::SeqCst), 40);
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 4);
+    }
+
+    #[test]
+    fn test_port_occupant() {
+        let listening = vec!["Deck IDE server listening on http://0.0.0.0:8787".to_string()];
+        let addr_in_use = vec!["Error: listen EADDRINUSE: address already in use :::8787".to_string()];
+
+        assert_eq!(port_occupant(true, true, &listening), PortOccupant::Ours);
+        assert_eq!(port_occupant(true, true, &addr_in_use), PortOccupant::Foreign);
+        assert_eq!(port_occupant(true, false, &[]), PortOccupant::Foreign);
+        // Still booting
+        assert_eq!(port_occupant(true, true, &[]), PortOccupant::Unknown);
+        assert_eq!(port_occupant(false, true, &listening), PortOccupant::Unknown);
+        // Crashed before binding; the supervisor restarts it
+        assert_eq!(port_occupant(false, false, &[]), PortOccupant::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_verify_port_owner_detects_lost_race() {
+        use std::time::Duration;
+
+        // Another server grabbed the port before ours could bind it
+        let holder = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+        let port = holder.local_addr().unwrap().port();
+        let logs = server::LogBuffer::new(10);
+        logs.push(format!("Error: listen EADDRINUSE: address already in use :::{port}"));
+
+        let result = verify_port_owner(port, Duration::from_secs(5), Duration::from_millis(50), &logs, || true).await;
+        assert_eq!(result, Err(CommandError::PortRaced(port)));
+    }
+
+    #[tokio::test]
+    async fn test_verify_port_owner_detects_exited_child() {
+        use std::time::Duration;
+
+        // Something holds the port and our process died
+        let holder = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+        let port = holder.local_addr().unwrap().port();
+        let logs = server::LogBuffer::new(10);
+
+        let result = verify_port_owner(port, Duration::from_secs(5), Duration::from_millis(50), &logs, || false).await;
+        assert_eq!(result, Err(CommandError::PortRaced(port)));
+    }
+
+    #[tokio::test]
+    async fn test_verify_port_owner_accepts_our_server() {
+        use std::time::{Duration, Instant};
+
+        // Our server bound the port and answers 404 on unknown routes
+        let port = HttpResponder::new("404 Not Found", "Not found").spawn();
+        let logs = server::LogBuffer::new(10);
+        logs.push(format!("Deck IDE server listening on http://0.0.0.0:{port}"));
+
+        let started = Instant::now();
+        let result = verify_port_owner(port, Duration::from_secs(5), Duration::from_millis(50), &logs, || true).await;
+        assert_eq!(result, Ok(()));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_verify_port_owner_is_bounded() {
+        use std::time::{Duration, Instant};
+
+        // A slow server that hasn't bound its port yet isn't a lost race
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let logs = server::LogBuffer::new(10);
+
+        let started = Instant::now();
+        let result =
+            verify_port_owner(port, Duration::from_millis(300), Duration::from_millis(50), &logs, || true).await;
+        assert_eq!(result, Ok(()));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_validate_connectivity_url() {
+        assert!(validate_connectivity_url("https://abc123.loca.lt").is_ok());
+        assert!(validate_connectivity_url("https://pc.tailnet-123.ts.net/").is_ok());
+        assert!(validate_connectivity_url(" http://192.168.1.50:8787 ").is_ok());
+
+        assert_eq!(
+            validate_connectivity_url("ftp://example.com").unwrap_err(),
+            "Only http/https schemes are allowed"
+        );
+        assert!(validate_connectivity_url("file:///etc/passwd").is_err());
+        assert!(validate_connectivity_url("not a url").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity_success() {
+        let port = HttpResponder::new("200 OK", "ok").spawn();
+        let url = validate_connectivity_url(&format!("http://127.0.0.1:{port}/health")).unwrap();
+
+        let result = check_connectivity(url).await;
+        assert!(result.reachable);
+        assert_eq!(result.status_code, Some(200));
+        assert_eq!(result.error, None);
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity_non_2xx() {
+        let port = HttpResponder::new("502 Bad Gateway", "").spawn();
+        let url = validate_connectivity_url(&format!("http://127.0.0.1:{port}")).unwrap();
+
+        let result = check_connectivity(url).await;
+        assert!(!result.reachable);
+        assert_eq!(result.status_code, Some(502));
+        assert_eq!(result.error.as_deref(), Some("Server responded with HTTP 502 Bad Gateway"));
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity_connection_refused() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let url = validate_connectivity_url(&format!("http://127.0.0.1:{port}")).unwrap();
+
+        let result = check_connectivity(url).await;
+        assert!(!result.reachable);
+        assert_eq!(result.status_code, None);
+        let error = result.error.unwrap();
+        assert!(error.starts_with("Connection failed: "), "{error}");
+        assert!(result.latency_ms < CONNECTIVITY_TIMEOUT.as_millis() as u64);
+    }
 }
@@ -1,6 +1,8 @@
 //! Common utilities for command finding and validation
 
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
 /// Minimum valid port number (ports below 1024 require special privileges)
 pub const MIN_PORT: u16 = 1024;
@@ -8,6 +10,80 @@ pub const MIN_PORT: u16 = 1024;
 /// Default port number to use when server is not running
 pub const DEFAULT_PORT: u16 = 8787;
 
+/// Upper bound for requests made without their own timeout
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long the shared HTTP client waits for a connection
+pub const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// HTTP client shared by server probes, health checks and MCP queries
+static HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Shared HTTP client, built on first use
+///
+/// Callers set per-request timeouts with `RequestBuilder::timeout`.
+/// Idle connections aren't pooled: local servers restart and scans hit a
+/// different port each time, so a kept-alive socket is more likely to be
+/// stale than reused.
+pub fn http_client() -> &'static reqwest::Client {
+    HTTP_CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(DEFAULT_HTTP_TIMEOUT)
+            .connect_timeout(HTTP_CONNECT_TIMEOUT)
+            .pool_max_idle_per_host(0)
+            .user_agent(concat!("S-IDE-Desktop/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_else(|e| {
+                // Only the TLS backend can fail here; the settings above are static
+                tracing::error!(target: "Desktop", "Failed to build HTTP client, using defaults: {e}");
+                reqwest::Client::new()
+            })
+    })
+}
+
+/// Memoized result of an executable lookup
+///
+/// Stores "not found" results too; call [`CommandCache::clear`] to look again
+/// (e.g. after the user installs a tool mid-session).
+pub struct CommandCache<T> {
+    slot: Mutex<Option<T>>,
+}
+
+impl<T: Clone> CommandCache<T> {
+    /// Creates an empty cache
+    pub const fn new() -> Self {
+        Self { slot: Mutex::new(None) }
+    }
+
+    /// Returns the cached value, if any
+    ///
+    /// For values that can only be resolved asynchronously: on a miss, resolve
+    /// it and store it with [`CommandCache::get_or_resolve`].
+    pub fn get(&self) -> Option<T> {
+        self.slot.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Returns the cached value, running `resolve` only if nothing is cached
+    pub fn get_or_resolve(&self, resolve: impl FnOnce() -> T) -> T {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        slot.get_or_insert_with(resolve).clone()
+    }
+
+    /// Forgets the cached value
+    pub fn clear(&self) {
+        *self.slot.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+static NPM_COMMAND: CommandCache<Result<String, String>> = CommandCache::new();
+static NODE_EXECUTABLE: CommandCache<Result<String, String>> = CommandCache::new();
+
+/// Forgets the cached node and npm paths so the next lookup searches again
+pub fn refresh_command_paths() {
+    NPM_COMMAND.clear();
+    NODE_EXECUTABLE.clear();
+}
+
 /// Validates that a port number is within the valid range (1024-65535)
 /// Ports below 1024 require special privileges
 ///
@@ -15,17 +91,145 @@ pub const DEFAULT_PORT: u16 = 8787;
 ///
 /// Returns an error if the port is below 1024 or is 0
 pub fn validate_port(port: u16) -> Result<(), String> {
-    if port < MIN_PORT {
+    validate_port_allowing_privileged(port, false)
+}
+
+/// Validates a port, optionally accepting privileged ports (below 1024)
+///
+/// With `allow_low` ports 1-1023 are accepted, for users who can bind them
+/// (root, or `CAP_NET_BIND_SERVICE` on Linux). Port 0 is always rejected.
+///
+/// # Errors
+///
+/// Returns an error if the port is 0, or below 1024 when `allow_low` is false
+pub fn validate_port_allowing_privileged(port: u16, allow_low: bool) -> Result<(), String> {
+    if port == 0 {
+        return Err("Port 0 is not valid".to_string());
+    }
+    if port < MIN_PORT && !allow_low {
         return Err(format!(
             "Port {port} is below {MIN_PORT}. Use a port between {MIN_PORT} and 65535."
         ));
     }
-    if port == 0 {
-        return Err("Port 0 is not valid".to_string());
-    }
     Ok(())
 }
 
+/// Returns the current user's home directory
+///
+/// # Errors
+///
+/// Returns an error if neither `USERPROFILE` (Windows) nor `HOME` is set
+pub fn home_dir() -> Result<PathBuf, String> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(p) = std::env::var("USERPROFILE") {
+            return Ok(PathBuf::from(p));
+        }
+    }
+    if let Ok(p) = std::env::var("HOME") {
+        return Ok(PathBuf::from(p));
+    }
+    Err("Could not determine home directory".to_string())
+}
+
+/// Per-user data directory holding settings, state and logs (`~/.side-ide`)
+///
+/// # Errors
+///
+/// Returns an error if the home directory cannot be determined
+pub fn data_dir() -> Result<PathBuf, String> {
+    Ok(home_dir()?.join(".side-ide"))
+}
+
+/// Creates `dir` if it's missing, making sure it lies within `root`
+///
+/// `..` components are refused before anything is created, and the created
+/// directory is checked again after resolving symlinks.
+///
+/// # Errors
+///
+/// Returns an error if `dir` is outside `root` or can't be created
+pub fn ensure_dir_within(dir: &Path, root: &Path) -> Result<PathBuf, String> {
+    let outside = || format!("{} is outside {}", dir.display(), root.display());
+    if !dir.starts_with(root) || dir.components().any(|c| c == Component::ParentDir) {
+        return Err(outside());
+    }
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let canonical = |path: &Path| {
+        path.canonicalize()
+            .map_err(|e| format!("Failed to resolve {}: {e}", path.display()))
+    };
+    if !canonical(dir)?.starts_with(canonical(root)?) {
+        return Err(outside());
+    }
+    Ok(dir.to_path_buf())
+}
+
+/// Finds an executable by checking `common_paths` and then PATH
+///
+/// The first entry of `common_paths` that exists wins; otherwise `names` are
+/// looked up on PATH in order. On Windows `where` is used and the full path is
+/// returned; elsewhere the bare name is returned so the shell resolves it.
+///
+/// # Errors
+///
+/// Returns an error naming the first candidate if nothing is found
+pub fn find_executable(names: &[&str], common_paths: &[PathBuf]) -> Result<String, String> {
+    if let Some(path) = common_paths.iter().find(|path| path.exists()) {
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    for name in names {
+        if let Some(path) = find_in_path(name) {
+            return Ok(path);
+        }
+    }
+
+    Err(format!(
+        "{} not found in PATH",
+        names.first().copied().unwrap_or("executable")
+    ))
+}
+
+/// Looks up a single executable on PATH
+#[cfg(target_os = "windows")]
+fn find_in_path(name: &str) -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    // `where` is more reliable than which on Windows
+    let mut cmd = std::process::Command::new("where");
+    cmd.arg(name);
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let path = stdout.lines().next()?.trim();
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+/// Looks up a single executable on PATH
+#[cfg(not(target_os = "windows"))]
+fn find_in_path(name: &str) -> Option<String> {
+    which::which(name).ok().map(|_| name.to_string())
+}
+
+/// Common paths under the user's global npm directory (Windows)
+#[cfg(target_os = "windows")]
+fn npm_global_paths(file_names: &[&str]) -> Vec<PathBuf> {
+    match std::env::var("USERNAME") {
+        Ok(username) => file_names
+            .iter()
+            .map(|file| PathBuf::from(format!(r"C:\Users\{username}\AppData\Roaming\npm")).join(file))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
 /// Finds the npm command on the system
 ///
 /// # Returns
@@ -36,77 +240,81 @@ pub fn validate_port(port: u16) -> Result<(), String> {
 ///
 /// Returns an error if npm cannot be found in PATH or common installation locations
 pub fn find_npm_command() -> Result<String, String> {
+    NPM_COMMAND.get_or_resolve(resolve_npm_command)
+}
+
+/// Searches for npm; see [`find_npm_command`]
+fn resolve_npm_command() -> Result<String, String> {
     // On Windows, prefer .cmd files and look in known locations first
     #[cfg(target_os = "windows")]
-    {
-        // Try common Node.js installation paths on Windows first
-        if let Ok(username) = std::env::var("USERNAME") {
-            let common_paths = [
-                format!(r"C:\Users\{username}\AppData\Roaming\npm\npm.cmd"),
-                r"C:\Program Files\nodejs\npm.cmd".to_string(),
-                r"C:\Program Files (x86)\nodejs\npm.cmd".to_string(),
-            ];
-            for path in &common_paths {
-                if PathBuf::from(path).exists() {
-                    return Ok(path.clone());
-                }
-            }
-        }
+    let (names, common_paths) = {
+        let mut common_paths = npm_global_paths(&["npm.cmd"]);
+        common_paths.push(PathBuf::from(r"C:\Program Files\nodejs\npm.cmd"));
+        common_paths.push(PathBuf::from(r"C:\Program Files (x86)\nodejs\npm.cmd"));
+        // Fallback to regular npm which will use cmd.exe /c
+        (["npm.cmd", "npm"].as_slice(), common_paths)
+    };
+    #[cfg(not(target_os = "windows"))]
+    let (names, common_paths) = (["npm"].as_slice(), Vec::new());
 
-        // Try using where command on Windows (more reliable than which on Windows)
-        let mut cmd1 = std::process::Command::new("where");
-        cmd1.arg("npm.cmd");
-        
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            cmd1.creation_flags(CREATE_NO_WINDOW);
-        }
-        
-        if let Ok(output) = cmd1.output() {
-            if output.status.success() {
-                if let Some(path) = String::from_utf8_lossy(&output.stdout).lines().next() {
-                    let path = path.trim();
-                    if !path.is_empty() {
-                        return Ok(path.to_string());
-                    }
-                }
-            }
-        }
+    find_executable(names, &common_paths).map_err(|_| {
+        "npm not found in PATH. Please install Node.js from https://nodejs.org/".to_string()
+    })
+}
 
-        // Fallback to regular npm which will use cmd.exe /c
-        let mut cmd2 = std::process::Command::new("where");
-        cmd2.arg("npm");
-        
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            cmd2.creation_flags(CREATE_NO_WINDOW);
-        }
-        
-        if let Ok(output) = cmd2.output() {
-            if output.status.success() {
-                if let Some(path) = String::from_utf8_lossy(&output.stdout).lines().next() {
-                    let path = path.trim();
-                    if !path.is_empty() {
-                        return Ok(path.to_string());
-                    }
-                }
-            }
-        }
-    }
+/// Finds the pnpm command on the system
+///
+/// # Errors
+///
+/// Returns an error if pnpm cannot be found in PATH or common installation locations
+pub fn find_pnpm_command() -> Result<String, String> {
+    find_package_manager_command("pnpm", "https://pnpm.io/installation")
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        // Try to find npm using which crate on non-Windows
-        if which::which("npm").is_ok() {
-            return Ok("npm".to_string());
+/// Finds the yarn command on the system
+///
+/// # Errors
+///
+/// Returns an error if yarn cannot be found in PATH or common installation locations
+pub fn find_yarn_command() -> Result<String, String> {
+    find_package_manager_command("yarn", "https://yarnpkg.com/getting-started/install")
+}
+
+/// Finds the bun command on the system
+///
+/// # Errors
+///
+/// Returns an error if bun cannot be found in PATH or common installation locations
+pub fn find_bun_command() -> Result<String, String> {
+    find_package_manager_command("bun", "https://bun.sh/")
+}
+
+/// Finds a JavaScript package manager the same way as [`find_npm_command`]
+///
+/// Besides PATH, looks in the global npm directory (where pnpm/yarn are
+/// usually installed on Windows) and in `~/.bun/bin`.
+fn find_package_manager_command(name: &str, install_url: &str) -> Result<String, String> {
+    let bun_bin = home_dir().ok().map(|home| home.join(".bun").join("bin"));
+
+    // On Windows the known locations come first, preferring .cmd shims
+    #[cfg(target_os = "windows")]
+    let found = {
+        let mut common_paths = Vec::new();
+        if let Ok(app_data) = std::env::var("APPDATA") {
+            common_paths.push(PathBuf::from(&app_data).join("npm").join(format!("{name}.cmd")));
         }
-    }
+        common_paths.extend(bun_bin.map(|bin| bin.join(format!("{name}.exe"))));
+        let cmd_name = format!("{name}.cmd");
+        find_executable(&[cmd_name.as_str(), name], &common_paths)
+    };
+    // Elsewhere PATH comes first
+    #[cfg(not(target_os = "windows"))]
+    let found = find_executable(&[name], &[]).or_else(|_| {
+        let bun_paths: Vec<PathBuf> = bun_bin.map(|bin| bin.join(name)).into_iter().collect();
+        find_executable(&[], &bun_paths)
+    });
 
-    Err("npm not found in PATH. Please install Node.js from https://nodejs.org/".to_string())
+    found.map_err(|_| format!("{name} not found in PATH. Please install it from {install_url}"))
 }
 
 /// Finds the npx command on the system
@@ -121,51 +329,19 @@ pub fn find_npm_command() -> Result<String, String> {
 pub fn find_npx_command() -> Result<String, String> {
     // On Windows, try .cmd files in known locations
     #[cfg(target_os = "windows")]
-    {
-        // Try common Node.js installation paths first
-        if let Ok(username) = std::env::var("USERNAME") {
-            let common_paths = [
-                r"C:\Program Files\nodejs\npx.cmd".to_string(),
-                r"C:\Program Files\nodejs\npx.exe".to_string(),
-                format!(r"C:\Users\{username}\AppData\Roaming\npm\npx.cmd"),
-                format!(r"C:\Users\{username}\AppData\Roaming\npm\npx.exe"),
-            ];
-            for path in &common_paths {
-                if PathBuf::from(path).exists() {
-                    return Ok(path.clone());
-                }
-            }
-        }
-
-        // Try using where command
-        let mut cmd = std::process::Command::new("where");
-        cmd.arg("npx.cmd");
-        
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        }
-        
-        if let Ok(output) = cmd.output() {
-            if output.status.success() {
-                if let Some(path) = String::from_utf8_lossy(&output.stdout).lines().next() {
-                    let path = path.trim();
-                    if !path.is_empty() {
-                        return Ok(path.to_string());
-                    }
-                }
-            }
-        }
-    }
-
+    let (names, common_paths) = {
+        let mut common_paths = vec![
+            PathBuf::from(r"C:\Program Files\nodejs\npx.cmd"),
+            PathBuf::from(r"C:\Program Files\nodejs\npx.exe"),
+        ];
+        common_paths.extend(npm_global_paths(&["npx.cmd", "npx.exe"]));
+        (["npx.cmd"].as_slice(), common_paths)
+    };
     #[cfg(not(target_os = "windows"))]
-    {
-        // Try to find npx using which on non-Windows
-        if which::which("npx").is_ok() {
-            return Ok("npx".to_string());
-        }
+    let (names, common_paths) = (["npx"].as_slice(), Vec::new());
+
+    if let Ok(path) = find_executable(names, &common_paths) {
+        return Ok(path);
     }
 
     // As a last resort, try to find npx relative to node
@@ -199,33 +375,42 @@ pub fn find_npx_command() -> Result<String, String> {
 ///
 /// Returns an error if node cannot be found in PATH or common installation locations
 pub fn find_node_executable() -> Result<String, String> {
+    NODE_EXECUTABLE.get_or_resolve(resolve_node_executable)
+}
+
+/// Searches for node; see [`find_node_executable`]
+fn resolve_node_executable() -> Result<String, String> {
     #[cfg(target_os = "windows")]
     let candidates = ["node.exe", "node.cmd"];
     #[cfg(not(target_os = "windows"))]
     let candidates = ["node"];
 
     // Try to find node in PATH
-    for cmd in &candidates {
-        if which::which(cmd).is_ok() {
-            return Ok((*cmd).to_string());
-        }
+    if let Ok(path) = find_executable(&candidates, &[]) {
+        return Ok(path);
     }
 
-    // Try common installation paths on Windows
+    // Try common installation paths
     #[cfg(target_os = "windows")]
-    {
-        let common_paths = [
-            r"C:\Program Files\nodejs\node.exe",
-            r"C:\Program Files (x86)\nodejs\node.exe",
-            r"C:\Program Files\nodejs\node",
-        ];
-        for path in common_paths {
-            if PathBuf::from(path).exists() {
-                return Ok(path.to_string());
-            }
-        }
+    let common_paths = [
+        r"C:\Program Files\nodejs\node.exe",
+        r"C:\Program Files (x86)\nodejs\node.exe",
+        r"C:\Program Files\nodejs\node",
+    ];
+    #[cfg(not(target_os = "windows"))]
+    let common_paths = [
+        "/usr/local/bin/node",
+        "/usr/bin/node",
+        "/opt/homebrew/bin/node",
+    ];
+    let common_paths: Vec<PathBuf> = common_paths.iter().map(PathBuf::from).collect();
+    if let Ok(path) = find_executable(&[], &common_paths) {
+        return Ok(path);
+    }
 
-        // Try to find relative to npm
+    // Try to find relative to npm
+    #[cfg(target_os = "windows")]
+    {
         if let Ok(npm_path) = find_npm_command() {
             let npm_path_buf = PathBuf::from(&npm_path);
             if let Some(node_dir) = npm_path_buf.parent() {
@@ -237,20 +422,5 @@ pub fn find_node_executable() -> Result<String, String> {
         }
     }
 
-    // Try common installation paths on macOS/Linux
-    #[cfg(not(target_os = "windows"))]
-    {
-        let common_paths = [
-            "/usr/local/bin/node",
-            "/usr/bin/node",
-            "/opt/homebrew/bin/node",
-        ];
-        for path in common_paths {
-            if PathBuf::from(path).exists() {
-                return Ok(path.to_string());
-            }
-        }
-    }
-
     Err("Node.js not found in PATH or common installation locations. Please install Node.js from https://nodejs.org/".to_string())
 }
@@ -0,0 +1,111 @@
+//! Unit tests for common utilities
+//!
+//! Tests executable lookup across common paths and PATH, and app directory
+//! resolution.
+
+use crate::common::*;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn test_find_executable_returns_first_existing_common_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let first = dir.join("first");
+        let second = dir.join("second");
+        std::fs::write(&first, "").unwrap();
+        std::fs::write(&second, "").unwrap();
+
+        let found = find_executable(&[], &[dir.join("missing"), first.clone(), second]).unwrap();
+        assert_eq!(PathBuf::from(found), first);
+
+        // Common paths take precedence over PATH
+        let found = find_executable(&["sh"], std::slice::from_ref(&first)).unwrap();
+        assert_eq!(PathBuf::from(found), first);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_executable_honors_name_priority() {
+        assert_eq!(find_executable(&["sh", "ls"], &[]), Ok("sh".to_string()));
+        assert_eq!(find_executable(&["ls", "sh"], &[]), Ok("ls".to_string()));
+        assert_eq!(
+            find_executable(&["side-missing-executable", "sh"], &[]),
+            Ok("sh".to_string())
+        );
+    }
+
+    #[test]
+    fn test_find_executable_not_found() {
+        let err = find_executable(&["side-missing-executable"], &[PathBuf::from("/side/missing")])
+            .unwrap_err();
+        assert!(err.contains("side-missing-executable"));
+    }
+
+    #[test]
+    fn test_http_client_is_built_once() {
+        let first: usize = std::thread::spawn(|| http_client() as *const reqwest::Client as usize)
+            .join()
+            .unwrap();
+        let second = http_client() as *const reqwest::Client as usize;
+        assert_eq!(first, second);
+        assert!(std::ptr::eq(http_client(), http_client()));
+    }
+
+    #[test]
+    fn test_command_cache_get_and_clear() {
+        let cache: CommandCache<Option<String>> = CommandCache::new();
+        assert_eq!(cache.get(), None);
+
+        assert_eq!(cache.get_or_resolve(|| Some("1.62.0".to_string())), Some("1.62.0".to_string()));
+        assert_eq!(cache.get(), Some(Some("1.62.0".to_string())));
+        // Cached values win over a new resolution
+        assert_eq!(cache.get_or_resolve(|| None), Some("1.62.0".to_string()));
+
+        cache.clear();
+        assert_eq!(cache.get(), None);
+    }
+
+    #[test]
+    fn test_data_dir_is_under_home() {
+        assert_eq!(data_dir().unwrap(), home_dir().unwrap().join(".side-ide"));
+    }
+
+    #[test]
+    fn test_ensure_dir_within_creates_missing_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let logs = root.join("logs");
+
+        assert_eq!(ensure_dir_within(&logs, root), Ok(logs.clone()));
+        assert!(logs.is_dir());
+        assert_eq!(ensure_dir_within(root, root), Ok(root.to_path_buf()));
+    }
+
+    #[test]
+    fn test_ensure_dir_within_rejects_paths_outside_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        assert!(ensure_dir_within(&std::env::temp_dir(), root).is_err());
+        let escape = root.join("..").join("side-common-escaped");
+        assert!(ensure_dir_within(&escape, root).is_err());
+        assert!(!escape.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ensure_dir_within_rejects_symlink_escape() {
+        let root_tmp = tempfile::tempdir().unwrap();
+        let root = root_tmp.path();
+        let outside_tmp = tempfile::tempdir().unwrap();
+        let outside = outside_tmp.path();
+        std::os::unix::fs::symlink(outside, root.join("logs")).unwrap();
+
+        assert!(ensure_dir_within(&root.join("logs"), root).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Typed app configuration stored in `~/.side-ide/config.json`
+//!
+//! Values are read and written by key so the frontend (and a future CLI) can
+//! get and set individual settings. Every write is validated first.
+//!
+//! This file is the only store for the update channel, Remote Access
+//! auto-start and the package manager; the updater, Remote Access and
+//! `start_server` read them from here.
+
+use crate::common;
+use crate::remote_access;
+use crate::server::{self, PackageManager};
+use crate::updater::{self, UpdateChannel};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Keys accepted by [`AppConfig::get`] and [`AppConfig::set`]
+pub const CONFIG_KEYS: &[&str] = &[
+    "default_port",
+    "auto_start_remote_access",
+    "update_channel",
+    "package_manager",
+    "close_to_tray",
+    "health_path",
+];
+
+/// App configuration
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AppConfig {
+    /// Port the server is started on
+    #[serde(default = "default_port")]
+    pub default_port: u16,
+    /// Whether Remote Access starts with the server
+    #[serde(default)]
+    pub auto_start_remote_access: bool,
+    /// Release channel to take updates from
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// Package manager used to run the development server
+    #[serde(default)]
+    pub package_manager: PackageManager,
+    /// Whether closing the window hides it to the tray instead of quitting
+    #[serde(default)]
+    pub close_to_tray: bool,
+    /// Path polled to tell when the server is ready
+    #[serde(default = "default_health_path")]
+    pub health_path: String,
+}
+
+fn default_port() -> u16 {
+    common::DEFAULT_PORT
+}
+
+fn default_health_path() -> String {
+    server::DEFAULT_HEALTH_PATH.to_string()
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            default_port: default_port(),
+            auto_start_remote_access: false,
+            update_channel: UpdateChannel::default(),
+            package_manager: PackageManager::default(),
+            close_to_tray: false,
+            health_path: default_health_path(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Gets the value of `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is unknown
+    pub fn get(&self, key: &str) -> Result<Value, String> {
+        let value = match key {
+            "default_port" => Value::from(self.default_port),
+            "auto_start_remote_access" => Value::from(self.auto_start_remote_access),
+            "update_channel" => serde_json::to_value(self.update_channel).map_err(|e| e.to_string())?,
+            "package_manager" => serde_json::to_value(self.package_manager).map_err(|e| e.to_string())?,
+            "close_to_tray" => Value::from(self.close_to_tray),
+            "health_path" => Value::from(self.health_path.clone()),
+            _ => return Err(unknown_key(key)),
+        };
+        Ok(value)
+    }
+
+    /// Validates `value` and stores it under `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is unknown or `value` is invalid for it;
+    /// the config is left unchanged in that case
+    pub fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        match key {
+            "default_port" => {
+                let port = value
+                    .as_u64()
+                    .and_then(|port| u16::try_from(port).ok())
+                    .ok_or_else(|| format!("default_port must be a port number, got {value}"))?;
+                common::validate_port(port)?;
+                self.default_port = port;
+            }
+            "auto_start_remote_access" => {
+                self.auto_start_remote_access = value
+                    .as_bool()
+                    .ok_or_else(|| format!("auto_start_remote_access must be true or false, got {value}"))?;
+            }
+            "update_channel" => {
+                self.update_channel = value
+                    .as_str()
+                    .ok_or_else(|| format!("update_channel must be a string, got {value}"))?
+                    .parse()?;
+            }
+            "package_manager" => {
+                self.package_manager = serde_json::from_value(value.clone()).map_err(|_| {
+                    format!("package_manager must be one of npm, pnpm, yarn or bun, got {value}")
+                })?;
+            }
+            "close_to_tray" => {
+                self.close_to_tray = value
+                    .as_bool()
+                    .ok_or_else(|| format!("close_to_tray must be true or false, got {value}"))?;
+            }
+            "health_path" => {
+                let path = value
+                    .as_str()
+                    .ok_or_else(|| format!("health_path must be a string, got {value}"))?;
+                server::validate_health_path(path)?;
+                self.health_path = path.to_string();
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+fn unknown_key(key: &str) -> String {
+    format!("Unknown config key '{key}'. Valid keys: {}", CONFIG_KEYS.join(", "))
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(common::home_dir()?.join(".side-ide").join("config.json"))
+}
+
+/// Loads the config from `path`, calling `missing` if it can't be read
+///
+/// Invalid JSON gives the defaults. A hand-edited health path that fails
+/// validation falls back to the default.
+pub fn load_from_or(path: &Path, missing: impl FnOnce() -> AppConfig) -> AppConfig {
+    let mut config: AppConfig = match std::fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str(&raw).unwrap_or_default(),
+        Err(_) => missing(),
+    };
+    if server::validate_health_path(&config.health_path).is_err() {
+        config.health_path = default_health_path();
+    }
+    config
+}
+
+/// Persists the config to `path`
+///
+/// # Errors
+///
+/// Returns an error if the config file can't be written
+pub fn save_to(path: &Path, config: &AppConfig) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {e}"))?;
+    }
+
+    let data = serde_json::to_string_pretty(config).map_err(|e| format!("Invalid JSON: {e}"))?;
+    std::fs::write(path, data).map_err(|e| format!("Failed to write config: {e}"))
+}
+
+/// Config seeded from the settings files used before this one existed
+fn migrated_config() -> AppConfig {
+    AppConfig {
+        update_channel: updater::load_stored_settings().channel,
+        auto_start_remote_access: remote_access::load_legacy_auto_start(),
+        ..AppConfig::default()
+    }
+}
+
+/// Loads the config, or defaults if missing or invalid
+pub fn load() -> AppConfig {
+    config_path()
+        .map(|path| load_from_or(&path, migrated_config))
+        .unwrap_or_default()
+}
+
+/// Persists the config
+///
+/// # Errors
+///
+/// Returns an error if the config file can't be written
+pub fn save(config: &AppConfig) -> Result<(), String> {
+    save_to(&config_path()?, config)
+}
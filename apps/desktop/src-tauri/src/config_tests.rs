@@ -0,0 +1,111 @@
+//! Unit tests for the app configuration
+//!
+//! Tests getting and setting keys, validation and persistence.
+
+use crate::config::*;
+use crate::server::PackageManager;
+use crate::updater::UpdateChannel;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn test_set_get_round_trip() {
+        let mut config = AppConfig::default();
+        config.set("default_port", json!(9000)).unwrap();
+        config.set("auto_start_remote_access", json!(true)).unwrap();
+        config.set("update_channel", json!("beta")).unwrap();
+        config.set("package_manager", json!("pnpm")).unwrap();
+        config.set("close_to_tray", json!(true)).unwrap();
+        config.set("health_path", json!("/api/health")).unwrap();
+
+        assert_eq!(config.get("default_port").unwrap(), json!(9000));
+        assert_eq!(config.get("auto_start_remote_access").unwrap(), json!(true));
+        assert_eq!(config.get("update_channel").unwrap(), json!("beta"));
+        assert_eq!(config.get("package_manager").unwrap(), json!("pnpm"));
+        assert_eq!(config.get("close_to_tray").unwrap(), json!(true));
+        assert_eq!(config.get("health_path").unwrap(), json!("/api/health"));
+        assert_eq!(config.update_channel, UpdateChannel::Beta);
+        assert_eq!(config.package_manager, PackageManager::Pnpm);
+    }
+
+    #[test]
+    fn test_every_key_is_readable() {
+        let config = AppConfig::default();
+        for key in CONFIG_KEYS {
+            assert!(config.get(key).is_ok(), "{key} should be readable");
+        }
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_values() {
+        let mut config = AppConfig::default();
+        assert!(config.set("default_port", json!(80)).is_err());
+        assert!(config.set("default_port", json!(70000)).is_err());
+        assert!(config.set("default_port", json!("8080")).is_err());
+        assert!(config.set("auto_start_remote_access", json!("yes")).is_err());
+        assert!(config.set("update_channel", json!("nightly")).is_err());
+        assert!(config.set("package_manager", json!("cargo")).is_err());
+        assert!(config.set("close_to_tray", json!(1)).is_err());
+        assert!(config.set("health_path", json!("healthz")).is_err());
+        assert!(config.set("health_path", json!(true)).is_err());
+        assert_eq!(config, AppConfig::default());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let mut config = AppConfig::default();
+        assert!(config.get("theme").is_err());
+        assert!(config.set("theme", json!("dark")).is_err());
+    }
+
+    #[test]
+    fn test_persisted_config_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let mut config = AppConfig::default();
+        config.set("default_port", json!(9001)).unwrap();
+
+        save_to(&path, &config).unwrap();
+
+        assert_eq!(load_from_or(&path, AppConfig::default), config);
+    }
+
+    #[test]
+    fn test_invalid_persisted_health_path_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{"default_port": 9002, "health_path": "healthz"}"#).unwrap();
+
+        let config = load_from_or(&path, AppConfig::default);
+        assert_eq!(config.default_port, 9002);
+        assert_eq!(config.health_path, "/health");
+    }
+
+    #[test]
+    fn test_missing_config_file_returns_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        assert_eq!(load_from_or(&path, AppConfig::default), AppConfig::default());
+    }
+
+    #[test]
+    fn test_missing_config_file_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let legacy = || AppConfig {
+            update_channel: UpdateChannel::Beta,
+            auto_start_remote_access: true,
+            ..AppConfig::default()
+        };
+
+        assert_eq!(load_from_or(&path, legacy), legacy());
+
+        // Once the config exists, the old settings files no longer matter
+        save_to(&path, &AppConfig::default()).unwrap();
+        assert_eq!(load_from_or(&path, legacy), AppConfig::default());
+    }
+}
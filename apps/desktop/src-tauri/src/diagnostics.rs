@@ -0,0 +1,162 @@
+//! Diagnostics bundle for bug reports
+//!
+//! Gathers the panic log, the server log, recent backend logs, version info
+//! and environment checks into one JSON file users can attach to an issue.
+//! Secrets are redacted before anything is written.
+
+use crate::log_file;
+use crate::logging::LogEntry;
+use crate::panic_log;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most bytes taken from the end of each log file
+pub const MAX_DIAGNOSTIC_LOG_BYTES: u64 = 256 * 1024;
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Object keys whose values are always redacted
+const SECRET_KEYS: &[&str] = &["password", "auth_url", "authkey", "auth_key", "token", "secret"];
+
+/// Everything collected for a bug report
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiagnosticsReport {
+    /// Milliseconds since the Unix epoch
+    pub generated_at_ms: u64,
+    /// Operating system and architecture the app was built for
+    pub platform: String,
+    /// Output of `get_version_info`, or the error it returned
+    pub version_info: Value,
+    /// Output of `validate_environment`, or the error it returned
+    pub environment_issues: Value,
+    /// End of the panic log, if there is one
+    pub panic_log: Option<String>,
+    /// End of the persistent server log, if there is one
+    pub server_log: Option<String>,
+    /// Recent in-memory backend logs
+    pub recent_logs: Vec<LogEntry>,
+}
+
+impl DiagnosticsReport {
+    /// Collects the log files and wraps the command outputs
+    pub fn collect<V: serde::Serialize, E: serde::Serialize>(
+        version_info: Result<V, String>,
+        environment_issues: Result<E, String>,
+        recent_logs: Vec<LogEntry>,
+    ) -> Self {
+        Self {
+            generated_at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or_default(),
+            platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+            version_info: section(version_info),
+            environment_issues: section(environment_issues),
+            panic_log: panic_log::panic_log_path().and_then(|path| read_tail(&path, MAX_DIAGNOSTIC_LOG_BYTES)),
+            server_log: log_file::log_file_path()
+                .ok()
+                .and_then(|path| read_tail(&path, MAX_DIAGNOSTIC_LOG_BYTES)),
+            recent_logs,
+        }
+    }
+}
+
+/// Serializes a command result, turning an error into `{"error": ...}`
+fn section<T: serde::Serialize>(result: Result<T, String>) -> Value {
+    result
+        .and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| serde_json::json!({ "error": e }))
+}
+
+/// Reads at most the last `max_bytes` of `path`, or `None` if it can't be read
+pub fn read_tail(path: &Path, max_bytes: u64) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes))).ok()?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn secret_patterns() -> &'static [(Regex, &'static str)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // Tailscale login links authorize a machine on the user's tailnet
+            (r"https://login\.tailscale\.com/\S+", "https://login.tailscale.com/[REDACTED]"),
+            // Credentials embedded in URLs
+            (r"(?i)(\b[a-z][a-z0-9+.-]*://)[^/\s:@]+:[^/\s@]+@", "${1}[REDACTED]@"),
+            // `password=...`, `token: ...` and the like in log lines
+            (
+                r#"(?i)\b(password|passwd|token|secret|auth_?key)(["']?\s*[=:]\s*["']?)[^\s"',;&]+"#,
+                "${1}${2}[REDACTED]",
+            ),
+        ]
+        .into_iter()
+        .map(|(pattern, replacement)| (Regex::new(pattern).expect("valid secret pattern"), replacement))
+        .collect()
+    })
+}
+
+/// Redacts secrets in a string
+///
+/// Known secret values (e.g. the current tunnel password) are replaced
+/// wherever they appear, then common secret patterns are masked.
+pub fn redact_text(text: &str, known_secrets: &[String]) -> String {
+    let mut redacted = text.to_string();
+    for secret in known_secrets.iter().filter(|secret| !secret.is_empty()) {
+        redacted = redacted.replace(secret.as_str(), REDACTED);
+    }
+    for (pattern, replacement) in secret_patterns() {
+        redacted = pattern.replace_all(&redacted, *replacement).into_owned();
+    }
+    redacted
+}
+
+/// Redacts secrets everywhere in `value`
+///
+/// Values under secret-looking keys (passwords, auth URLs, tokens) are
+/// replaced outright; every other string goes through [`redact_text`].
+pub fn redact_value(value: Value, known_secrets: &[String]) -> Value {
+    match value {
+        Value::String(text) => Value::String(redact_text(&text, known_secrets)),
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| redact_value(item, known_secrets)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, field)| {
+                    let field = if is_secret_key(&key) && !field.is_null() {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_value(field, known_secrets)
+                    };
+                    (key, field)
+                })
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other,
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEYS.iter().any(|secret| key == *secret || key.ends_with(&format!("_{secret}")))
+}
+
+/// Redacts `report` and writes it to `path` as pretty-printed JSON
+///
+/// # Errors
+///
+/// Returns an error if the report can't be serialized or written
+pub fn write_report(path: &Path, report: &DiagnosticsReport, known_secrets: &[String]) -> Result<(), String> {
+    let value = serde_json::to_value(report).map_err(|e| format!("Invalid JSON: {e}"))?;
+    let data = serde_json::to_string_pretty(&redact_value(value, known_secrets))
+        .map_err(|e| format!("Invalid JSON: {e}"))?;
+    std::fs::write(path, data).map_err(|e| format!("Failed to write diagnostics to {}: {e}", path.display()))
+}
@@ -0,0 +1,140 @@
+//! Docker container discovery
+//!
+//! Lists running containers and their published ports so scan results can be
+//! matched to the container that owns a port.
+
+use std::time::Duration;
+
+/// How long `docker ps` may take before Docker is reported unavailable
+pub const DOCKER_PS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A published port, `host_ip:host_port -> container_port/protocol`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PortMapping {
+    /// Host address the port is bound to (e.g. `0.0.0.0` or `::`)
+    pub host_ip: String,
+    pub host_port: u16,
+    pub container_port: u16,
+    /// `tcp` or `udp`
+    pub protocol: String,
+}
+
+/// A running container
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DockerContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Human-readable status (e.g. `Up 2 hours`)
+    pub status: String,
+    pub ports: Vec<PortMapping>,
+}
+
+/// Why containers couldn't be listed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DockerError {
+    /// The CLI is missing or the daemon isn't running
+    NotAvailable(String),
+    /// `docker ps` ran but its output couldn't be used
+    Failed(String),
+}
+
+impl std::fmt::Display for DockerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DockerError::NotAvailable(reason) => write!(f, "Docker not available: {reason}"),
+            DockerError::Failed(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// One line of `docker ps --format json`
+#[derive(serde::Deserialize)]
+struct PsLine {
+    #[serde(rename = "ID", default)]
+    id: String,
+    #[serde(rename = "Names", default)]
+    names: String,
+    #[serde(rename = "Image", default)]
+    image: String,
+    #[serde(rename = "Status", default)]
+    status: String,
+    #[serde(rename = "Ports", default)]
+    ports: String,
+}
+
+/// Parses one entry of the `Ports` column, e.g. `0.0.0.0:8080->80/tcp`
+///
+/// Returns None for ports that are exposed but not published (`80/tcp`).
+pub fn parse_port_mapping(entry: &str) -> Option<PortMapping> {
+    let (host, container) = entry.trim().split_once("->")?;
+    let (host_ip, host_port) = host.rsplit_once(':')?;
+    let (container_port, protocol) = container.split_once('/')?;
+
+    Some(PortMapping {
+        host_ip: host_ip.trim_start_matches('[').trim_end_matches(']').to_string(),
+        host_port: host_port.parse().ok()?,
+        container_port: container_port.parse().ok()?,
+        protocol: protocol.to_string(),
+    })
+}
+
+/// Parses the `Ports` column; port ranges are skipped
+pub fn parse_ports(ports: &str) -> Vec<PortMapping> {
+    ports
+        .split(',')
+        .filter_map(parse_port_mapping)
+        .collect()
+}
+
+/// Parses `docker ps --format json` output (one JSON object per line)
+///
+/// # Errors
+///
+/// Returns an error if a line isn't valid JSON
+pub fn parse_ps_output(output: &str) -> Result<Vec<DockerContainer>, DockerError> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let ps: PsLine = serde_json::from_str(line)
+                .map_err(|e| DockerError::Failed(format!("Unexpected docker ps output: {e}")))?;
+            Ok(DockerContainer {
+                id: ps.id,
+                name: ps.names,
+                image: ps.image,
+                status: ps.status,
+                ports: parse_ports(&ps.ports),
+            })
+        })
+        .collect()
+}
+
+/// Lists running containers via the Docker CLI
+///
+/// # Errors
+///
+/// Returns [`DockerError::NotAvailable`] if the CLI is missing, the daemon
+/// isn't running or doesn't answer in time
+pub async fn list_containers() -> Result<Vec<DockerContainer>, DockerError> {
+    let docker = which::which("docker")
+        .map_err(|_| DockerError::NotAvailable("docker CLI not found in PATH".to_string()))?;
+
+    let output = tokio::process::Command::new(docker)
+        .args(["ps", "--format", "json"])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(DOCKER_PS_TIMEOUT, output)
+        .await
+        .map_err(|_| DockerError::NotAvailable("docker ps timed out".to_string()))?
+        .map_err(|e| DockerError::NotAvailable(format!("failed to run docker: {e}")))?;
+
+    if !output.status.success() {
+        // Most commonly "Cannot connect to the Docker daemon"
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(DockerError::NotAvailable(stderr.trim().to_string()));
+    }
+
+    parse_ps_output(&String::from_utf8_lossy(&output.stdout))
+}
@@ -0,0 +1,72 @@
+//! Unit tests for Docker container discovery
+//!
+//! Tests parsing `docker ps --format json` output.
+
+use crate::docker::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ps_output() {
+        let output = concat!(
+            r#"{"Command":"\"docker-entrypoint.s…\"","ID":"3f2a1b","Image":"postgres:16","Names":"db","Ports":"0.0.0.0:5432->5432/tcp, :::5432->5432/tcp","State":"running","Status":"Up 2 hours"}"#,
+            "\n",
+            r#"{"ID":"9c8d7e","Image":"redis:7","Names":"cache","Ports":"6379/tcp","State":"running","Status":"Up 5 minutes"}"#,
+            "\n",
+        );
+
+        let containers = parse_ps_output(output).unwrap();
+        assert_eq!(containers.len(), 2);
+
+        let db = &containers[0];
+        assert_eq!(db.name, "db");
+        assert_eq!(db.image, "postgres:16");
+        assert_eq!(db.status, "Up 2 hours");
+        assert_eq!(
+            db.ports,
+            vec![
+                PortMapping {
+                    host_ip: "0.0.0.0".to_string(),
+                    host_port: 5432,
+                    container_port: 5432,
+                    protocol: "tcp".to_string(),
+                },
+                PortMapping {
+                    host_ip: "::".to_string(),
+                    host_port: 5432,
+                    container_port: 5432,
+                    protocol: "tcp".to_string(),
+                },
+            ]
+        );
+
+        // Exposed but unpublished ports have no host mapping
+        assert!(containers[1].ports.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ps_output_empty() {
+        assert!(parse_ps_output("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_ps_output_rejects_garbage() {
+        assert!(matches!(parse_ps_output("not json"), Err(DockerError::Failed(_))));
+    }
+
+    #[test]
+    fn test_parse_port_mapping_different_ports() {
+        let mapping = parse_port_mapping("127.0.0.1:8080->80/udp").unwrap();
+        assert_eq!(mapping.host_ip, "127.0.0.1");
+        assert_eq!(mapping.host_port, 8080);
+        assert_eq!(mapping.container_port, 80);
+        assert_eq!(mapping.protocol, "udp");
+    }
+
+    #[test]
+    fn test_parse_port_mapping_skips_ranges() {
+        assert!(parse_port_mapping("0.0.0.0:8000-8001->8000-8001/tcp").is_none());
+    }
+}
@@ -0,0 +1,191 @@
+//! Persistent server log file with size-based rotation
+//!
+//! Server output is appended to `~/.side-ide/logs/server.log`. Once the file
+//! reaches [`MAX_LOG_FILE_BYTES`] it is rolled to `server.log.1` (older files
+//! shift up), keeping at most [`MAX_LOG_FILES`] files.
+
+use crate::common;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::OnceLock;
+
+/// Name of the active log file
+pub const LOG_FILE_NAME: &str = "server.log";
+
+/// Size at which the log file is rotated
+pub const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Log files kept, including the active one (`server.log`, `.1`, `.2`)
+pub const MAX_LOG_FILES: usize = 3;
+
+/// Shared sink for all server output, created on first use
+static SERVER_LOG_SINK: OnceLock<Option<LogFileSink>> = OnceLock::new();
+
+/// Directory holding the log files
+///
+/// # Errors
+///
+/// Returns an error if the home directory cannot be determined
+pub fn log_dir() -> Result<PathBuf, String> {
+    Ok(common::home_dir()?.join(".side-ide").join("logs"))
+}
+
+/// Path of the active server log file
+///
+/// # Errors
+///
+/// Returns an error if the home directory cannot be determined
+pub fn log_file_path() -> Result<PathBuf, String> {
+    Ok(log_dir()?.join(LOG_FILE_NAME))
+}
+
+/// Whether writing `incoming` more bytes to a file of `current_size` bytes
+/// should rotate it first
+///
+/// An empty file is never rotated, so a single oversized line still gets written.
+pub fn needs_rotation(current_size: u64, incoming: u64, max_bytes: u64) -> bool {
+    current_size > 0 && current_size + incoming > max_bytes
+}
+
+/// Path of rotated file `index` (e.g. `server.log.1`)
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{index}"));
+    path.with_file_name(name)
+}
+
+/// Shifts `path` to `path.1`, `path.1` to `path.2`, ..., dropping the oldest
+/// so that at most `max_files` files remain
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be renamed
+pub fn rotate(path: &Path, max_files: usize) -> std::io::Result<()> {
+    if max_files <= 1 {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    let _ = std::fs::remove_file(rotated_path(path, max_files - 1));
+    for index in (1..max_files - 1).rev() {
+        let from = rotated_path(path, index);
+        if from.exists() {
+            std::fs::rename(&from, rotated_path(path, index + 1))?;
+        }
+    }
+    if path.exists() {
+        std::fs::rename(path, rotated_path(path, 1))?;
+    }
+    Ok(())
+}
+
+/// Appends lines to a log file, rotating it when it grows too large
+pub struct LogFileWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl LogFileWriter {
+    /// Creates a writer; the file is opened on the first write
+    pub fn new(path: PathBuf, max_bytes: u64, max_files: usize) -> Self {
+        Self {
+            path,
+            max_bytes,
+            max_files,
+            file: None,
+            size: 0,
+        }
+    }
+
+    /// Appends `line` followed by a newline
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be rotated, opened or written
+    pub fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.file.is_none() {
+            self.open()?;
+        }
+
+        let incoming = line.len() as u64 + 1;
+        if needs_rotation(self.size, incoming, self.max_bytes) {
+            self.file = None;
+            rotate(&self.path, self.max_files)?;
+            self.open()?;
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            writeln!(file, "{line}")?;
+            self.size += incoming;
+        }
+        Ok(())
+    }
+
+    fn open(&mut self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+}
+
+/// Sends lines to a dedicated writer thread
+///
+/// Every reader (stdout, stderr, each server) shares the same thread, so
+/// writes to the file never interleave mid-line.
+#[derive(Clone)]
+pub struct LogFileSink {
+    tx: mpsc::Sender<String>,
+}
+
+impl LogFileSink {
+    /// Starts the writer thread for `writer`
+    pub fn spawn(mut writer: LogFileWriter) -> Self {
+        let (tx, rx) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            // Warn once per run of failures, so a full disk doesn't flood the
+            // log, and a subscriber that also writes here can't feed back into it
+            let mut failing = false;
+            for line in rx {
+                match writer.write_line(&line) {
+                    Ok(()) => failing = false,
+                    Err(e) if !failing => {
+                        failing = true;
+                        tracing::warn!(target: "Desktop", "Failed to write server log file: {e}");
+                    }
+                    Err(_) => {}
+                }
+            }
+        });
+        Self { tx }
+    }
+
+    /// Queues a line for writing
+    pub fn write_line(&self, line: &str) {
+        let _ = self.tx.send(line.to_string());
+    }
+}
+
+/// The shared sink writing to [`log_file_path`], or `None` if there is no home directory
+pub fn server_log_sink() -> Option<LogFileSink> {
+    SERVER_LOG_SINK
+        .get_or_init(|| {
+            let path = log_file_path().ok()?;
+            Some(LogFileSink::spawn(LogFileWriter::new(
+                path,
+                MAX_LOG_FILE_BYTES,
+                MAX_LOG_FILES,
+            )))
+        })
+        .clone()
+}
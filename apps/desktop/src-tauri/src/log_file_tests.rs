@@ -0,0 +1,77 @@
+//! Unit tests for the server log file
+//!
+//! Tests when rotation triggers and how rotated files are shifted.
+
+use crate::log_file::*;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn test_needs_rotation_only_when_limit_exceeded() {
+        assert!(!needs_rotation(0, 10, 100));
+        assert!(!needs_rotation(90, 10, 100));
+        assert!(needs_rotation(91, 10, 100));
+    }
+
+    #[test]
+    fn test_needs_rotation_never_rotates_empty_file() {
+        assert!(!needs_rotation(0, 1_000, 100));
+    }
+
+    #[test]
+    fn test_rotated_path_appends_index() {
+        let path = PathBuf::from("/tmp/logs/server.log");
+        assert_eq!(rotated_path(&path, 2), PathBuf::from("/tmp/logs/server.log.2"));
+    }
+
+    #[test]
+    fn test_rotate_shifts_files_and_drops_oldest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join(LOG_FILE_NAME);
+        std::fs::write(&path, "current").unwrap();
+        std::fs::write(rotated_path(&path, 1), "older").unwrap();
+        std::fs::write(rotated_path(&path, 2), "oldest").unwrap();
+
+        rotate(&path, 3).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 1)).unwrap(), "current");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 2)).unwrap(), "older");
+        assert!(!rotated_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn test_writer_rotates_when_file_is_full() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join(LOG_FILE_NAME);
+        let mut writer = LogFileWriter::new(path.clone(), 10, 3);
+
+        writer.write_line("first").unwrap();
+        writer.write_line("second").unwrap();
+        writer.write_line("third").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 1)).unwrap(), "second\n");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 2)).unwrap(), "first\n");
+    }
+
+    #[test]
+    fn test_writer_appends_to_existing_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join(LOG_FILE_NAME);
+        std::fs::write(&path, "earlier\n").unwrap();
+        let mut writer = LogFileWriter::new(path.clone(), 1_000, 3);
+
+        writer.write_line("later").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "earlier\nlater\n");
+        assert!(!rotated_path(&path, 1).exists());
+    }
+}
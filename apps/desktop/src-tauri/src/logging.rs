@@ -0,0 +1,213 @@
+//! Leveled logging for the backend
+//!
+//! A small [`tracing`] subscriber that prints events to stderr as
+//! `[Target] message` (the format the app has always used) and keeps the most
+//! recent ones in memory for the frontend. The level can be changed at runtime.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Maximum number of events kept for [`recent_logs`]
+pub const LOG_BUFFER_CAPACITY: usize = 1000;
+
+/// Level used until [`set_level`] is called
+pub const DEFAULT_LEVEL: Level = Level::INFO;
+
+/// The subscriber installed by [`init`]
+static COLLECTOR: OnceLock<LogSubscriber> = OnceLock::new();
+
+/// A captured log event
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct LogEntry {
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// `ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`
+    pub level: String,
+    /// Component that logged it (e.g. `Desktop`, `Server`, `Tunnel`)
+    pub target: String,
+    /// The formatted message, followed by any extra fields as `key=value`
+    pub message: String,
+}
+
+/// Filters, prints and buffers tracing events
+pub struct LogCollector {
+    level: AtomicUsize,
+    entries: Mutex<VecDeque<LogEntry>>,
+    capacity: usize,
+    echo: bool,
+    next_span: AtomicU64,
+}
+
+impl LogCollector {
+    /// Creates a collector keeping `capacity` events; `echo` also prints them to stderr
+    pub fn new(capacity: usize, echo: bool) -> Self {
+        Self {
+            level: AtomicUsize::new(level_index(DEFAULT_LEVEL)),
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            echo,
+            next_span: AtomicU64::new(1),
+        }
+    }
+
+    /// Sets the most verbose level that is recorded
+    pub fn set_level(&self, level: Level) {
+        self.level.store(level_index(level), Ordering::Relaxed);
+    }
+
+    /// The most verbose level that is recorded
+    pub fn level(&self) -> Level {
+        LEVELS[self.level.load(Ordering::Relaxed)]
+    }
+
+    /// Buffered events, oldest first
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    fn push(&self, entry: LogEntry) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+}
+
+/// Levels from least to most verbose
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+fn level_index(level: Level) -> usize {
+    LEVELS.iter().position(|l| *l == level).unwrap_or(2)
+}
+
+/// Parses a level name such as `info` or `DEBUG`
+///
+/// # Errors
+///
+/// Returns an error naming the valid levels
+pub fn parse_level(level: &str) -> Result<Level, String> {
+    level.trim().parse().map_err(|_| {
+        format!("Unknown log level '{level}'. Use error, warn, info, debug or trace.")
+    })
+}
+
+/// Collects an event's `message` and any other fields as ` key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+}
+
+/// [`Subscriber`] handle sharing one [`LogCollector`]
+#[derive(Clone)]
+pub struct LogSubscriber(pub Arc<LogCollector>);
+
+impl Subscriber for LogSubscriber {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // The level can change at runtime, so decide per event
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.0.level()
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.0.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let message = visitor.message + &visitor.fields;
+        let target = metadata.target();
+
+        if self.0.echo {
+            if *metadata.level() <= Level::INFO {
+                eprintln!("[{target}] {message}");
+            } else {
+                eprintln!("[{target}] {}: {message}", metadata.level());
+            }
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        self.0.push(LogEntry {
+            timestamp_ms,
+            level: metadata.level().to_string(),
+            target: target.to_string(),
+            message,
+        });
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// Installs the global subscriber; later calls do nothing
+pub fn init() {
+    let subscriber = COLLECTOR.get_or_init(|| {
+        LogSubscriber(Arc::new(LogCollector::new(LOG_BUFFER_CAPACITY, true)))
+    });
+    let _ = tracing::subscriber::set_global_default(subscriber.clone());
+}
+
+/// Changes the global log level
+///
+/// # Errors
+///
+/// Returns an error if logging hasn't been initialized
+pub fn set_level(level: Level) -> Result<(), String> {
+    let subscriber = COLLECTOR
+        .get()
+        .ok_or_else(|| "Logging is not initialized".to_string())?;
+    subscriber.0.set_level(level);
+    Ok(())
+}
+
+/// Recently logged events, oldest first
+pub fn recent_logs() -> Vec<LogEntry> {
+    COLLECTOR
+        .get()
+        .map(|subscriber| subscriber.0.entries())
+        .unwrap_or_default()
+}
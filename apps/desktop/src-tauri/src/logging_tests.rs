@@ -0,0 +1,66 @@
+//! Unit tests for backend logging
+//!
+//! Tests that the collector buffers events and honours the level filter.
+
+use crate::logging::*;
+use std::sync::Arc;
+use tracing::Level;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(level: Level, emit: impl FnOnce()) -> Vec<LogEntry> {
+        let collector = Arc::new(LogCollector::new(10, false));
+        collector.set_level(level);
+        tracing::subscriber::with_default(LogSubscriber(Arc::clone(&collector)), emit);
+        collector.entries()
+    }
+
+    #[test]
+    fn test_buffer_captures_events() {
+        let entries = collect(Level::INFO, || {
+            tracing::info!(target: "Desktop", "Server ready on port {}", 8787);
+            tracing::warn!(target: "Tunnel", attempt = 2, "Reconnecting");
+        });
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].target, "Desktop");
+        assert_eq!(entries[0].level, "INFO");
+        assert_eq!(entries[0].message, "Server ready on port 8787");
+        assert_eq!(entries[1].target, "Tunnel");
+        assert_eq!(entries[1].message, "Reconnecting attempt=2");
+    }
+
+    #[test]
+    fn test_level_filter_drops_verbose_events() {
+        let entries = collect(Level::WARN, || {
+            tracing::debug!(target: "Server", "hidden");
+            tracing::info!(target: "Server", "hidden");
+            tracing::error!(target: "Server", "shown");
+        });
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "shown");
+    }
+
+    #[test]
+    fn test_buffer_drops_oldest_when_full() {
+        let collector = Arc::new(LogCollector::new(2, false));
+        tracing::subscriber::with_default(LogSubscriber(Arc::clone(&collector)), || {
+            for i in 0..3 {
+                tracing::info!(target: "Desktop", "line {}", i);
+            }
+        });
+
+        let messages: Vec<String> = collector.entries().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec!["line 1", "line 2"]);
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("debug"), Ok(Level::DEBUG));
+        assert_eq!(parse_level(" WARN "), Ok(Level::WARN));
+        assert!(parse_level("verbose").is_err());
+    }
+}
@@ -6,36 +6,84 @@
 
 mod common;
 mod commands;
+mod config;
+mod diagnostics;
+mod docker;
+mod log_file;
+mod logging;
+mod panic_log;
 mod scanner;
 mod server;
 mod tailscale;
 mod remote_access;
+mod tray;
 mod tunnel;
+mod updater;
 mod window;
 
 // Test modules (only compiled when testing)
 #[cfg(test)]
 mod commands_tests;
 #[cfg(test)]
+mod common_tests;
+#[cfg(test)]
+mod config_tests;
+#[cfg(test)]
+mod diagnostics_tests;
+#[cfg(test)]
+mod docker_tests;
+#[cfg(test)]
+mod log_file_tests;
+#[cfg(test)]
+mod logging_tests;
+#[cfg(test)]
+mod panic_log_tests;
+#[cfg(test)]
 mod server_tests;
 #[cfg(test)]
 mod tailscale_tests;
 #[cfg(test)]
 mod remote_access_tests;
 #[cfg(test)]
+mod tray_tests;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
 mod tunnel_tests;
+#[cfg(test)]
+mod updater_tests;
+#[cfg(test)]
+mod window_tests;
 
+use std::collections::HashMap;
 use tokio::sync::Mutex as TokioMutex;
+use tokio_util::sync::CancellationToken;
 
-type ServerStateInner = TokioMutex<Option<server::ServerHandle>>;
+type ServerStateInner = TokioMutex<HashMap<u16, server::ServerHandle>>;
 type TunnelStateInner = TokioMutex<Option<tunnel::TunnelHandle>>;
+type ScanStateInner = TokioMutex<HashMap<String, CancellationToken>>;
+type ScanCacheStateInner = TokioMutex<scanner::ScanCache>;
+type RemoteAccessWatcherStateInner = TokioMutex<Option<CancellationToken>>;
+type UpdateStateInner = TokioMutex<Option<CancellationToken>>;
 
-/// Shared state for the server handle
+/// Shared state for managed server handles, keyed by port
 struct ServerState(ServerStateInner);
 
 /// Shared state for the tunnel handle
 struct TunnelState(TunnelStateInner);
 
+/// Shared state for cancellation tokens of in-progress scans, keyed by scan id
+struct ScanState(ScanStateInner);
+
+/// Shared cache of recent scan results
+struct ScanCacheState(ScanCacheStateInner);
+
+/// Cancellation token of the running Remote Access status watcher
+struct RemoteAccessWatcherState(RemoteAccessWatcherStateInner);
+
+/// Cancellation token of the update download in progress
+struct UpdateState(UpdateStateInner);
+
 /// Runs the Tauri application
 ///
 /// # Panics
@@ -43,12 +91,24 @@ struct TunnelState(TunnelStateInner);
 /// Panics if the Tauri builder context cannot be generated or if the application fails to run
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init();
+
     tauri::Builder::default()
-        .manage(ServerState(TokioMutex::new(None)))
+        // Must come first: a second launch hands its deep link to this instance
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            window::focus_main_window(app);
+        }))
+        .manage(ServerState(TokioMutex::new(HashMap::new())))
         .manage(TunnelState(TokioMutex::new(None)))
+        .manage(ScanState(TokioMutex::new(HashMap::new())))
+        .manage(ScanCacheState(TokioMutex::new(scanner::ScanCache::default())))
+        .manage(RemoteAccessWatcherState(TokioMutex::new(None)))
+        .manage(UpdateState(TokioMutex::new(None)))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_opener::init())
         .setup(|app| {
             // Setup window behavior and spawn server task
             // Errors here will NOT prevent app from starting
@@ -59,30 +119,95 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::start_server,
+            commands::precheck_server_start,
             commands::stop_server,
             commands::get_server_status,
+            commands::kill_process_on_port,
             commands::get_server_logs,
+            commands::get_server_logs_tail,
+            commands::get_log_file_path,
+            commands::open_data_directory,
+            commands::open_server_logs_directory,
+            commands::set_log_level,
+            commands::get_recent_logs,
+            commands::collect_diagnostics,
+            commands::wait_for_server_ready,
+            commands::get_server_resource_usage,
+            commands::get_server_settings,
+            commands::set_server_path,
             commands::start_tunnel,
             commands::stop_tunnel,
             commands::get_tunnel_status,
+            commands::get_tunnel_password,
+            commands::get_tunnel_logs,
             commands::get_tailscale_status,
+            commands::refresh_tailscale_path,
+            commands::tailscale_login,
+            commands::tailscale_logout,
             commands::get_remote_access_status,
+            commands::get_remote_access_url,
             commands::get_remote_access_settings,
             commands::set_remote_access_settings,
+            commands::check_remote_access_prerequisites,
             commands::start_remote_access_https,
             commands::stop_remote_access,
+            commands::start_remote_access_watcher,
+            commands::stop_remote_access_watcher,
+            commands::config_get,
+            commands::config_set,
+            commands::config_list,
+            commands::get_system_status,
+            commands::shutdown_all,
             commands::check_environment,
+            commands::validate_environment,
+            commands::get_version_info,
+            commands::check_for_updates,
+            commands::download_and_install,
+            commands::cancel_update,
+            commands::get_updater_settings,
+            commands::set_updater_settings,
+            commands::set_update_channel,
+            commands::dismiss_update,
+            commands::refresh_command_paths,
             commands::check_port,
             commands::scan_local_servers,
             commands::get_mcp_servers,
+            commands::take_launch_deep_links,
+            commands::test_connectivity,
             commands::scan_local_servers_advanced,
+            commands::scan_host_advanced,
+            commands::scan_host_streaming,
+            commands::cancel_scan,
+            commands::clear_scan_cache,
+            commands::scan_subnet,
             commands::check_nmap_available,
+            commands::refresh_nmap_status,
+            commands::list_docker_containers,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
+/// Env var that keeps the console attached in release builds on Windows
+///
+/// Set it (to any value) to see log output while debugging startup issues.
+#[cfg(all(target_os = "windows", not(debug_assertions)))]
+const SHOW_CONSOLE_ENV: &str = "S_IDE_SHOW_CONSOLE";
+
 fn main() {
+    // Record panics in panic.log so crashes can be reported
+    panic_log::install_hook();
+
+    // Detach from the console so release builds don't flash one, unless
+    // S_IDE_SHOW_CONSOLE asks to keep it
+    #[cfg(all(target_os = "windows", not(debug_assertions)))]
+    if std::env::var_os(SHOW_CONSOLE_ENV).is_none() {
+        // SAFETY: FreeConsole has no memory-safety preconditions
+        unsafe {
+            windows_sys::Win32::System::Console::FreeConsole();
+        }
+    }
+
     // Run the application with basic error handling
     if let Err(e) = std::panic::catch_unwind(|| {
         run();
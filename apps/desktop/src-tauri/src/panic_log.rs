@@ -0,0 +1,105 @@
+//! Crash log written by the panic hook
+//!
+//! Panics are appended with a UTC timestamp to `panic.log` in the platform's
+//! local data dir (`%LOCALAPPDATA%\S-IDE` on Windows, `~/Library/Application
+//! Support/S-IDE` on macOS, `~/.local/share/S-IDE` on Linux). The file is
+//! rotated to `panic.log.1` once it reaches [`MAX_PANIC_LOG_BYTES`].
+
+use crate::log_file;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory name under the platform data dir
+pub const APP_DATA_DIR_NAME: &str = "S-IDE";
+
+/// Name of the active panic log
+pub const PANIC_LOG_FILE_NAME: &str = "panic.log";
+
+/// Size at which the panic log is rotated
+pub const MAX_PANIC_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Panic logs kept, including the active one (`panic.log`, `.1`)
+pub const MAX_PANIC_LOG_FILES: usize = 2;
+
+/// Per-user app data dir for this platform, or `None` if it can't be found
+pub fn app_data_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(APP_DATA_DIR_NAME))
+}
+
+/// Path of the active panic log
+pub fn panic_log_path() -> Option<PathBuf> {
+    app_data_dir().map(|dir| dir.join(PANIC_LOG_FILE_NAME))
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC timestamp
+pub fn format_utc_timestamp(secs: u64) -> String {
+    let days = secs / 86_400;
+    let time = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Formats one panic log entry
+pub fn format_entry(timestamp_secs: u64, message: &str) -> String {
+    format!("[{}] {}\n", format_utc_timestamp(timestamp_secs), message.trim_end())
+}
+
+/// Appends `entry` to the log at `path`, rotating it first if it would grow
+/// past `max_bytes`
+///
+/// # Errors
+///
+/// Returns an error if the directory or file can't be written
+pub fn append_entry(path: &Path, entry: &str, max_bytes: u64) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let size = std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    if log_file::needs_rotation(size, entry.len() as u64, max_bytes) {
+        log_file::rotate(path, MAX_PANIC_LOG_FILES)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(entry.as_bytes())
+}
+
+/// Installs a panic hook that records panics in the panic log
+///
+/// The previous hook still runs afterwards, so panics are printed as before.
+pub fn install_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(path) = panic_log_path() {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default();
+            let thread = std::thread::current();
+            let message = format!(
+                "thread '{}' {info}\n{}",
+                thread.name().unwrap_or("<unnamed>"),
+                std::backtrace::Backtrace::force_capture()
+            );
+            let _ = append_entry(&path, &format_entry(now, &message), MAX_PANIC_LOG_BYTES);
+        }
+        previous(info);
+    }));
+}
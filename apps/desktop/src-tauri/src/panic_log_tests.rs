@@ -0,0 +1,81 @@
+//! Unit tests for the panic log
+//!
+//! Tests the per-platform path, timestamps, appending and rotation.
+
+use crate::panic_log::*;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_panic_log_path_windows() {
+        let local_app_data = PathBuf::from(std::env::var_os("LOCALAPPDATA").unwrap());
+        assert_eq!(panic_log_path(), Some(local_app_data.join("S-IDE").join("panic.log")));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_panic_log_path_macos() {
+        let home = PathBuf::from(std::env::var_os("HOME").unwrap());
+        assert_eq!(
+            panic_log_path(),
+            Some(home.join("Library/Application Support").join("S-IDE").join("panic.log"))
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_panic_log_path_linux() {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .unwrap_or_else(|| PathBuf::from(std::env::var_os("HOME").unwrap()).join(".local/share"));
+        assert_eq!(panic_log_path(), Some(data_home.join("S-IDE").join("panic.log")));
+    }
+
+    #[test]
+    fn test_format_utc_timestamp() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc_timestamp(1_792_220_583), "2026-10-17T07:03:03Z");
+    }
+
+    #[test]
+    fn test_format_entry() {
+        assert_eq!(
+            format_entry(0, "thread 'main' panicked at src/main.rs:1:1\n"),
+            "[1970-01-01T00:00:00Z] thread 'main' panicked at src/main.rs:1:1\n"
+        );
+    }
+
+    #[test]
+    fn test_append_entry_keeps_earlier_panics() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join("nested").join("panic.log");
+
+        append_entry(&path, "first\n", 1024).unwrap();
+        append_entry(&path, "second\n", 1024).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+    }
+
+    #[test]
+    fn test_append_entry_rotates_when_full() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join("panic.log");
+
+        append_entry(&path, "first\n", 10).unwrap();
+        append_entry(&path, "second\n", 10).unwrap();
+        append_entry(&path, "third\n", 10).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(std::fs::read_to_string(dir.join("panic.log.1")).unwrap(), "second\n");
+        assert!(!dir.join("panic.log.2").exists());
+    }
+}
@@ -2,12 +2,35 @@
 //!
 //! We use `tailscale serve` to provide HTTPS access to the local S-IDE server.
 
+use crate::common;
+use crate::config;
 use crate::tailscale;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 const DEV_WEB_PORTS: [u16; 3] = [5173, 1420, 4173];
 
+/// HTTPS ports tried in order when no serve port is requested.
+///
+/// Prefer 443 (no port in URL). If it is already taken, fall back to 8443.
+const DEFAULT_SERVE_PORTS: [u16; 2] = [443, 8443];
+
+/// Event emitted by the watcher when the serve status changes.
+pub const STATUS_CHANGED_EVENT: &str = "remote-access-status-changed";
+
+/// Set while a serve mapping started by this app is active
+static ENABLED_BY_APP: AtomicBool = AtomicBool::new(false);
+
+/// Whether Remote Access was enabled by this app rather than by hand
+pub fn enabled_by_app() -> bool {
+    ENABLED_BY_APP.load(Ordering::SeqCst)
+}
+
+/// How often the watcher polls `tailscale serve status`.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteAccessSettings {
     #[serde(default)]
@@ -33,53 +56,54 @@ pub struct RemoteAccessStatus {
     pub settings: RemoteAccessSettings,
 }
 
-fn home_dir() -> Result<std::path::PathBuf, String> {
-    #[cfg(target_os = "windows")]
-    {
-        if let Ok(p) = std::env::var("USERPROFILE") {
-            return Ok(std::path::PathBuf::from(p));
+impl RemoteAccessStatus {
+    /// Shareable HTTPS URL for the served UI, if Serve is on
+    ///
+    /// Prefers the URL parsed from `tailscale serve status`, falling back to
+    /// this device's MagicDNS name when Serve is enabled but the URL couldn't
+    /// be parsed.
+    pub fn share_url(&self) -> Option<String> {
+        if !self.serve_enabled {
+            return None;
         }
+        if let Some(url) = self.serve_url.as_ref().filter(|url| !url.is_empty()) {
+            return Some(url.clone());
+        }
+        self.self_dns_name
+            .as_deref()
+            .map(|name| name.trim_end_matches('.'))
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("https://{name}/"))
     }
-    if let Ok(p) = std::env::var("HOME") {
-        return Ok(std::path::PathBuf::from(p));
-    }
-    Err("Could not determine home directory".to_string())
 }
 
-fn settings_path() -> Result<std::path::PathBuf, String> {
-    Ok(home_dir()?.join(".side-ide").join("remote-access.json"))
+/// Where settings were kept before they moved to the app config
+fn legacy_settings_path() -> Result<std::path::PathBuf, String> {
+    Ok(common::home_dir()?.join(".side-ide").join("remote-access.json"))
 }
 
-pub async fn load_settings() -> RemoteAccessSettings {
-    let path = match settings_path() {
-        Ok(p) => p,
-        Err(_) => return RemoteAccessSettings::default(),
-    };
-
-    // Ensure parent exists
-    if let Some(parent) = path.parent() {
-        let _ = tokio::fs::create_dir_all(parent).await;
-    }
+/// Auto-start as saved in `remote-access.json`, for migrating to the app config
+pub fn load_legacy_auto_start() -> bool {
+    legacy_settings_path()
+        .and_then(|path| std::fs::read_to_string(path).map_err(|e| e.to_string()))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<RemoteAccessSettings>(&raw).ok())
+        .unwrap_or_default()
+        .auto_start
+}
 
-    match tokio::fs::read_to_string(&path).await {
-        Ok(s) => serde_json::from_str::<RemoteAccessSettings>(&s).unwrap_or_default(),
-        Err(_) => RemoteAccessSettings::default(),
+/// Loads the settings from the app config
+pub async fn load_settings() -> RemoteAccessSettings {
+    RemoteAccessSettings {
+        auto_start: config::load().auto_start_remote_access,
     }
 }
 
+/// Saves the settings to the app config
 pub async fn save_settings(settings: &RemoteAccessSettings) -> Result<(), String> {
-    let path = settings_path()?;
-    if let Some(parent) = path.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .map_err(|e| format!("Failed to create config dir: {e}"))?;
-    }
-
-    let data = serde_json::to_string_pretty(settings).map_err(|e| format!("Invalid JSON: {e}"))?;
-    tokio::fs::write(&path, data)
-        .await
-        .map_err(|e| format!("Failed to write settings: {e}"))?;
-    Ok(())
+    let mut app_config = config::load();
+    app_config.auto_start_remote_access = settings.auto_start;
+    config::save(&app_config)
 }
 
 async fn run_tailscale(args: &[&str]) -> Result<std::process::Output, String> {
@@ -169,6 +193,60 @@ pub async fn get_serve_status() -> Result<(bool, Option<String>), String> {
     Ok((is_serve_enabled_from_text(&combined), pick_serve_url_from_text(&combined)))
 }
 
+/// Serve state reported by [`STATUS_CHANGED_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ServeStatus {
+    pub serve_enabled: bool,
+    pub serve_url: Option<String>,
+}
+
+/// Debounced change detection for the serve status watcher.
+///
+/// A reading only counts once it has been seen twice in a row, so a single
+/// transient read doesn't cause two events. The first confirmed reading is the
+/// baseline and is not reported.
+#[derive(Debug, Default)]
+pub(crate) struct ServeStatusTracker {
+    current: Option<ServeStatus>,
+    pending: Option<ServeStatus>,
+}
+
+impl ServeStatusTracker {
+    /// Record a reading, returning it when it is a confirmed change.
+    pub(crate) fn observe(&mut self, reading: ServeStatus) -> Option<ServeStatus> {
+        let confirmed = self.pending.as_ref() == Some(&reading);
+        self.pending = Some(reading.clone());
+        if !confirmed || self.current.as_ref() == Some(&reading) {
+            return None;
+        }
+
+        let is_baseline = self.current.is_none();
+        self.current = Some(reading.clone());
+        (!is_baseline).then_some(reading)
+    }
+}
+
+/// Poll the serve status until `token` is cancelled, calling `on_change` for
+/// each confirmed change.
+pub async fn watch_serve_status<F>(token: CancellationToken, interval: Duration, on_change: F)
+where
+    F: Fn(ServeStatus),
+{
+    let mut tracker = ServeStatusTracker::default();
+    loop {
+        if let Ok((serve_enabled, serve_url)) = get_serve_status().await {
+            if let Some(changed) = tracker.observe(ServeStatus { serve_enabled, serve_url }) {
+                on_change(changed);
+            }
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
+
 pub(crate) fn select_remote_access_target_port(
     server_port: u16,
     server_serves_ui: bool,
@@ -204,16 +282,165 @@ async fn serves_ui_root(port: u16) -> bool {
     }
 }
 
-pub async fn start_https(local_port: u16) -> Result<(), String> {
+/// Check that a `tailscale serve` mount path is absolute (e.g. `/ide`).
+pub(crate) fn validate_mount_path(path: &str) -> Result<(), String> {
+    if !path.starts_with('/') {
+        return Err(format!("Mount path must start with '/': {path}"));
+    }
+    if path.chars().any(char::is_whitespace) {
+        return Err(format!("Mount path must not contain whitespace: {path}"));
+    }
+    Ok(())
+}
+
+/// HTTPS ports to try: only the requested one, or the 443/8443 fallback ladder.
+pub(crate) fn serve_port_candidates(serve_port: Option<u16>) -> Vec<u16> {
+    match serve_port {
+        Some(port) => vec![port],
+        None => DEFAULT_SERVE_PORTS.to_vec(),
+    }
+}
+
+/// Build the `tailscale serve` arguments exposing `target_port` over HTTPS.
+pub(crate) fn serve_args(serve_port: u16, target_port: u16, mount_path: Option<&str>) -> Vec<String> {
+    let mut args: Vec<String> = ["serve", "--yes", "--bg", "--https"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    args.push(serve_port.to_string());
+    if let Some(path) = mount_path {
+        args.push("--set-path".to_string());
+        args.push(path.to_string());
+    }
+    args.push(target_port.to_string());
+    args
+}
+
+/// Something `tailscale serve --https` needs before it can work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Prerequisite {
+    /// The `tailscale` CLI is installed.
+    Installed,
+    /// This device is logged in and connected (backend state `Running`).
+    LoggedIn,
+    /// MagicDNS is enabled for the tailnet.
+    MagicDns,
+    /// HTTPS certificates are enabled for the tailnet.
+    HttpsCertificates,
+}
+
+impl Prerequisite {
+    /// Every prerequisite, in the order they should be fixed.
+    pub const ALL: [Prerequisite; 4] = [
+        Prerequisite::Installed,
+        Prerequisite::LoggedIn,
+        Prerequisite::MagicDns,
+        Prerequisite::HttpsCertificates,
+    ];
+
+    /// Whether `status` satisfies this prerequisite.
+    pub fn is_met(self, status: &tailscale::TailscaleStatusSummary) -> bool {
+        match self {
+            Prerequisite::Installed => status.installed,
+            Prerequisite::LoggedIn => status.backend_state.as_deref() == Some("Running"),
+            Prerequisite::MagicDns => status.magic_dns_enabled,
+            Prerequisite::HttpsCertificates => status.https_enabled,
+        }
+    }
+
+    /// What the user should do when this prerequisite isn't met.
+    pub fn remediation(self) -> &'static str {
+        match self {
+            Prerequisite::Installed => "Tailscale is not installed. Install it from https://tailscale.com/download.",
+            Prerequisite::LoggedIn => {
+                "Tailscale is not logged in or not connected. Log in to Tailscale and make sure it is running."
+            }
+            Prerequisite::MagicDns => {
+                "MagicDNS is disabled for your tailnet. Enable it under DNS in the Tailscale admin console."
+            }
+            Prerequisite::HttpsCertificates => {
+                "HTTPS certificates are disabled for your tailnet. Enable HTTPS under DNS in the Tailscale admin console."
+            }
+        }
+    }
+}
+
+/// Result of one prerequisite check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrerequisiteCheck {
+    pub prerequisite: Prerequisite,
+    pub ok: bool,
+    /// How to fix it; `None` when the check passed.
+    pub remediation: Option<String>,
+}
+
+/// Whether Remote Access can be enabled, with a check per prerequisite.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RemoteAccessReadiness {
+    pub ready: bool,
+    pub checks: Vec<PrerequisiteCheck>,
+}
+
+impl RemoteAccessReadiness {
+    /// Checks every prerequisite against a Tailscale status.
+    pub fn from_status(status: &tailscale::TailscaleStatusSummary) -> Self {
+        let checks: Vec<PrerequisiteCheck> = Prerequisite::ALL
+            .into_iter()
+            .map(|prerequisite| {
+                let ok = prerequisite.is_met(status);
+                PrerequisiteCheck {
+                    prerequisite,
+                    ok,
+                    remediation: (!ok).then(|| prerequisite.remediation().to_string()),
+                }
+            })
+            .collect();
+        Self { ready: checks.iter().all(|check| check.ok), checks }
+    }
+
+    /// Error for the first failing check, which is the one to fix first.
+    pub fn error(&self) -> Option<String> {
+        self.checks
+            .iter()
+            .find_map(|check| check.remediation.as_ref())
+            .map(|remediation| format!("Remote Access is not ready: {remediation}"))
+    }
+}
+
+/// Checks that Tailscale is installed, logged in and has MagicDNS and HTTPS on.
+pub async fn check_prerequisites() -> RemoteAccessReadiness {
+    RemoteAccessReadiness::from_status(&tailscale::get_status_summary().await)
+}
+
+pub async fn start_https(
+    local_port: u16,
+    serve_port: Option<u16>,
+    mount_path: Option<String>,
+) -> Result<(), String> {
+    if let Some(error) = check_prerequisites().await.error() {
+        return Err(error);
+    }
+
+    if let Some(path) = &mount_path {
+        validate_mount_path(path)?;
+        let version = tailscale::get_version().await;
+        if !tailscale::version_at_least(version.as_deref(), tailscale::MIN_SERVE_SET_PATH_VERSION) {
+            let (major, minor, patch) = tailscale::MIN_SERVE_SET_PATH_VERSION;
+            return Err(format!(
+                "Serving under a path requires Tailscale {major}.{minor}.{patch} or newer (installed: {})",
+                version.unwrap_or_default()
+            ));
+        }
+    }
+
     // Ensure we replace stale serve mappings deterministically.
     // Older mappings (e.g. 443 -> 8787) can survive between runs.
     let _ = run_tailscale(&["serve", "reset"]).await;
 
     // Newer Tailscale CLI uses: `tailscale serve --bg --https <port> <target>`
     // where <target> can be a port number (e.g. 8787) for http://127.0.0.1:<target>.
-    //
-    // Prefer 443 (no port in URL). If it is already taken, fall back to 8443.
-    let candidates = [443u16, 8443u16];
+    let candidates = serve_port_candidates(serve_port);
 
     let server_serves_ui = serves_ui_root(local_port).await;
     let mut dev_ports_with_ui = Vec::new();
@@ -225,20 +452,21 @@ pub async fn start_https(local_port: u16) -> Result<(), String> {
     let target_port =
         select_remote_access_target_port(local_port, server_serves_ui, &dev_ports_with_ui)?;
 
-    for serve_port in candidates {
-        let serve_port_s = serve_port.to_string();
-        let target_s = target_port.to_string();
-        let output =
-            run_tailscale(&["serve", "--yes", "--bg", "--https", &serve_port_s, &target_s]).await?;
+    for (i, &candidate) in candidates.iter().enumerate() {
+        let args = serve_args(candidate, target_port, mount_path.as_deref());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = run_tailscale(&args).await?;
         if output.status.success() {
+            ENABLED_BY_APP.store(true, Ordering::SeqCst);
             return Ok(());
         }
 
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
 
-        // If 443 is already used, try the fallback port.
-        if serve_port == 443 && (stderr.contains("listener already exists") || stdout.contains("listener already exists"))
+        // If the port is already used, try the next fallback port.
+        let has_fallback = i + 1 < candidates.len();
+        if has_fallback && (stderr.contains("listener already exists") || stdout.contains("listener already exists"))
         {
             continue;
         }
@@ -246,13 +474,15 @@ pub async fn start_https(local_port: u16) -> Result<(), String> {
         return Err(format!("tailscale serve failed: {stdout}\n{stderr}").trim().to_string());
     }
 
-    Err("tailscale serve failed: could not bind 443 or 8443".to_string())
+    let ports: Vec<String> = candidates.iter().map(u16::to_string).collect();
+    Err(format!("tailscale serve failed: could not bind {}", ports.join(" or ")))
 }
 
 pub async fn stop() -> Result<(), String> {
     // Reset all serve config.
     let output = run_tailscale(&["serve", "reset"]).await?;
     if output.status.success() {
+        ENABLED_BY_APP.store(false, Ordering::SeqCst);
         return Ok(());
     }
     let stdout = String::from_utf8_lossy(&output.stdout);
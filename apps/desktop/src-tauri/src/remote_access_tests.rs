@@ -1,6 +1,12 @@
-//! Unit tests for Remote Access target port selection.
+//! Unit tests for Remote Access target port selection, serve arguments,
+//! status change detection, the shareable URL and prerequisite checks.
 
-use crate::remote_access::select_remote_access_target_port;
+use crate::remote_access::{
+    select_remote_access_target_port, serve_args, serve_port_candidates, validate_mount_path,
+    Prerequisite, RemoteAccessReadiness, RemoteAccessSettings, RemoteAccessStatus, ServeStatus,
+    ServeStatusTracker,
+};
+use crate::tailscale::TailscaleStatusSummary;
 
 #[test]
 fn keeps_server_port_when_server_serves_ui() {
@@ -25,3 +31,185 @@ fn returns_error_when_no_ui_candidate_found() {
     let chosen = select_remote_access_target_port(8787, false, &[]);
     assert!(chosen.is_err());
 }
+
+#[test]
+fn serve_args_without_path() {
+    assert_eq!(
+        serve_args(443, 8787, None),
+        vec!["serve", "--yes", "--bg", "--https", "443", "8787"]
+    );
+}
+
+#[test]
+fn serve_args_with_path_and_port() {
+    assert_eq!(
+        serve_args(10000, 8787, Some("/ide")),
+        vec!["serve", "--yes", "--bg", "--https", "10000", "--set-path", "/ide", "8787"]
+    );
+}
+
+#[test]
+fn serve_port_candidates_fall_back_only_without_port() {
+    assert_eq!(serve_port_candidates(None), vec![443, 8443]);
+    assert_eq!(serve_port_candidates(Some(10000)), vec![10000]);
+}
+
+#[test]
+fn mount_path_must_start_with_slash() {
+    assert!(validate_mount_path("/ide").is_ok());
+    assert!(validate_mount_path("/").is_ok());
+    assert!(validate_mount_path("ide").is_err());
+    assert!(validate_mount_path("").is_err());
+    assert!(validate_mount_path("/my ide").is_err());
+}
+
+fn serve_status(enabled: bool, url: Option<&str>) -> ServeStatus {
+    ServeStatus { serve_enabled: enabled, serve_url: url.map(str::to_string) }
+}
+
+#[test]
+fn status_tracker_emits_confirmed_changes_only() {
+    let off = serve_status(false, None);
+    let on = serve_status(true, Some("https://home-pc.tailnet-123.ts.net/"));
+    let mut tracker = ServeStatusTracker::default();
+
+    // The first confirmed reading is the baseline
+    assert_eq!(tracker.observe(off.clone()), None);
+    assert_eq!(tracker.observe(off.clone()), None);
+
+    // A change needs two identical reads in a row
+    assert_eq!(tracker.observe(on.clone()), None);
+    assert_eq!(tracker.observe(on.clone()), Some(on.clone()));
+
+    // No repeat event while the status stays the same
+    assert_eq!(tracker.observe(on.clone()), None);
+}
+
+#[test]
+fn status_tracker_ignores_transient_flaps() {
+    let on = serve_status(true, Some("https://home-pc.tailnet-123.ts.net/"));
+    let other_url = serve_status(true, Some("https://home-pc.tailnet-123.ts.net:8443/"));
+    let mut tracker = ServeStatusTracker::default();
+
+    tracker.observe(on.clone());
+    tracker.observe(on.clone());
+
+    assert_eq!(tracker.observe(serve_status(false, None)), None);
+    assert_eq!(tracker.observe(on.clone()), None);
+    assert_eq!(tracker.observe(on.clone()), None);
+
+    // A URL change alone is a change
+    assert_eq!(tracker.observe(other_url.clone()), None);
+    assert_eq!(tracker.observe(other_url.clone()), Some(other_url));
+}
+
+fn remote_access_status(serve_enabled: bool, serve_url: Option<&str>, dns_name: Option<&str>) -> RemoteAccessStatus {
+    RemoteAccessStatus {
+        installed: true,
+        backend_state: Some("Running".to_string()),
+        auth_url: None,
+        self_hostname: Some("devbox".to_string()),
+        self_dns_name: dns_name.map(str::to_string),
+        tailscale_ips: vec!["100.64.0.1".to_string()],
+        serve_enabled,
+        serve_url: serve_url.map(str::to_string),
+        settings: RemoteAccessSettings::default(),
+    }
+}
+
+#[test]
+fn share_url_prefers_serve_url() {
+    let status = remote_access_status(
+        true,
+        Some("https://devbox.tail1234.ts.net:8443/"),
+        Some("devbox.tail1234.ts.net."),
+    );
+    assert_eq!(status.share_url().as_deref(), Some("https://devbox.tail1234.ts.net:8443/"));
+}
+
+#[test]
+fn share_url_falls_back_to_dns_name() {
+    let status = remote_access_status(true, None, Some("devbox.tail1234.ts.net."));
+    assert_eq!(status.share_url().as_deref(), Some("https://devbox.tail1234.ts.net/"));
+
+    let status = remote_access_status(true, Some(""), Some("devbox.tail1234.ts.net"));
+    assert_eq!(status.share_url().as_deref(), Some("https://devbox.tail1234.ts.net/"));
+}
+
+#[test]
+fn share_url_is_none_without_serve_or_name() {
+    let status = remote_access_status(false, Some("https://devbox.tail1234.ts.net/"), Some("devbox.tail1234.ts.net."));
+    assert_eq!(status.share_url(), None);
+
+    assert_eq!(remote_access_status(true, None, None).share_url(), None);
+    assert_eq!(remote_access_status(true, None, Some(".")).share_url(), None);
+}
+
+fn tailscale_status(
+    installed: bool,
+    backend_state: Option<&str>,
+    magic_dns_enabled: bool,
+    https_enabled: bool,
+) -> TailscaleStatusSummary {
+    TailscaleStatusSummary {
+        installed,
+        version: None,
+        backend_state: backend_state.map(str::to_string),
+        auth_url: None,
+        self_hostname: None,
+        self_dns_name: None,
+        tailscale_ips: vec![],
+        magic_dns_enabled,
+        https_enabled,
+        peers: vec![],
+    }
+}
+
+fn failing(readiness: &RemoteAccessReadiness) -> Vec<Prerequisite> {
+    readiness.checks.iter().filter(|check| !check.ok).map(|check| check.prerequisite).collect()
+}
+
+#[test]
+fn readiness_passes_when_all_prerequisites_met() {
+    let readiness = RemoteAccessReadiness::from_status(&tailscale_status(true, Some("Running"), true, true));
+    assert!(readiness.ready);
+    assert!(readiness.checks.iter().all(|check| check.ok && check.remediation.is_none()));
+    assert_eq!(readiness.error(), None);
+}
+
+#[test]
+fn readiness_maps_each_failure_to_its_remediation() {
+    let cases = [
+        (tailscale_status(false, None, false, false), Prerequisite::Installed, "Tailscale is not installed"),
+        (tailscale_status(true, Some("NeedsLogin"), true, true), Prerequisite::LoggedIn, "not logged in"),
+        (tailscale_status(true, None, true, true), Prerequisite::LoggedIn, "not logged in"),
+        (tailscale_status(true, Some("Running"), false, true), Prerequisite::MagicDns, "MagicDNS is disabled"),
+        (
+            tailscale_status(true, Some("Running"), true, false),
+            Prerequisite::HttpsCertificates,
+            "HTTPS certificates are disabled",
+        ),
+    ];
+
+    for (status, prerequisite, message) in cases {
+        let readiness = RemoteAccessReadiness::from_status(&status);
+        assert!(!readiness.ready);
+        assert_eq!(failing(&readiness)[0], prerequisite);
+
+        let check = readiness.checks.iter().find(|check| check.prerequisite == prerequisite).unwrap();
+        assert_eq!(check.remediation.as_deref(), Some(prerequisite.remediation()));
+        let error = readiness.error().unwrap();
+        assert!(error.starts_with("Remote Access is not ready: "), "{error}");
+        assert!(error.contains(message), "{error}");
+    }
+}
+
+#[test]
+fn readiness_error_reports_first_failure() {
+    let readiness = RemoteAccessReadiness::from_status(&tailscale_status(true, Some("Stopped"), false, false));
+    assert_eq!(
+        failing(&readiness),
+        vec![Prerequisite::LoggedIn, Prerequisite::MagicDns, Prerequisite::HttpsCertificates]
+    );
+    assert!(readiness.error().unwrap().contains("not logged in"));
+}
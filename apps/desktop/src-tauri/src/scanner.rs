@@ -3,20 +3,40 @@
 //! Provides port scanning, OS detection, and service version detection
 //! using pure Rust with tokio for TCP scanning, with nmap subprocess fallback.
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
 use std::time::Duration;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::CryptoProvider;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{DigitallySignedStruct, SignatureScheme};
+use tokio_util::sync::CancellationToken;
 
 /// Result of a network scan on a single host
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
     /// Target host address
     pub host: String,
+    /// Reverse DNS (PTR) name of the host, if it has one
+    #[serde(default)]
+    pub hostname: Option<String>,
     /// Scanned ports with their status
     pub ports: Vec<PortInfo>,
     /// Guessed operating system (if OS detection enabled)
     pub os_guess: Option<String>,
+    /// Confidence in the OS guess: `"high"` or `"low"`
+    #[serde(default)]
+    pub os_confidence: Option<String>,
     /// Detected services with version info
     pub services: Vec<ServiceInfo>,
+    /// Whether this result was served from the scan cache
+    #[serde(default)]
+    pub cached: bool,
 }
 
 /// Detailed information about a single port
@@ -32,18 +52,60 @@ pub struct PortInfo {
     pub service: Option<String>,
     /// Service version (if version detection enabled)
     pub version: Option<String>,
+    /// Address family the port was found on, when the host is an IP literal
+    #[serde(default)]
+    pub address_family: Option<AddressFamily>,
+}
+
+/// IP version a port was reached over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
+    /// Reachable over both, e.g. a dual-stack listener on localhost
+    Dual,
+}
+
+impl AddressFamily {
+    /// Family of an IP literal, or `None` for hostnames
+    pub fn of_host(host: &str) -> Option<Self> {
+        match host.parse::<std::net::IpAddr>().ok()? {
+            std::net::IpAddr::V4(_) => Some(Self::Ipv4),
+            std::net::IpAddr::V6(_) => Some(Self::Ipv6),
+        }
+    }
 }
 
 /// Port scanning status
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PortStatus {
     Open,
     Closed,
     Filtered,
+    /// UDP port that sent no reply: either open and silent, or firewalled
+    OpenFiltered,
+}
+
+/// Transport protocol to probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    /// Lowercase protocol name as reported in `PortInfo.protocol`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        }
+    }
 }
 
 /// Service information with version detection
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceInfo {
     /// Service name
     pub name: String,
@@ -51,6 +113,183 @@ pub struct ServiceInfo {
     pub version: Option<String>,
     /// Additional service info
     pub info: Option<String>,
+    /// TLS certificate expiry (notAfter, RFC 3339 UTC) for TLS services
+    #[serde(default)]
+    pub cert_expiry: Option<String>,
+    /// Reverse proxy in front of an HTTP service, from its `Server`/`Via` headers
+    #[serde(default)]
+    pub proxy: Option<String>,
+}
+
+/// Event emitted after each scan batch completes
+pub const SCAN_PROGRESS_EVENT: &str = "scan-progress";
+
+/// Progress payload for [`SCAN_PROGRESS_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanProgress {
+    /// Number of probes completed so far
+    pub scanned: usize,
+    /// Total number of probes in this scan
+    pub total: usize,
+    /// Number of open ports found so far
+    pub open_count: usize,
+}
+
+/// Event emitted by streaming scans for each open port as it is found
+pub const SCAN_PORT_FOUND_EVENT: &str = "scan-port-found";
+
+/// Event emitted once a streaming scan has finished
+pub const SCAN_COMPLETE_EVENT: &str = "scan-complete";
+
+/// Payload for [`SCAN_PORT_FOUND_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+pub struct PortFound {
+    /// Host the port was found on, to tell multi-host streams apart
+    pub host: String,
+    /// The open port
+    pub port: PortInfo,
+}
+
+/// Payload for [`SCAN_COMPLETE_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanComplete {
+    /// Host that was scanned
+    pub host: String,
+    /// Wall-clock duration of the scan
+    pub duration_ms: u64,
+    /// Number of ports probed
+    pub ports_scanned: usize,
+    /// Number of open ports found (one [`SCAN_PORT_FOUND_EVENT`] each)
+    pub open_count: usize,
+}
+
+/// Scan results with how long the scan took and how much it covered
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanSummary {
+    /// Per-host results
+    pub results: Vec<ScanResult>,
+    /// Wall-clock duration of the scan (near zero for cache hits)
+    pub duration_ms: u64,
+    /// Number of ports probed per host
+    pub ports_scanned: usize,
+    /// Open ports across all results
+    pub open_count: usize,
+}
+
+impl ScanSummary {
+    /// Wrap `results`, counting their open ports
+    pub fn new(results: Vec<ScanResult>, ports_scanned: usize, duration: Duration) -> Self {
+        let open_count = results
+            .iter()
+            .flat_map(|r| &r.ports)
+            .filter(|p| matches!(p.status, PortStatus::Open))
+            .count();
+        Self {
+            results,
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            ports_scanned,
+            open_count,
+        }
+    }
+}
+
+/// Number of ports a scan of `ports` probes; `None` means the common-port set
+pub fn scanned_port_count(ports: Option<&[u16]>) -> usize {
+    ports.map_or(COMMON_PORTS.len(), <[u16]>::len)
+}
+
+/// Default time-to-live for cached scan results
+pub const DEFAULT_SCAN_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Cache key identifying an equivalent scan
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScanCacheKey {
+    /// Scanned host
+    pub host: String,
+    /// Hash of the (sorted, deduplicated) port set
+    pub ports_hash: u64,
+    /// OS detection flag
+    pub os_detection: bool,
+    /// Version detection flag
+    pub version_detection: bool,
+    /// Whether nmap produced the result
+    pub use_nmap: bool,
+    /// Per-port timeout; a shorter one may have missed slow ports
+    pub timeout: Duration,
+}
+
+impl ScanCacheKey {
+    /// Build a key; `None` ports means the default common-port set
+    pub fn new(
+        host: &str,
+        ports: Option<&[u16]>,
+        os_detection: bool,
+        version_detection: bool,
+        use_nmap: bool,
+    ) -> Self {
+        use std::hash::{Hash, Hasher};
+
+        let mut port_set: Vec<u16> = ports.unwrap_or(COMMON_PORTS).to_vec();
+        port_set.sort_unstable();
+        port_set.dedup();
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        port_set.hash(&mut hasher);
+
+        Self {
+            host: host.to_string(),
+            ports_hash: hasher.finish(),
+            os_detection,
+            version_detection,
+            use_nmap,
+            timeout: ScanOptions::default().timeout,
+        }
+    }
+
+    /// Key for a scan run with a non-default per-port timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// In-memory cache of recent scan results
+#[derive(Debug, Default)]
+pub struct ScanCache {
+    entries: HashMap<ScanCacheKey, (std::time::Instant, Vec<ScanResult>)>,
+}
+
+impl ScanCache {
+    /// Returns cached results (marked `cached: true`) if younger than `ttl`
+    pub fn get(&mut self, key: &ScanCacheKey, ttl: Duration) -> Option<Vec<ScanResult>> {
+        let (stored_at, results) = self.entries.get(key)?;
+        if stored_at.elapsed() >= ttl {
+            self.entries.remove(key);
+            return None;
+        }
+
+        Some(
+            results
+                .iter()
+                .cloned()
+                .map(|mut r| {
+                    r.cached = true;
+                    r
+                })
+                .collect(),
+        )
+    }
+
+    /// Stores results for a key, replacing any previous entry
+    pub fn insert(&mut self, key: ScanCacheKey, results: &[ScanResult]) {
+        self.entries
+            .insert(key, (std::time::Instant::now(), results.to_vec()));
+    }
+
+    /// Removes all cached entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
 }
 
 /// Scan options for advanced scanning
@@ -66,6 +305,36 @@ pub struct ScanOptions {
     pub timeout: Duration,
     /// Maximum parallel connections
     pub parallelism: usize,
+    /// Protocols to probe (TCP only by default)
+    pub protocols: Vec<Protocol>,
+    /// Token checked between batches to abort the scan early
+    pub cancel_token: Option<CancellationToken>,
+    /// Include filtered (timed out / unreachable) ports in results
+    pub include_filtered: bool,
+    /// Extra TCP connect attempts before giving up on a port
+    pub retries: usize,
+    /// Skip the port scan for hosts that don't answer a ping
+    pub ping_first: bool,
+    /// Maximum hosts scanned concurrently by [`scan_subnet`]
+    pub host_parallelism: usize,
+    /// Largest subnet (in host addresses) [`scan_subnet`] accepts
+    pub max_subnet_hosts: usize,
+    /// Maximum probes started per second (None for unlimited)
+    ///
+    /// Probes are then spaced evenly instead of `parallelism` being fired at
+    /// once, so a scan is slower but looks far less like a SYN flood to an
+    /// IDS. [`scan_subnet`] shares the budget across all hosts.
+    pub rate_limit: Option<u32>,
+    /// Most probe sockets open at once, across all hosts and ports
+    ///
+    /// Caps total concurrency whatever `parallelism` and `host_parallelism`
+    /// are, so large subnet scans don't run into "too many open files".
+    pub max_open_sockets: usize,
+    /// Local address probes are sent from (None for the default route)
+    ///
+    /// Lets a scan go out over a specific interface, e.g. the LAN while a
+    /// VPN owns the default route.
+    pub bind_addr: Option<std::net::IpAddr>,
 }
 
 impl Default for ScanOptions {
@@ -76,10 +345,127 @@ impl Default for ScanOptions {
             version_detection: false,
             timeout: Duration::from_millis(200),
             parallelism: 100,
+            protocols: vec![Protocol::Tcp],
+            cancel_token: None,
+            include_filtered: false,
+            retries: 0,
+            ping_first: false,
+            host_parallelism: 16,
+            max_subnet_hosts: DEFAULT_MAX_SUBNET_HOSTS,
+            rate_limit: None,
+            max_open_sockets: DEFAULT_MAX_OPEN_SOCKETS,
+            bind_addr: None,
+        }
+    }
+}
+
+/// Default for [`ScanOptions::max_open_sockets`]
+pub const DEFAULT_MAX_OPEN_SOCKETS: usize = 500;
+
+/// Shortest per-port timeout a caller may request
+pub const MIN_SCAN_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Longest per-port timeout a caller may request
+pub const MAX_SCAN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most parallel connections a caller may request
+pub const MAX_SCAN_PARALLELISM: usize = 1000;
+
+impl ScanOptions {
+    /// Overrides the per-port timeout and parallelism with caller-supplied values
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `timeout_ms` is outside [`MIN_SCAN_TIMEOUT`]..=
+    /// [`MAX_SCAN_TIMEOUT`] or `parallelism` is outside 1..=[`MAX_SCAN_PARALLELISM`]
+    pub fn with_tuning(mut self, timeout_ms: Option<u64>, parallelism: Option<usize>) -> Result<Self, String> {
+        if let Some(timeout_ms) = timeout_ms {
+            let timeout = Duration::from_millis(timeout_ms);
+            if !(MIN_SCAN_TIMEOUT..=MAX_SCAN_TIMEOUT).contains(&timeout) {
+                return Err(format!(
+                    "timeout_ms must be between {} and {}, got {timeout_ms}",
+                    MIN_SCAN_TIMEOUT.as_millis(),
+                    MAX_SCAN_TIMEOUT.as_millis()
+                ));
+            }
+            self.timeout = timeout;
+        }
+        if let Some(parallelism) = parallelism {
+            if !(1..=MAX_SCAN_PARALLELISM).contains(&parallelism) {
+                return Err(format!(
+                    "parallelism must be between 1 and {MAX_SCAN_PARALLELISM}, got {parallelism}"
+                ));
+            }
+            self.parallelism = parallelism;
+        }
+        Ok(self)
+    }
+}
+
+/// Spaces probe starts evenly to honour [`ScanOptions::rate_limit`]
+///
+/// Clones share one budget, so hosts scanned concurrently together stay
+/// within the limit.
+#[derive(Clone)]
+struct RateLimiter(std::sync::Arc<tokio::sync::Mutex<tokio::time::Interval>>);
+
+impl RateLimiter {
+    /// Limiter allowing `per_second` probes a second, or None if unlimited
+    fn new(per_second: Option<u32>) -> Option<Self> {
+        let per_second = per_second.filter(|&n| n > 0)?;
+        // Rates above 1e9/s would round to a zero period, which `interval` rejects
+        let period = (Duration::from_secs(1) / per_second).max(Duration::from_nanos(1));
+        let mut interval = tokio::time::interval(period);
+        // Don't burst to catch up after a slow batch
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Some(Self(std::sync::Arc::new(tokio::sync::Mutex::new(interval))))
+    }
+
+    /// Waits until the next probe may start
+    async fn acquire(&self) {
+        self.0.lock().await.tick().await;
+    }
+}
+
+/// Caps open probe sockets at [`ScanOptions::max_open_sockets`]
+///
+/// Clones share one set of permits, so every host of a subnet scan draws
+/// from the same budget.
+#[derive(Clone)]
+struct SocketBudget(std::sync::Arc<tokio::sync::Semaphore>);
+
+impl SocketBudget {
+    fn new(max_open_sockets: usize) -> Self {
+        Self(std::sync::Arc::new(tokio::sync::Semaphore::new(max_open_sockets.max(1))))
+    }
+
+    /// Runs `probe` once a socket permit is free, holding it until it finishes
+    async fn run<T>(self, probe: impl std::future::Future<Output = T>) -> T {
+        // The semaphore is never closed, so acquiring can't fail
+        let _permit = self.0.acquire_owned().await.ok();
+        probe.await
+    }
+}
+
+/// Limits shared by every host of one scan
+#[derive(Clone)]
+struct ScanLimits {
+    rate_limiter: Option<RateLimiter>,
+    sockets: SocketBudget,
+}
+
+impl ScanLimits {
+    fn new(options: &ScanOptions) -> Self {
+        Self {
+            rate_limiter: RateLimiter::new(options.rate_limit),
+            sockets: SocketBudget::new(options.max_open_sockets),
         }
     }
 }
 
+/// Default subnet size limit for [`scan_subnet`] (an IPv4 /16)
+pub const DEFAULT_MAX_SUBNET_HOSTS: usize = 1 << 16;
+
 /// Common development ports to scan by default
 const COMMON_PORTS: &[u16] = &[
     21,    // FTP
@@ -104,84 +490,440 @@ const COMMON_PORTS: &[u16] = &[
     9000,  // Alternative dev
 ];
 
-/// Service fingerprints for common ports
-const SERVICE_FINGERPRINTS: &[(u16, &str)] = &[
-    (21, "ftp"),
-    (22, "ssh"),
-    (23, "telnet"),
-    (25, "smtp"),
-    (53, "dns"),
-    (80, "http"),
-    (110, "pop3"),
-    (143, "imap"),
-    (443, "https"),
-    (3306, "mysql"),
-    (3389, "rdp"),
-    (5432, "postgresql"),
-    (3000, "nodejs"),
-    (5173, "vite"),
-    (8000, "http-alt"),
-    (8080, "http-proxy"),
-    (8787, "side-ide"),
-];
+/// Built-in service fingerprints (port -> service name), embedded at compile time
+const BUILTIN_FINGERPRINTS_JSON: &str = include_str!("fingerprints.json");
 
-/// Scan localhost with advanced options
-pub async fn scan_localhost(
-    ports: Option<Vec<u16>>,
-    os_detection: bool,
-    version_detection: bool,
-) -> Result<Vec<ScanResult>, String> {
-    let options = ScanOptions {
-        ports,
-        os_detection,
-        version_detection,
-        ..Default::default()
+/// User fingerprint overrides, relative to the home directory
+const USER_FINGERPRINTS_FILE: &str = ".side-ide/fingerprints.json";
+
+/// Merged fingerprint table, loaded on first use
+static SERVICE_FINGERPRINTS: OnceLock<HashMap<u16, String>> = OnceLock::new();
+
+/// Parse the embedded fingerprint table
+fn builtin_fingerprints() -> HashMap<u16, &'static str> {
+    let raw: HashMap<&'static str, &'static str> = serde_json::from_str(BUILTIN_FINGERPRINTS_JSON)
+        .expect("embedded fingerprints.json must be valid");
+    raw.into_iter()
+        .filter_map(|(port, name)| port.parse().ok().map(|port| (port, name)))
+        .collect()
+}
+
+/// Build the fingerprint table, letting entries in `override_path` shadow built-ins
+///
+/// A missing or malformed override file is ignored so the built-in table always works.
+fn load_fingerprints(override_path: Option<&Path>) -> HashMap<u16, String> {
+    let mut fingerprints: HashMap<u16, String> = builtin_fingerprints()
+        .into_iter()
+        .map(|(port, name)| (port, name.to_string()))
+        .collect();
+
+    let Some(raw) = override_path.and_then(|p| std::fs::read_to_string(p).ok()) else {
+        return fingerprints;
+    };
+
+    match serde_json::from_str::<HashMap<String, String>>(&raw) {
+        Ok(overrides) => {
+            for (port, name) in overrides {
+                match port.parse::<u16>() {
+                    Ok(port) => {
+                        fingerprints.insert(port, name);
+                    }
+                    Err(_) => {
+                        tracing::warn!(target: "Scanner", "Ignoring fingerprint override for invalid port {port:?}");
+                    }
+                }
+            }
+        }
+        Err(e) => tracing::warn!(target: "Scanner", "Ignoring malformed fingerprint overrides: {e}"),
+    }
+
+    fingerprints
+}
+
+/// Service fingerprints for common ports (built-ins merged with user overrides)
+fn service_fingerprints() -> &'static HashMap<u16, String> {
+    SERVICE_FINGERPRINTS.get_or_init(|| {
+        let override_path = crate::common::home_dir()
+            .ok()
+            .map(|home| home.join(USER_FINGERPRINTS_FILE));
+        load_fingerprints(override_path.as_deref())
+    })
+}
+
+/// IPv4 loopback address
+pub const LOOPBACK_V4: &str = "127.0.0.1";
+
+/// IPv6 loopback address
+pub const LOOPBACK_V6: &str = "::1";
+
+/// Scan localhost with advanced options, reporting progress after each batch
+///
+/// With `include_ipv6`, `::1` is scanned after `127.0.0.1` and both are
+/// merged into one result (see [`merge_loopback_results`]). Progress then
+/// covers both passes.
+pub async fn scan_localhost<F>(
+    options: ScanOptions,
+    include_ipv6: bool,
+    mut on_progress: F,
+) -> Result<Vec<ScanResult>, String>
+where
+    F: FnMut(ScanProgress),
+{
+    if !include_ipv6 {
+        return scan_host_with_progress(LOOPBACK_V4, &options, on_progress).await;
+    }
+
+    let mut v4_progress = ScanProgress { scanned: 0, total: 0, open_count: 0 };
+    let v4 = scan_host_with_progress(LOOPBACK_V4, &options, |progress| {
+        v4_progress = progress.clone();
+        on_progress(ScanProgress {
+            scanned: progress.scanned,
+            total: progress.total * 2,
+            open_count: progress.open_count,
+        });
+    })
+    .await?;
+
+    if options.cancel_token.as_ref().is_some_and(|t| t.is_cancelled()) {
+        return Ok(v4);
+    }
+
+    let v6 = scan_host_with_progress(LOOPBACK_V6, &options, |progress| {
+        on_progress(ScanProgress {
+            scanned: v4_progress.total + progress.scanned,
+            total: v4_progress.total + progress.total,
+            open_count: v4_progress.open_count + progress.open_count,
+        });
+    })
+    .await?;
+
+    Ok(vec![merge_loopback_results(v4.into_iter().next(), v6.into_iter().next())])
+}
+
+/// Merge the `127.0.0.1` and `::1` scans of localhost into one result
+///
+/// A port open on both is a single dual-stack listener, so it is reported
+/// once and tagged [`AddressFamily::Dual`] rather than counted twice.
+/// Services are likewise deduplicated.
+pub fn merge_loopback_results(v4: Option<ScanResult>, v6: Option<ScanResult>) -> ScanResult {
+    let mut merged = v4.unwrap_or_else(|| ScanResult {
+        host: LOOPBACK_V4.to_string(),
+        hostname: None,
+        ports: Vec::new(),
+        os_guess: None,
+        os_confidence: None,
+        services: Vec::new(),
+        cached: false,
+    });
+    let Some(v6) = v6 else {
+        return merged;
+    };
+
+    for port in v6.ports {
+        match merged
+            .ports
+            .iter_mut()
+            .find(|p| p.port == port.port && p.protocol == port.protocol)
+        {
+            Some(existing) => {
+                if matches!(port.status, PortStatus::Open) && !matches!(existing.status, PortStatus::Open) {
+                    *existing = port;
+                } else if matches!(port.status, PortStatus::Open) {
+                    existing.address_family = Some(AddressFamily::Dual);
+                    existing.service = existing.service.take().or(port.service);
+                    existing.version = existing.version.take().or(port.version);
+                }
+            }
+            None => merged.ports.push(port),
+        }
+    }
+    merged.ports.sort_by_key(|p| p.port);
+
+    for service in v6.services {
+        let duplicate = merged
+            .services
+            .iter()
+            .any(|s| s.name == service.name && s.version == service.version);
+        if !duplicate {
+            merged.services.push(service);
+        }
+    }
+
+    merged.os_guess = merged.os_guess.or(v6.os_guess);
+    merged.os_confidence = merged.os_confidence.or(v6.os_confidence);
+    merged
+}
+
+/// Parse an nmap-style port specification such as `"22,80,443"`,
+/// `"1-1024"` or `"8000-8100,9000,9090"`
+///
+/// The returned list is sorted and deduplicated.
+///
+/// # Errors
+///
+/// Returns an error if the spec is empty, contains a reversed range, or a
+/// value that is not a valid port (1-65535)
+pub fn parse_port_spec(spec: &str) -> Result<Vec<u16>, String> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Err("Port spec is empty".to_string());
+    }
+
+    let parse_port = |s: &str| -> Result<u16, String> {
+        let port = s
+            .trim()
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid port: {:?}", s.trim()))?;
+        if port == 0 {
+            return Err("Port 0 is not valid".to_string());
+        }
+        Ok(port)
     };
 
-    scan_host("127.0.0.1", &options).await
+    let mut ports = std::collections::BTreeSet::new();
+    for part in spec.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            return Err(format!("Empty entry in port spec: {spec:?}"));
+        }
+
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start = parse_port(start)?;
+                let end = parse_port(end)?;
+                if start > end {
+                    return Err(format!("Reversed port range: {part}"));
+                }
+                ports.extend(start..=end);
+            }
+            None => {
+                ports.insert(parse_port(part)?);
+            }
+        }
+    }
+
+    Ok(ports.into_iter().collect())
+}
+
+/// Maximum length of a DNS hostname
+const MAX_HOSTNAME_LEN: usize = 253;
+
+/// Validate a user-supplied scan target (IP address or hostname)
+///
+/// # Errors
+///
+/// Returns an error if the target is empty, too long, or contains characters
+/// that cannot appear in an IP address or DNS name
+pub fn validate_host(host: &str) -> Result<(), String> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err("Host must not be empty".to_string());
+    }
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return Ok(());
+    }
+    if host.len() > MAX_HOSTNAME_LEN {
+        return Err(format!("Host name is longer than {MAX_HOSTNAME_LEN} characters"));
+    }
+    let labels_valid = host.trim_end_matches('.').split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    if !labels_valid {
+        return Err(format!("Invalid host: {host}"));
+    }
+    Ok(())
+}
+
+/// Resolve a validated host to the IP address that will actually be probed
+///
+/// # Errors
+///
+/// Returns an error if the host is invalid or DNS resolution fails
+pub async fn resolve_host(host: &str) -> Result<String, String> {
+    validate_host(host)?;
+    let host = host.trim();
+
+    if let Ok(ip) = host.parse::<std::net::IpAddr>() {
+        return Ok(ip.to_string());
+    }
+
+    let mut addrs = tokio::net::lookup_host((host, 0))
+        .await
+        .map_err(|e| format!("Failed to resolve {host}: {e}"))?;
+
+    // Prefer IPv4 since most dev services only listen there
+    let addrs: Vec<_> = addrs.by_ref().collect();
+    addrs
+        .iter()
+        .find(|a| a.is_ipv4())
+        .or_else(|| addrs.first())
+        .map(|a| a.ip().to_string())
+        .ok_or_else(|| format!("No addresses found for {host}"))
 }
 
 /// Scan a specific host with given options
 pub async fn scan_host(host: &str, options: &ScanOptions) -> Result<Vec<ScanResult>, String> {
+    scan_host_with_progress(host, options, |_| {}).await
+}
+
+/// Scan a specific host, reporting progress after each batch of probes
+///
+/// `on_progress` is always called at least once, with `scanned == total` on
+/// the final call, even when no ports are open. If `options.cancel_token` is
+/// cancelled, the scan stops before the next batch and returns whatever was
+/// found so far.
+pub async fn scan_host_with_progress<F>(
+    host: &str,
+    options: &ScanOptions,
+    on_progress: F,
+) -> Result<Vec<ScanResult>, String>
+where
+    F: FnMut(ScanProgress),
+{
+    scan_host_with_limits(host, options, &ScanLimits::new(options), on_progress, |_| {}).await
+}
+
+/// Scan a specific host, reporting each open port as soon as it is found
+///
+/// `on_port_found` is called once per open port, straight from the batch
+/// loop rather than after the whole scan. Returns counts for the finished scan.
+///
+/// # Errors
+///
+/// Returns an error if the scan fails
+pub async fn scan_host_streaming<F>(
+    host: &str,
+    options: &ScanOptions,
+    mut on_port_found: F,
+) -> Result<ScanComplete, String>
+where
+    F: FnMut(PortFound),
+{
+    let started = std::time::Instant::now();
+    let mut open_count = 0;
+    scan_host_with_limits(host, options, &ScanLimits::new(options), |_| {}, |port| {
+        open_count += 1;
+        on_port_found(PortFound { host: host.to_string(), port: port.clone() });
+    })
+    .await?;
+
+    Ok(ScanComplete {
+        host: host.to_string(),
+        duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        ports_scanned: scanned_port_count(options.ports.as_deref()),
+        open_count,
+    })
+}
+
+/// [`scan_host_with_progress`] with limits that may be shared with other hosts
+///
+/// `on_port_found` sees each open port as soon as its probe finishes.
+async fn scan_host_with_limits<F, P>(
+    host: &str,
+    options: &ScanOptions,
+    limits: &ScanLimits,
+    mut on_progress: F,
+    mut on_port_found: P,
+) -> Result<Vec<ScanResult>, String>
+where
+    F: FnMut(ScanProgress),
+    P: FnMut(&PortInfo),
+{
     let ports_to_scan = options.ports.clone().unwrap_or_else(|| COMMON_PORTS.to_vec());
+    let total = ports_to_scan.len() * options.protocols.len();
+    let mut scanned = 0;
+
+    if options.ping_first && !ping_host(host, options.timeout).await {
+        on_progress(ScanProgress { scanned: total, total, open_count: 0 });
+        return Ok(Vec::new());
+    }
 
     // Scan ports in parallel batches
     let mut open_ports = Vec::new();
     let mut closed_ports = Vec::new();
+    let mut filtered_ports = Vec::new();
+    let mut open_filtered_ports = Vec::new();
 
     let batch_size = options.parallelism;
-    for chunk in ports_to_scan.chunks(batch_size) {
-        let mut tasks = Vec::new();
-        for &port in chunk {
-            tasks.push(tokio::spawn(probe_port(
-                host.to_string(),
-                port,
-                options.timeout,
-            )));
-        }
-
-        for task in tasks {
-            if let Ok(Some(port_info)) = task.await {
-                match port_info.status {
-                    PortStatus::Open => open_ports.push(port_info),
-                    PortStatus::Closed => closed_ports.push(port_info),
-                    _ => {}
+    'protocols: for &protocol in &options.protocols {
+        for chunk in ports_to_scan.chunks(batch_size) {
+            if options.cancel_token.as_ref().is_some_and(|t| t.is_cancelled()) {
+                break 'protocols;
+            }
+
+            let mut tasks = Vec::new();
+            for &port in chunk {
+                if let Some(limiter) = &limits.rate_limiter {
+                    limiter.acquire().await;
                 }
+                let host = host.to_string();
+                let sockets = limits.sockets.clone();
+                tasks.push(match protocol {
+                    Protocol::Tcp => {
+                        let probe = probe_port(host, port, options.timeout, options.retries, options.bind_addr);
+                        tokio::spawn(sockets.run(probe))
+                    }
+                    Protocol::Udp => {
+                        tokio::spawn(sockets.run(probe_port_udp(host, port, options.timeout, options.bind_addr)))
+                    }
+                });
             }
+
+            for task in tasks {
+                if let Ok(Some(port_info)) = task.await {
+                    match port_info.status {
+                        PortStatus::Open => {
+                            on_port_found(&port_info);
+                            open_ports.push(port_info);
+                        }
+                        PortStatus::Closed => closed_ports.push(port_info),
+                        PortStatus::Filtered => filtered_ports.push(port_info),
+                        PortStatus::OpenFiltered => open_filtered_ports.push(port_info),
+                    }
+                }
+            }
+
+            scanned += chunk.len();
+            on_progress(ScanProgress {
+                scanned,
+                total,
+                open_count: open_ports.len(),
+            });
         }
     }
 
+    if scanned == 0 {
+        on_progress(ScanProgress { scanned, total, open_count: 0 });
+    }
+
     // Build scan result
+    // Unanswered UDP ports may well be open, so they are always reported
+    let mut reported_ports = open_ports.clone();
+    reported_ports.extend(open_filtered_ports);
+    if options.include_filtered {
+        reported_ports.extend(filtered_ports);
+    }
+
     let mut result = ScanResult {
         host: host.to_string(),
-        ports: open_ports.clone(),
+        hostname: reverse_lookup(host).await,
+        ports: reported_ports,
         os_guess: None,
+        os_confidence: None,
         services: Vec::new(),
+        cached: false,
     };
 
     // OS detection (if enabled)
     if options.os_detection {
-        result.os_guess = detect_os(host, &open_ports).await;
+        if let Some((guess, confidence)) = detect_os(host, &open_ports, options.timeout).await {
+            result.os_guess = Some(guess);
+            result.os_confidence = Some(confidence.to_string());
+        }
     }
 
     // Service version detection (if enabled)
@@ -194,15 +936,13 @@ pub async fn scan_host(host: &str, options: &ScanOptions) -> Result<Vec<ScanResu
     } else {
         // Basic service fingerprinting
         for port in &open_ports {
-            if let Some(name) = SERVICE_FINGERPRINTS
-                .iter()
-                .find(|(p, _)| *p == port.port)
-                .map(|(_, name)| name.to_string())
-            {
+            if let Some(name) = service_fingerprints().get(&port.port).cloned() {
                 result.services.push(ServiceInfo {
                     name,
                     version: None,
                     info: None,
+                    cert_expiry: None,
+                    proxy: None,
                 });
             }
         }
@@ -211,40 +951,478 @@ pub async fn scan_host(host: &str, options: &ScanOptions) -> Result<Vec<ScanResu
     Ok(vec![result])
 }
 
-/// Probe a single port to check if it's open
-async fn probe_port(host: String, port: u16, timeout_duration: Duration) -> Option<PortInfo> {
+/// How long a reverse DNS lookup may add to a host's scan
+const REVERSE_DNS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Best-effort reverse DNS (PTR) lookup of `host`
+///
+/// Goes through the system resolver, so `/etc/hosts` entries count too.
+/// Returns None if `host` isn't an IP address, has no name, or the lookup
+/// takes longer than [`REVERSE_DNS_TIMEOUT`].
+pub async fn reverse_lookup(host: &str) -> Option<String> {
+    let ip: std::net::IpAddr = host.parse().ok()?;
+    let lookup = tokio::task::spawn_blocking(move || system_reverse_lookup(ip));
+    tokio::time::timeout(REVERSE_DNS_TIMEOUT, lookup).await.ok()?.ok()?
+}
+
+/// Largest host name `getnameinfo` writes, including the NUL (`NI_MAXHOST`)
+const MAX_HOST_NAME_LEN: usize = 1025;
+
+/// Blocking `getnameinfo` call; a numeric result means there was no name
+fn system_reverse_lookup(ip: std::net::IpAddr) -> Option<String> {
+    let addr = socket2::SockAddr::from(std::net::SocketAddr::new(ip, 0));
+    let mut name = [0u8; MAX_HOST_NAME_LEN];
+
+    // SAFETY: `addr` owns an initialised sockaddr of `addr.len()` bytes and
+    // `name` a writable buffer of `name.len()` bytes (which fits in
+    // `socklen_t`); both outlive the call. The service buffer is null with a
+    // length of 0, which getnameinfo accepts as "no service name wanted".
+    // On success getnameinfo NUL-terminates `name` within its length.
+    #[cfg(unix)]
+    let rc = unsafe {
+        libc::getnameinfo(
+            addr.as_ptr().cast(),
+            addr.len(),
+            name.as_mut_ptr().cast(),
+            name.len() as libc::socklen_t,
+            std::ptr::null_mut(),
+            0,
+            0,
+        )
+    };
+    #[cfg(windows)]
+    let rc = {
+        use windows_sys::Win32::Networking::WinSock::{getnameinfo, WSACleanup, WSAStartup, WSADATA};
+
+        // SAFETY: WSADATA is plain data, so all-zero is a valid value for
+        // WSAStartup to overwrite. Winsock must be initialised before
+        // getnameinfo; WSAStartup is reference counted, so pairing it with
+        // WSACleanup is safe even when std or socket2 initialised it already.
+        let mut wsa_data: WSADATA = unsafe { std::mem::zeroed() };
+        if unsafe { WSAStartup(0x0202, &mut wsa_data) } != 0 {
+            return None;
+        }
+        // SAFETY: same buffer invariants as the unix call above: `addr` is
+        // `addr.len()` valid bytes, `name` is `name.len()` writable bytes,
+        // and the null service buffer has a length of 0.
+        let rc = unsafe {
+            getnameinfo(
+                addr.as_ptr().cast(),
+                addr.len(),
+                name.as_mut_ptr(),
+                name.len() as u32,
+                std::ptr::null_mut(),
+                0,
+                0,
+            )
+        };
+        // SAFETY: balances the successful WSAStartup above
+        unsafe { WSACleanup() };
+        rc
+    };
+    if rc != 0 {
+        return None;
+    }
+
+    let name = std::ffi::CStr::from_bytes_until_nul(&name).ok()?.to_str().ok()?;
+    if name.is_empty() || name.parse::<std::net::IpAddr>().is_ok() {
+        return None;
+    }
+    Some(name.trim_end_matches('.').to_string())
+}
+
+/// Ports tried by the TCP liveness fallback when ICMP is unavailable
+const TCP_LIVENESS_PORTS: &[u16] = &[80, 443];
+
+/// Check whether a host is alive using an ICMP echo request
+///
+/// Raw/datagram ICMP sockets need privileges on some platforms; when the
+/// socket can't be used, a TCP connect to port 80/443 is used instead. Both
+/// an accepted and a refused connection prove the host is up.
+pub async fn ping_host(host: &str, timeout: Duration) -> bool {
+    let Some(ip) = resolve_host(host)
+        .await
+        .ok()
+        .and_then(|ip| ip.parse::<std::net::IpAddr>().ok())
+    else {
+        return false;
+    };
+
+    match icmp_ping(ip, timeout).await {
+        Ok(reply) => reply.is_some(),
+        Err(e) => {
+            tracing::debug!(target: "Scanner", "ICMP unavailable ({e}), falling back to TCP liveness check");
+            tcp_liveness_check(ip, timeout).await
+        }
+    }
+}
+
+/// Send a single ICMP echo request
+///
+/// Returns `Ok(Some(ttl))` on a reply, where `ttl` is the reply's IP TTL when
+/// the platform exposes it. Returns an error only when ICMP itself can't be
+/// used (e.g. permission denied).
+async fn icmp_ping(
+    ip: std::net::IpAddr,
+    timeout: Duration,
+) -> Result<Option<Option<u8>>, std::io::Error> {
+    use surge_ping::{Client, Config, IcmpPacket, PingIdentifier, PingSequence, SurgeError, ICMP};
+
+    let config = match ip {
+        std::net::IpAddr::V4(_) => Config::default(),
+        std::net::IpAddr::V6(_) => Config::builder().kind(ICMP::V6).build(),
+    };
+    let client = Client::new(&config)?;
+    let mut pinger = client.pinger(ip, PingIdentifier(std::process::id() as u16)).await;
+    pinger.timeout(timeout);
+
+    match pinger.ping(PingSequence(0), &[0; 8]).await {
+        Ok((IcmpPacket::V4(packet), _)) => Ok(Some(packet.get_ttl())),
+        // ICMPv6 replies don't carry the hop limit
+        Ok((IcmpPacket::V6(_), _)) => Ok(Some(None)),
+        Err(SurgeError::IOError(e)) if e.kind() == std::io::ErrorKind::PermissionDenied => Err(e),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Liveness check via TCP connect to well-known ports
+async fn tcp_liveness_check(ip: std::net::IpAddr, timeout: Duration) -> bool {
     use tokio::net::TcpStream;
     use tokio::time::timeout as tokio_timeout;
 
-    let addr = format!("{}:{}", host, port);
+    for &port in TCP_LIVENESS_PORTS {
+        match tokio_timeout(timeout, TcpStream::connect((ip, port))).await {
+            Ok(Ok(_)) => return true,
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => return true,
+            _ => {}
+        }
+    }
+    false
+}
 
-    match tokio_timeout(
-        timeout_duration,
-        TcpStream::connect(&addr)
-    ).await {
-        Ok(Ok(_)) => Some(PortInfo {
-            port,
-            status: PortStatus::Open,
-            protocol: "tcp".to_string(),
-            service: None,
-            version: None,
-        }),
-        Ok(Err(_)) => Some(PortInfo {
-            port,
-            status: PortStatus::Closed,
-            protocol: "tcp".to_string(),
-            service: None,
-            version: None,
-        }),
-        Err(_) => None, // Timeout - treat as filtered
+/// Format a host/port pair, bracketing IPv6 literals
+fn socket_addr(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+/// Expand a CIDR block into its usable host addresses
+///
+/// Network and broadcast addresses are skipped (except for /31, /32 and the
+/// IPv6 equivalents, where every address is a host).
+///
+/// # Errors
+///
+/// Returns an error if the CIDR is malformed or covers more than `max_hosts` addresses
+pub fn expand_cidr(cidr: &str, max_hosts: usize) -> Result<Vec<std::net::IpAddr>, String> {
+    let net: ipnet::IpNet = cidr
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid CIDR: {cidr}"))?;
+
+    let host_bits = u32::from(net.max_prefix_len() - net.prefix_len());
+    let too_large = 1u128
+        .checked_shl(host_bits)
+        .is_none_or(|size| size > max_hosts as u128);
+    if too_large {
+        return Err(format!(
+            "Subnet {net} is too large to scan (limit is {max_hosts} addresses)"
+        ));
+    }
+
+    Ok(net.hosts().collect())
+}
+
+/// Scan every host in a CIDR block, e.g. `192.168.1.0/24`
+///
+/// Hosts are scanned concurrently, at most `options.host_parallelism` at a
+/// time, and one `ScanResult` is returned per responsive host: with
+/// `ping_first` that is every host answering the ping, otherwise every host
+/// with at least one reported port.
+///
+/// # Errors
+///
+/// Returns an error if the CIDR is invalid or larger than `options.max_subnet_hosts`
+pub async fn scan_subnet(cidr: String, options: ScanOptions) -> Result<Vec<ScanResult>, String> {
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+
+    let hosts = expand_cidr(&cidr, options.max_subnet_hosts)?;
+    let semaphore = Arc::new(Semaphore::new(options.host_parallelism.max(1)));
+    let limits = ScanLimits::new(&options);
+    let options = Arc::new(options);
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (index, ip) in hosts.into_iter().enumerate() {
+        if options.cancel_token.as_ref().is_some_and(|t| t.is_cancelled()) {
+            break;
+        }
+
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| format!("Subnet scan aborted: {e}"))?;
+        let options = options.clone();
+        let limits = limits.clone();
+        tasks.spawn(async move {
+            let _permit = permit;
+            let results = scan_host_with_limits(&ip.to_string(), &options, &limits, |_| {}, |_| {})
+                .await
+                .unwrap_or_default();
+            (index, results)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, host_results)) = joined {
+            results.extend(
+                host_results
+                    .into_iter()
+                    .filter(|r| options.ping_first || !r.ports.is_empty())
+                    .map(|r| (index, r)),
+            );
+        }
+    }
+
+    // Keep address order regardless of completion order
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, r)| r).collect())
+}
+
+/// Probe a single port to check if it's open
+///
+/// A refused connection means the host is reachable but nothing is listening
+/// (closed); a timeout or any other connect error (e.g. host unreachable)
+/// means the probe was dropped somewhere along the way (filtered).
+///
+/// The connect is attempted up to `retries + 1` times, each bounded by
+/// `timeout_duration`, and the port is open as soon as any attempt succeeds.
+/// Only timeouts and other filtered outcomes are retried; a refusal (RST)
+/// is a definitive closed.
+/// Worst case (an unresponsive port) this takes `(retries + 1) * timeout_duration`.
+/// With a `bind_addr` the connection is made from that local address; if the
+/// socket can't be bound there, the port is left out rather than misreported.
+async fn probe_port(
+    host: String,
+    port: u16,
+    timeout_duration: Duration,
+    retries: usize,
+    bind_addr: Option<std::net::IpAddr>,
+) -> Option<PortInfo> {
+    use tokio::time::timeout as tokio_timeout;
+
+    let addr = socket_addr(&host, port);
+
+    let status = retry_while_filtered(retries, || async {
+        match tokio_timeout(timeout_duration, connect_tcp(&addr, bind_addr)).await {
+            Ok(Ok(connected)) => Some(tcp_connect_status(Some(connected))),
+            Ok(Err(e)) => {
+                tracing::debug!(target: "Scanner", "Can't probe {addr} from {bind_addr:?}: {e}");
+                None
+            }
+            Err(_) => Some(tcp_connect_status(None)),
+        }
+    })
+    .await?;
+
+    Some(PortInfo {
+        port,
+        status,
+        protocol: Protocol::Tcp.as_str().to_string(),
+        service: None,
+        version: None,
+        address_family: AddressFamily::of_host(&host),
+    })
+}
+
+/// Runs `attempt` up to `retries + 1` times until it gives a definitive status
+///
+/// Open and closed end it right away; only filtered results are retried.
+/// `attempt` returning None (the probe couldn't be sent) ends it with None.
+async fn retry_while_filtered<F, Fut>(retries: usize, mut attempt: F) -> Option<PortStatus>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Option<PortStatus>>,
+{
+    for _ in 0..retries {
+        match attempt().await? {
+            PortStatus::Filtered => {}
+            status => return Some(status),
+        }
+    }
+    attempt().await
+}
+
+/// Connect to `addr`, from `bind_addr` when given
+///
+/// Binding before connecting needs a raw socket, so it is set up with
+/// `socket2` and handed to tokio for the connect itself. The outer error
+/// means the local socket couldn't be set up or bound, so nothing was sent;
+/// the inner result is the connect itself.
+async fn connect_tcp(
+    addr: &str,
+    bind_addr: Option<std::net::IpAddr>,
+) -> std::io::Result<std::io::Result<()>> {
+    let Some(bind_ip) = bind_addr else {
+        return Ok(tokio::net::TcpStream::connect(addr).await.map(|_| ()));
+    };
+
+    let target: std::net::SocketAddr = addr
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{addr}: {e}")))?;
+    let socket = socket2::Socket::new(
+        socket2::Domain::for_address(target),
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&std::net::SocketAddr::new(bind_ip, 0).into())?;
+
+    let socket = tokio::net::TcpSocket::from_std_stream(socket.into());
+    Ok(socket.connect(target).await.map(|_| ()))
+}
+
+/// Checks that `ip` is assigned to this machine, so probes can be sent from it
+///
+/// # Errors
+///
+/// Returns an error if a socket can't be bound to the address
+pub fn validate_bind_addr(ip: std::net::IpAddr) -> Result<(), String> {
+    std::net::UdpSocket::bind((ip, 0))
+        .map(drop)
+        .map_err(|e| format!("Can't scan from {ip}: {e}"))
+}
+
+/// Classify a TCP connect attempt (`None` means it timed out)
+fn tcp_connect_status(outcome: Option<std::io::Result<()>>) -> PortStatus {
+    match outcome {
+        Some(Ok(())) => PortStatus::Open,
+        Some(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => PortStatus::Closed,
+        Some(Err(_)) | None => PortStatus::Filtered,
+    }
+}
+
+/// Probe a single UDP port
+///
+/// UDP is connectionless, so a silent port is indistinguishable from a
+/// firewalled one: a reply means open, an ICMP port-unreachable (surfaced as
+/// `ConnectionRefused` on a connected socket) means closed, and a timeout is
+/// reported as [`PortStatus::OpenFiltered`].
+async fn probe_port_udp(
+    host: String,
+    port: u16,
+    timeout_duration: Duration,
+    bind_addr: Option<std::net::IpAddr>,
+) -> Option<PortInfo> {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+    use tokio::net::UdpSocket;
+    use tokio::time::timeout as tokio_timeout;
+
+    let addr = socket_addr(&host, port);
+
+    let unspecified = if host.contains(':') {
+        IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    };
+    let socket = UdpSocket::bind(SocketAddr::new(bind_addr.unwrap_or(unspecified), 0))
+        .await
+        .ok()?;
+    socket.connect(&addr).await.ok()?;
+
+    let status = match socket.send(udp_probe_payload(port)).await {
+        Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => PortStatus::Closed,
+        Err(_) => return None,
+        Ok(_) => {
+            let mut buffer = [0u8; 512];
+            match tokio_timeout(timeout_duration, socket.recv(&mut buffer)).await {
+                Ok(Ok(_)) => PortStatus::Open,
+                Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => PortStatus::Closed,
+                Ok(Err(_)) => return None,
+                Err(_) => PortStatus::OpenFiltered, // No reply
+            }
+        }
+    };
+
+    Some(PortInfo {
+        port,
+        status,
+        protocol: Protocol::Udp.as_str().to_string(),
+        service: None,
+        version: None,
+        address_family: AddressFamily::of_host(&host),
+    })
+}
+
+/// Protocol-specific datagram that is likely to elicit a reply on a UDP port
+fn udp_probe_payload(port: u16) -> &'static [u8] {
+    // Standard DNS query for the root NS records
+    const DNS_QUERY: &[u8] = &[
+        0x53, 0x49, // Transaction ID
+        0x01, 0x00, // Flags: recursion desired
+        0x00, 0x01, // Questions
+        0x00, 0x00, // Answer RRs
+        0x00, 0x00, // Authority RRs
+        0x00, 0x00, // Additional RRs
+        0x00,       // Root name
+        0x00, 0x02, // Type NS
+        0x00, 0x01, // Class IN
+    ];
+
+    match port {
+        53 | 5353 => DNS_QUERY,
+        _ => &[],
     }
 }
 
 /// Detect operating system based on open ports and responses
-async fn detect_os(_host: &str, open_ports: &[PortInfo]) -> Option<String> {
-    // Basic OS detection based on common port patterns
-    // This is a simplified version - full TCP/IP fingerprinting requires raw sockets
+///
+/// The TTL of an ICMP echo reply is mapped to the sender's likely initial TTL
+/// (see [`os_from_ttl`]), giving a `"high"` confidence guess. The TTL is read
+/// from ICMP rather than a TCP connection because a connected TCP socket
+/// never exposes the TTL of received packets (`IP_TTL` is our own outgoing
+/// value) without raw sockets. When ICMP isn't usable or the reply carries
+/// no TTL, the open-port pattern heuristic is used with `"low"` confidence.
+async fn detect_os(
+    host: &str,
+    open_ports: &[PortInfo],
+    timeout: Duration,
+) -> Option<(String, &'static str)> {
+    if let Ok(ip) = host.parse::<std::net::IpAddr>() {
+        match icmp_ping(ip, timeout).await {
+            Ok(Some(Some(ttl))) => {
+                if let Some(os) = os_from_ttl(ttl) {
+                    return Some((os.to_string(), "high"));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::debug!(target: "Scanner", "ICMP unavailable ({e}), guessing the OS of {host} from open ports");
+            }
+        }
+    }
 
+    detect_os_from_ports(open_ports).map(|os| (os, "low"))
+}
+
+/// Map an observed TTL to an OS family by rounding up to the nearest common
+/// initial TTL (64 for Linux/Unix, 128 for Windows, 255 for network devices)
+fn os_from_ttl(ttl: u8) -> Option<&'static str> {
+    match ttl {
+        0 => None,
+        1..=64 => Some("Unix/Linux"),
+        65..=128 => Some("Windows"),
+        129..=255 => Some("Network device"),
+    }
+}
+
+/// Guess the OS from common port patterns
+fn detect_os_from_ports(open_ports: &[PortInfo]) -> Option<String> {
     if open_ports.is_empty() {
         return None;
     }
@@ -263,52 +1441,334 @@ async fn detect_os(_host: &str, open_ports: &[PortInfo]) -> Option<String> {
     }
 }
 
+/// Ports on which version detection attempts a TLS handshake first
+const TLS_PORTS: &[u16] = &[443, 8443];
+
+/// Ports on which version detection speaks HTTP
+const HTTP_PORTS: &[u16] = &[80, 8000, 8080, 3000, 5173, 8787];
+
+/// How long to wait for each banner or probe response
+const BANNER_READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Protocol-specific nudge used to get a service to identify itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BannerProbe {
+    /// The server sends `SSH-2.0-<software>` as soon as we connect
+    Ssh,
+    /// The server greets first; `EHLO` gets the extension list too
+    Smtp,
+    /// Nothing is sent until we ask; `HEAD` returns just the headers
+    Http,
+}
+
+impl BannerProbe {
+    /// Probe to use for `port`, or None to just read passively
+    fn for_port(port: u16) -> Option<Self> {
+        match port {
+            22 => Some(BannerProbe::Ssh),
+            25 | 587 => Some(BannerProbe::Smtp),
+            p if HTTP_PORTS.contains(&p) => Some(BannerProbe::Http),
+            _ => None,
+        }
+    }
+
+    /// Whether the server speaks first, so we read before sending
+    fn server_speaks_first(self) -> bool {
+        matches!(self, BannerProbe::Ssh | BannerProbe::Smtp)
+    }
+
+    fn payload(self) -> &'static [u8] {
+        match self {
+            BannerProbe::Ssh => b"",
+            BannerProbe::Smtp => b"EHLO side-ide\r\n",
+            BannerProbe::Http => b"HEAD / HTTP/1.0\r\n\r\n",
+        }
+    }
+
+    fn extract_version(self, response: &str) -> Option<String> {
+        match self {
+            BannerProbe::Ssh => extract_ssh_version(response),
+            BannerProbe::Smtp => extract_smtp_version(response),
+            BannerProbe::Http => extract_http_version(response),
+        }
+    }
+}
+
+/// Compiles `pattern` once into `cell`
+fn cached_regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("banner regex is valid"))
+}
+
+/// Software version from an SSH identification string
+///
+/// `SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13` yields `OpenSSH_9.6p1`.
+fn extract_ssh_version(banner: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = cached_regex(&RE, r"(?m)^SSH-\d+\.\d+-(\S+)");
+    re.captures(banner).map(|c| c[1].to_string())
+}
+
+/// Product and version from an SMTP `220` greeting
+///
+/// `220 mail.example.com ESMTP Exim 4.96 ...` yields `Exim 4.96`. Greetings
+/// that don't name a versioned product (e.g. plain Postfix) yield None.
+fn extract_smtp_version(banner: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = cached_regex(&RE, r"(?m)^220[ -](?:.*?[\s(])?([A-Za-z][\w-]*)[ /]v?(\d+\.\d[\w.]*)");
+    re.captures(banner).map(|c| format!("{} {}", &c[1], &c[2]))
+}
+
+/// Value of the `Server` header in an HTTP response
+fn extract_http_version(response: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = cached_regex(&RE, r"(?mi)^Server:[ \t]*([^\r\n]+?)[ \t]*\r?$");
+    re.captures(response).map(|c| c[1].to_string())
+}
+
+/// Header substrings (lowercase) naming a reverse proxy, and the proxy
+const REVERSE_PROXY_MARKERS: &[(&str, &str)] = &[
+    ("openresty", "openresty"),
+    ("nginx", "nginx"),
+    ("caddy", "caddy"),
+    ("traefik", "traefik"),
+    ("envoy", "envoy"),
+    ("haproxy", "haproxy"),
+    ("varnish", "varnish"),
+];
+
+/// Reverse proxy named by the `Via` or `Server` headers of an HTTP response
+///
+/// `Via` is checked first since it is added by the proxy itself, while
+/// `Server` may come from the app behind it. Best-effort: a proxy that
+/// strips or rewrites these headers goes unnoticed.
+fn detect_reverse_proxy(response: &str) -> Option<String> {
+    let header_values = |name: &str| {
+        response
+            .lines()
+            .take_while(|line| !line.trim().is_empty())
+            .filter_map(|line| line.split_once(':'))
+            .filter(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_ascii_lowercase())
+            .collect::<Vec<_>>()
+    };
+
+    ["via", "server"].iter().flat_map(|name| header_values(name)).find_map(|value| {
+        REVERSE_PROXY_MARKERS
+            .iter()
+            .find(|(marker, _)| value.contains(marker))
+            .map(|(_, proxy)| proxy.to_string())
+    })
+}
+
+/// Appends whatever the peer sends within [`BANNER_READ_TIMEOUT`]
+async fn read_banner<R>(reader: &mut R, into: &mut String)
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut buffer = vec![0u8; 1024];
+    if let Ok(Ok(n)) = tokio::time::timeout(BANNER_READ_TIMEOUT, reader.read(&mut buffer)).await {
+        into.push_str(&String::from_utf8_lossy(&buffer[..n]));
+    }
+}
+
 /// Detect service version by connecting and reading banner
+///
+/// On TLS ports the server certificate is inspected first, falling back to
+/// the plaintext banner if the handshake fails. Ports with a [`BannerProbe`]
+/// get the protocol's nudge and version regex; anything else, or a probe
+/// whose regex finds nothing, falls back to a passive read and
+/// [`parse_version_from_banner`].
 async fn detect_service_version(
     host: &str,
     port: &PortInfo,
     timeout_duration: Duration,
 ) -> Option<ServiceInfo> {
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::io::AsyncWriteExt;
     use tokio::net::TcpStream;
     use tokio::time::timeout as tokio_timeout;
 
-    let addr = format!("{}:{}", host, port.port);
+    if TLS_PORTS.contains(&port.port) {
+        if let Some(service) = detect_tls_certificate(host, port, timeout_duration).await {
+            return Some(service);
+        }
+    }
+
+    let addr = socket_addr(host, port.port);
 
     // Try to connect with timeout
-    let stream = match tokio_timeout(timeout_duration, TcpStream::connect(&addr)).await {
+    let mut stream = match tokio_timeout(timeout_duration, TcpStream::connect(&addr)).await {
         Ok(Ok(s)) => s,
         _ => return None,
     };
 
-    let (mut reader, mut writer) = tokio::io::split(stream);
-
-    // Send HTTP request for web servers
-    if [80, 8000, 8080, 3000, 5173, 8787].contains(&port.port) {
-        let _ = tokio_timeout(Duration::from_millis(100), async {
-            let _ = writer.write_all(b"GET / HTTP/1.0\r\n\r\n").await;
-            let mut buffer = vec![0u8; 1024];
-            let n = reader.read(&mut buffer).await.ok()?;
-            String::from_utf8_lossy(&buffer[..n]).to_string().into()
-        }).await.ok().flatten();
-    }
-
-    // Read initial response/banner
-    let mut buffer = vec![0u8; 512];
-    let banner = tokio_timeout(Duration::from_millis(200), reader.read(&mut buffer)).await;
-
-    if let Ok(Ok(n)) = banner {
-        if n > 0 {
-            let banner_str = String::from_utf8_lossy(&buffer[..n]);
-            return Some(ServiceInfo {
-                name: port.service.clone().unwrap_or_else(|| "unknown".to_string()),
-                version: parse_version_from_banner(&banner_str),
-                info: Some(banner_str.trim().to_string()),
-            });
-        }
+    let probe = BannerProbe::for_port(port.port);
+    let mut response = String::new();
+    match probe {
+        Some(probe) => {
+            if probe.server_speaks_first() {
+                read_banner(&mut stream, &mut response).await;
+            }
+            if !probe.payload().is_empty() && stream.write_all(probe.payload()).await.is_ok() {
+                read_banner(&mut stream, &mut response).await;
+            }
+        }
+        None => read_banner(&mut stream, &mut response).await,
+    }
+
+    if response.is_empty() {
+        return None;
+    }
+
+    let version = probe
+        .and_then(|probe| probe.extract_version(&response))
+        .or_else(|| parse_version_from_banner(&response));
+    let proxy = match probe {
+        Some(BannerProbe::Http) => detect_reverse_proxy(&response),
+        _ => None,
+    };
+    Some(ServiceInfo {
+        name: port.service.clone().unwrap_or_else(|| "unknown".to_string()),
+        version,
+        info: Some(response.trim().to_string()),
+        cert_expiry: None,
+        proxy,
+    })
+}
+
+/// Subject, issuer and expiry of a server certificate
+#[derive(Debug, PartialEq)]
+struct CertificateSummary {
+    subject_cn: Option<String>,
+    issuer: String,
+    not_after: String,
+}
+
+impl CertificateSummary {
+    /// Human-readable one-line description for `ServiceInfo.info`
+    fn describe(&self) -> String {
+        format!(
+            "CN={}; issuer={}; notAfter={}",
+            self.subject_cn.as_deref().unwrap_or("(none)"),
+            self.issuer,
+            self.not_after
+        )
+    }
+}
+
+/// Perform a TLS handshake and summarize the server's leaf certificate
+async fn detect_tls_certificate(
+    host: &str,
+    port: &PortInfo,
+    timeout_duration: Duration,
+) -> Option<ServiceInfo> {
+    use std::sync::Arc;
+    use tokio::net::TcpStream;
+    use tokio::time::timeout as tokio_timeout;
+    use tokio_rustls::rustls;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .ok()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+        .with_no_client_auth();
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+    let server_name = ServerName::try_from(host.to_string()).ok()?;
+
+    // The handshake takes several round trips on top of the TCP connect
+    let addr = socket_addr(host, port.port);
+    let tls = tokio_timeout(timeout_duration * 5, async {
+        let stream = TcpStream::connect(&addr).await.ok()?;
+        connector.connect(server_name, stream).await.ok()
+    })
+    .await
+    .ok()??;
+
+    let (_, session) = tls.get_ref();
+    let leaf = session.peer_certificates()?.first()?;
+    let summary = summarize_certificate(leaf.as_ref())?;
+
+    Some(ServiceInfo {
+        name: port.service.clone().unwrap_or_else(|| "https".to_string()),
+        version: session.protocol_version().and_then(|v| v.as_str()).map(str::to_string),
+        info: Some(summary.describe()),
+        cert_expiry: Some(summary.not_after),
+        proxy: None,
+    })
+}
+
+/// Extract subject CN, issuer and notAfter from a DER-encoded certificate
+fn summarize_certificate(der: &[u8]) -> Option<CertificateSummary> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+
+    let subject_cn = cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(str::to_string);
+
+    let not_after = cert.validity().not_after.to_datetime();
+    let not_after = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        not_after.year(),
+        u8::from(not_after.month()),
+        not_after.day(),
+        not_after.hour(),
+        not_after.minute(),
+        not_after.second()
+    );
+
+    Some(CertificateSummary {
+        subject_cn,
+        issuer: cert.issuer().to_string(),
+        not_after,
+    })
+}
+
+/// Certificate verifier that accepts anything
+///
+/// Version detection only reports certificate details; it never sends data
+/// over the connection, so self-signed and expired certificates are fine.
+#[derive(Debug)]
+struct AcceptAnyCertificate(std::sync::Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
     }
 
-    None
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
 }
 
 /// Parse version string from service banner
@@ -355,8 +1815,50 @@ fn parse_version_from_banner(banner: &str) -> Option<String> {
     None
 }
 
+/// Whether nmap is installed, and how to get it if not
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NmapStatus {
+    pub available: bool,
+    /// Version from `nmap --version`, e.g. `"7.94"`
+    pub version: Option<String>,
+    /// Command that installs nmap on this platform
+    pub install_hint: String,
+}
+
+/// Cached result of [`nmap_status`]
+static NMAP_STATUS: crate::common::CommandCache<NmapStatus> = crate::common::CommandCache::new();
+
 /// Check if nmap is available on the system
+///
+/// The result is cached; see [`refresh_nmap_status`].
 pub fn is_nmap_available() -> bool {
+    nmap_status().available
+}
+
+/// Whether nmap is available and its version, looked up once and cached
+pub fn nmap_status() -> NmapStatus {
+    nmap_status_with(&NMAP_STATUS, false, probe_nmap)
+}
+
+/// Forgets the cached nmap status and looks again, e.g. after installing nmap
+pub fn refresh_nmap_status() -> NmapStatus {
+    nmap_status_with(&NMAP_STATUS, true, probe_nmap)
+}
+
+/// Reads the status from `cache`, running `probe` if it's empty or `refresh` is set
+pub fn nmap_status_with(
+    cache: &crate::common::CommandCache<NmapStatus>,
+    refresh: bool,
+    probe: impl FnOnce() -> NmapStatus,
+) -> NmapStatus {
+    if refresh {
+        cache.clear();
+    }
+    cache.get_or_resolve(probe)
+}
+
+/// Runs `nmap --version`
+fn probe_nmap() -> NmapStatus {
     use std::process::Command;
     let mut cmd = Command::new("nmap");
     cmd.arg("--version");
@@ -369,20 +1871,170 @@ pub fn is_nmap_available() -> bool {
         cmd.creation_flags(CREATE_NO_WINDOW);
     }
     
-    cmd.output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+    let output = cmd.output().ok().filter(|output| output.status.success());
+    NmapStatus {
+        available: output.is_some(),
+        version: output.and_then(|output| parse_nmap_version(&String::from_utf8_lossy(&output.stdout))),
+        install_hint: nmap_install_hint().to_string(),
+    }
+}
+
+/// Version number from `nmap --version` output (`Nmap version 7.94 ( https://nmap.org )`)
+pub fn parse_nmap_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Nmap version "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(str::to_string)
+}
+
+/// Command that installs nmap with this platform's usual package manager
+pub fn nmap_install_hint() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "brew install nmap"
+    } else if cfg!(target_os = "windows") {
+        "choco install nmap"
+    } else if cfg!(target_os = "linux") {
+        "apt install nmap"
+    } else {
+        "Download nmap from https://nmap.org/download.html"
+    }
+}
+
+/// Default time [`scan_with_nmap`] waits for nmap before killing it
+///
+/// Generous because `-O`/`-sV` against a slow host legitimately take a while,
+/// but bounded so a black-holed target can't leave the scan pending forever.
+pub const DEFAULT_NMAP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Output of a subprocess run by [`run_with_timeout`]
+struct ProcessOutput {
+    status: std::process::ExitStatus,
+    stdout: Vec<u8>,
+    stderr: String,
+}
+
+/// Runs `cmd`, killing it if it hasn't exited within `timeout`
+///
+/// stderr is logged line by line as it arrives (nmap reports progress there)
+/// and also returned for error messages.
+async fn run_with_timeout(
+    mut cmd: tokio::process::Command,
+    timeout: Duration,
+) -> Result<ProcessOutput, String> {
+    use std::process::Stdio;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+    let program = cmd.as_std().get_program().to_string_lossy().into_owned();
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true);
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to run {program}: {e}"))?;
+
+    let mut stdout = child.stdout.take().ok_or("stdout was not captured")?;
+    let stderr = child.stderr.take().ok_or("stderr was not captured")?;
+    let stdout_task = tokio::spawn(async move {
+        let mut buffer = Vec::new();
+        let _ = stdout.read_to_end(&mut buffer).await;
+        buffer
+    });
+    let log_name = program.clone();
+    let stderr_task = tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        let mut captured = String::new();
+        while let Ok(Some(line)) = lines.next_line().await {
+            tracing::debug!(target: "Scanner", "{log_name}: {line}");
+            captured.push_str(&line);
+            captured.push('\n');
+        }
+        captured
+    });
+
+    let status = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => status.map_err(|e| format!("Failed to wait for {program}: {e}"))?,
+        Err(_) => {
+            let _ = child.kill().await;
+            stdout_task.abort();
+            stderr_task.abort();
+            tracing::warn!(target: "Scanner", "{program} timed out after {timeout:?}, killed it");
+            return Err(format!("{program} did not finish within {timeout:?} and was stopped"));
+        }
+    };
+
+    Ok(ProcessOutput {
+        status,
+        stdout: stdout_task.await.unwrap_or_default(),
+        stderr: stderr_task.await.unwrap_or_default(),
+    })
+}
+
+/// nmap flags users may pass as-is
+const NMAP_ALLOWED_FLAGS: &[&str] = &[
+    "-sS", "-sT", "-sU", "-sV", "-sC", "-O", "-A", "-Pn", "-n", "-F", "-6", "-v",
+    "-T0", "-T1", "-T2", "-T3", "-T4", "-T5",
+    "--open", "--reason", "--traceroute", "--osscan-guess",
+    "--version-light", "--version-all",
+];
+
+/// nmap options users may pass with a value, written as `--option=value`
+const NMAP_ALLOWED_VALUE_FLAGS: &[&str] = &[
+    "--script", "--script-args", "--top-ports", "--version-intensity",
+    "--max-retries", "--host-timeout", "--scan-delay", "--max-scan-delay",
+    "--min-rate", "--max-rate", "--min-parallelism", "--max-parallelism",
+];
+
+/// Checks user-supplied nmap flags against the allowlist
+///
+/// nmap is spawned directly, so there is no shell to inject into, but flags
+/// can still write files (`-oN /etc/passwd`), read them (`-iL`) or run
+/// arbitrary Lua (`--script=/tmp/x.nse`). Output flags are always rejected,
+/// values must use the `--option=value` form, and `--script` only accepts
+/// script and category names, not paths.
+///
+/// # Errors
+///
+/// Returns an error naming the first flag that isn't allowed
+pub fn validate_nmap_flags(flags: &[String]) -> Result<(), String> {
+    for flag in flags {
+        if flag.starts_with("-o") || flag.starts_with("--append-output") {
+            return Err(format!("nmap output flags are not allowed: {flag}"));
+        }
+        if NMAP_ALLOWED_FLAGS.contains(&flag.as_str()) {
+            continue;
+        }
+
+        let Some((name, value)) = flag.split_once('=') else {
+            return Err(format!("nmap flag is not allowed: {flag}"));
+        };
+        if !NMAP_ALLOWED_VALUE_FLAGS.contains(&name) || value.is_empty() {
+            return Err(format!("nmap flag is not allowed: {flag}"));
+        }
+        let safe_value = match name {
+            "--script" => value.chars().all(|c| c.is_ascii_alphanumeric() || "-_,".contains(c)),
+            _ => !value.contains(['/', '\\']),
+        };
+        if !safe_value {
+            return Err(format!("nmap flag value is not allowed: {flag}"));
+        }
+    }
+    Ok(())
 }
 
 /// Run nmap subprocess for advanced scanning (NSE scripts, etc.)
+///
+/// `extra_flags` are appended after the defaults once they pass
+/// [`validate_nmap_flags`]. nmap is killed if it runs longer than `timeout`
+/// (see [`DEFAULT_NMAP_TIMEOUT`]).
 pub async fn scan_with_nmap(
     host: &str,
     ports: Option<Vec<u16>>,
     os_detection: bool,
     version_detection: bool,
+    extra_flags: &[String],
+    timeout: Duration,
 ) -> Result<Vec<ScanResult>, String> {
     use tokio::process::Command;
 
+    validate_nmap_flags(extra_flags)?;
+
     let mut cmd = Command::new("nmap");
 
     // Add target host
@@ -409,6 +2061,9 @@ pub async fn scan_with_nmap(
 
     // Fast scan
     cmd.arg("-T4");
+
+    // Allowlisted user flags, after the defaults so e.g. `-T2` wins
+    cmd.args(extra_flags);
     
     // Hide console window on Windows
     #[cfg(target_os = "windows")]
@@ -419,13 +2074,10 @@ pub async fn scan_with_nmap(
         cmd.creation_flags(CREATE_NO_WINDOW);
     }
 
-    let output = cmd
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run nmap: {}", e))?;
+    let output = run_with_timeout(cmd, timeout).await?;
 
     if !output.status.success() {
-        return Err(format!("nmap scan failed: {}", String::from_utf8_lossy(&output.stderr)));
+        return Err(format!("nmap scan failed: {}", output.stderr));
     }
 
     // Parse nmap XML output
@@ -433,83 +2085,111 @@ pub async fn scan_with_nmap(
 }
 
 /// Parse nmap XML output into ScanResult format
+///
+/// Produces one `ScanResult` per `<host>` element, with its `<ports>`,
+/// `<service>` and `<osmatch>` children attached to that host.
 fn parse_nmap_xml(xml: &str) -> Result<Vec<ScanResult>, String> {
-    // Simplified parsing - extract basic information
-    let mut result = ScanResult {
-        host: "unknown".to_string(),
-        ports: Vec::new(),
-        os_guess: None,
-        services: Vec::new(),
-    };
-
-    for line in xml.lines() {
-        // Extract host address
-        if line.contains("address=") && line.contains("addr=") {
-            if let Some(start) = line.find("addr=\"") {
-                let addr_start = start + 6;
-                if let Some(end) = line[addr_start..].find('"') {
-                    result.host = line[addr_start..addr_start + end].to_string();
-                }
-            }
-        }
+    let doc = roxmltree::Document::parse(xml)
+        .map_err(|e| format!("Failed to parse nmap XML: {e}"))?;
 
-        // Extract port information
-        if line.contains("<port ") {
-            if let Some(port_id) = extract_attr(line, "portid") {
-                if let Ok(port_num) = port_id.parse::<u16>() {
-                    let protocol = extract_attr(line, "protocol").unwrap_or_else(|| "tcp".to_string());
-                    let state = extract_attr(line, "state").unwrap_or_else(|| "unknown".to_string());
-                    let status = match state.as_str() {
-                        "open" => PortStatus::Open,
-                        "closed" => PortStatus::Closed,
-                        _ => PortStatus::Filtered,
-                    };
-
-                    result.ports.push(PortInfo {
-                        port: port_num,
-                        status,
-                        protocol,
-                        service: None,
-                        version: None,
-                    });
-                }
-            }
-        }
+    let results = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("host"))
+        .map(parse_nmap_host)
+        .collect();
 
-        // Extract service information
-        if line.contains("<service ") {
-            let name = extract_attr(line, "name").unwrap_or_else(|| "unknown".to_string());
-            let version = extract_attr(line, "version");
-            let product = extract_attr(line, "product");
+    Ok(results)
+}
 
-            result.services.push(ServiceInfo {
-                name: if product.is_some() { product.unwrap() } else { name.clone() },
-                version,
+/// Minimum nmap `osmatch` accuracy (percent) reported as high confidence
+const NMAP_HIGH_CONFIDENCE_ACCURACY: u8 = 90;
+
+/// Convert a single nmap `<host>` element into a ScanResult
+fn parse_nmap_host(host: roxmltree::Node) -> ScanResult {
+    let addresses: Vec<_> = host
+        .children()
+        .filter(|n| n.has_tag_name("address"))
+        .collect();
+    let address_of = |addrtype: &str| {
+        addresses
+            .iter()
+            .find(|a| a.attribute("addrtype") == Some(addrtype))
+            .and_then(|a| a.attribute("addr"))
+    };
+    let hostname = host
+        .descendants()
+        .find(|n| n.has_tag_name("hostname"))
+        .and_then(|n| n.attribute("name"));
+
+    let host_addr = address_of("ipv4")
+        .or_else(|| address_of("ipv6"))
+        .or(hostname)
+        .unwrap_or("unknown")
+        .to_string();
+
+    let mut ports = Vec::new();
+    let mut services = Vec::new();
+
+    for port in host.descendants().filter(|n| n.has_tag_name("port")) {
+        let Some(port_num) = port.attribute("portid").and_then(|p| p.parse::<u16>().ok()) else {
+            continue;
+        };
+
+        let status = match port
+            .children()
+            .find(|n| n.has_tag_name("state"))
+            .and_then(|n| n.attribute("state"))
+        {
+            Some("open") => PortStatus::Open,
+            Some("closed") => PortStatus::Closed,
+            Some("open|filtered") => PortStatus::OpenFiltered,
+            _ => PortStatus::Filtered,
+        };
+
+        let service = port.children().find(|n| n.has_tag_name("service"));
+        let service_name = service.and_then(|s| s.attribute("name")).map(str::to_string);
+        let version = service.and_then(|s| s.attribute("version")).map(str::to_string);
+
+        if let Some(service) = service {
+            let name = service_name.clone().unwrap_or_else(|| "unknown".to_string());
+            services.push(ServiceInfo {
+                name: service.attribute("product").map(str::to_string).unwrap_or_else(|| name.clone()),
+                version: version.clone(),
                 info: Some(name),
+                cert_expiry: None,
+                proxy: None,
             });
         }
 
-        // Extract OS guess
-        if line.contains("<osmatch ") {
-            if let Some(name) = extract_attr(line, "name") {
-                result.os_guess = Some(name);
-            }
-        }
+        ports.push(PortInfo {
+            port: port_num,
+            status,
+            protocol: port.attribute("protocol").unwrap_or("tcp").to_string(),
+            service: service_name,
+            version,
+            address_family: AddressFamily::of_host(&host_addr),
+        });
     }
 
-    Ok(vec![result])
-}
-
-/// Extract attribute value from XML-like string
-fn extract_attr(line: &str, attr: &str) -> Option<String> {
-    // Simple string-based extraction
-    if let Some(start) = line.find(&format!(r#"{}=""#, attr)) {
-        let value_start = start + attr.len() + 2;
-        if let Some(end) = line[value_start..].find('"') {
-            return Some(line[value_start..value_start + end].to_string());
-        }
+    // nmap lists OS matches in descending order of accuracy
+    let os_match = host.descendants().find(|n| n.has_tag_name("osmatch"));
+    let os_guess = os_match
+        .and_then(|n| n.attribute("name"))
+        .map(str::to_string);
+    let os_confidence = os_match.filter(|_| os_guess.is_some()).map(|n| {
+        let accuracy: u8 = n.attribute("accuracy").and_then(|a| a.parse().ok()).unwrap_or(0);
+        if accuracy >= NMAP_HIGH_CONFIDENCE_ACCURACY { "high" } else { "low" }.to_string()
+    });
+
+    ScanResult {
+        host: host_addr,
+        hostname: hostname.map(str::to_string),
+        ports,
+        os_guess,
+        os_confidence,
+        services,
+        cached: false,
     }
-    None
 }
 
 #[cfg(test)]
@@ -518,14 +2198,535 @@ mod tests {
 
     #[tokio::test]
     async fn test_scan_localhost() {
-        let results = scan_localhost(Some(vec![8787]), false, false).await;
+        let options = ScanOptions { ports: Some(vec![8787]), ..Default::default() };
+        let results = scan_localhost(options, false, |_| {}).await;
         assert!(results.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_scan_localhost_detects_ipv6_only_listener() {
+        let Ok(listener) = std::net::TcpListener::bind("[::1]:0") else {
+            return; // No IPv6 loopback on this machine
+        };
+        let port = listener.local_addr().unwrap().port();
+
+        let options = ScanOptions { ports: Some(vec![port]), ..Default::default() };
+        let results = scan_localhost(options, true, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        let found: Vec<_> = results[0].ports.iter().filter(|p| p.port == port).collect();
+        assert_eq!(found.len(), 1);
+        assert!(matches!(found[0].status, PortStatus::Open));
+        assert_eq!(found[0].address_family, Some(AddressFamily::Ipv6));
+    }
+
+    #[test]
+    fn test_merge_loopback_results_counts_dual_stack_once() {
+        let port = |port: u16, host: &str| PortInfo {
+            port,
+            status: PortStatus::Open,
+            protocol: "tcp".to_string(),
+            service: None,
+            version: None,
+            address_family: AddressFamily::of_host(host),
+        };
+        let service = |name: &str| ServiceInfo {
+            name: name.to_string(),
+            version: None,
+            info: None,
+            cert_expiry: None,
+            proxy: None,
+        };
+        let mut v4 = sample_result();
+        v4.ports = vec![port(8787, "127.0.0.1"), port(22, "127.0.0.1")];
+        v4.services = vec![service("ssh")];
+        let mut v6 = sample_result();
+        v6.host = "::1".to_string();
+        v6.ports = vec![port(8787, "::1"), port(5173, "::1")];
+        v6.services = vec![service("ssh"), service("vite")];
+
+        let merged = merge_loopback_results(Some(v4), Some(v6));
+
+        let families: Vec<_> = merged.ports.iter().map(|p| (p.port, p.address_family)).collect();
+        assert_eq!(
+            families,
+            vec![
+                (22, Some(AddressFamily::Ipv4)),
+                (5173, Some(AddressFamily::Ipv6)),
+                (8787, Some(AddressFamily::Dual)),
+            ]
+        );
+        let names: Vec<_> = merged.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["ssh", "vite"]);
+        assert_eq!(merged.host, "127.0.0.1");
+    }
+
+    #[test]
+    fn test_service_info_without_newer_fields_deserializes() {
+        let info: ServiceInfo =
+            serde_json::from_str(r#"{"name": "http", "version": "1.18.0", "info": null}"#).unwrap();
+        assert_eq!(info.name, "http");
+        assert_eq!(info.cert_expiry, None);
+        assert_eq!(info.proxy, None);
+    }
+
+    #[tokio::test]
+    async fn test_probe_port_udp_reports_udp_protocol() {
+        let info = probe_port_udp("127.0.0.1".to_string(), 53, Duration::from_millis(200), None).await;
+        let info = info.expect("UDP probe should produce a result");
+        assert_eq!(info.port, 53);
+        assert_eq!(info.protocol, "udp");
+    }
+
+    #[tokio::test]
+    async fn test_scan_reports_silent_udp_port_as_open_filtered() {
+        // Bound but never read, so probes get neither a reply nor an ICMP error
+        let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let port = silent.local_addr().unwrap().port();
+
+        let info = probe_port_udp("127.0.0.1".to_string(), port, Duration::from_millis(100), None).await;
+        assert_eq!(info.unwrap().status, PortStatus::OpenFiltered);
+
+        let options = ScanOptions {
+            ports: Some(vec![port]),
+            protocols: vec![Protocol::Udp],
+            timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let results = scan_host("127.0.0.1", &options).await.unwrap();
+        let ports = &results[0].ports;
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].status, PortStatus::OpenFiltered);
+    }
+
+    #[test]
+    fn test_scan_options_with_tuning() {
+        let options = ScanOptions::default().with_tuning(Some(2000), Some(25)).unwrap();
+        assert_eq!(options.timeout, Duration::from_secs(2));
+        assert_eq!(options.parallelism, 25);
+
+        let options = ScanOptions::default().with_tuning(None, None).unwrap();
+        assert_eq!(options.timeout, ScanOptions::default().timeout);
+        assert_eq!(options.parallelism, ScanOptions::default().parallelism);
+
+        // Bounds are inclusive
+        assert!(ScanOptions::default().with_tuning(Some(10), Some(1)).is_ok());
+        assert!(ScanOptions::default().with_tuning(Some(10_000), Some(1000)).is_ok());
+    }
+
+    #[test]
+    fn test_scan_options_with_tuning_rejects_out_of_range() {
+        let err = ScanOptions::default().with_tuning(Some(5), None).unwrap_err();
+        assert_eq!(err, "timeout_ms must be between 10 and 10000, got 5");
+        assert!(ScanOptions::default().with_tuning(Some(10_001), None).is_err());
+
+        let err = ScanOptions::default().with_tuning(None, Some(0)).unwrap_err();
+        assert_eq!(err, "parallelism must be between 1 and 1000, got 0");
+        assert!(ScanOptions::default().with_tuning(None, Some(1001)).is_err());
+    }
+
+    #[test]
+    fn test_parse_port_spec() {
+        assert_eq!(parse_port_spec("80").unwrap(), vec![80]);
+        assert_eq!(parse_port_spec("22,80,443").unwrap(), vec![22, 80, 443]);
+        assert_eq!(parse_port_spec("1-3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_port_spec(" 443 , 80 ").unwrap(), vec![80, 443]);
+        assert_eq!(parse_port_spec("1-1024").unwrap().len(), 1024);
+        assert_eq!(parse_port_spec("8000-8002,9000,9090").unwrap(), vec![8000, 8001, 8002, 9000, 9090]);
+
+        // Overlapping ranges and duplicates are merged
+        assert_eq!(parse_port_spec("10-12,11-13,12").unwrap(), vec![10, 11, 12, 13]);
+        assert_eq!(parse_port_spec("5-5").unwrap(), vec![5]);
+    }
+
+    #[test]
+    fn test_parse_port_spec_errors() {
+        assert!(parse_port_spec("").is_err());
+        assert!(parse_port_spec("   ").is_err());
+        assert!(parse_port_spec("80,").is_err());
+        assert!(parse_port_spec("100-10").is_err());
+        assert!(parse_port_spec("65536").is_err());
+        assert!(parse_port_spec("1-70000").is_err());
+        assert!(parse_port_spec("0").is_err());
+        assert!(parse_port_spec("http").is_err());
+        assert!(parse_port_spec("1-2-3").is_err());
+    }
+
+    #[test]
+    fn test_validate_host() {
+        assert!(validate_host("192.168.1.50").is_ok());
+        assert!(validate_host("::1").is_ok());
+        assert!(validate_host("raspberrypi.local").is_ok());
+        assert!(validate_host("localhost").is_ok());
+
+        assert!(validate_host("").is_err());
+        assert!(validate_host("   ").is_err());
+        assert!(validate_host("host name").is_err());
+        assert!(validate_host("-bad.example").is_err());
+        assert!(validate_host("a..b").is_err());
+        assert!(validate_host("127.0.0.1:8080").is_err());
+        assert!(validate_host(&"a".repeat(300)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_host_returns_ip() {
+        assert_eq!(resolve_host("127.0.0.1").await.unwrap(), "127.0.0.1");
+        let resolved = resolve_host("localhost").await.unwrap();
+        assert!(resolved.parse::<std::net::IpAddr>().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scan_progress_reaches_total_with_no_open_ports() {
+        // Port 1 (tcpmux) is essentially never open on a dev machine
+        let options = ScanOptions {
+            ports: Some(vec![1]),
+            ..Default::default()
+        };
+        let mut updates = Vec::new();
+        let results = scan_host_with_progress("127.0.0.1", &options, |p| updates.push(p))
+            .await
+            .unwrap();
+
+        assert!(results[0].ports.is_empty());
+        let last = updates.last().expect("progress should fire at least once");
+        assert_eq!(last.scanned, last.total);
+        assert_eq!(last.total, 1);
+        assert_eq!(last.open_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_scan_returns_partial_result() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = ScanOptions {
+            ports: Some((1..=1000).collect()),
+            parallelism: 10,
+            cancel_token: Some(token),
+            ..Default::default()
+        };
+        let mut updates = Vec::new();
+        let results = scan_host_with_progress("127.0.0.1", &options, |p| updates.push(p))
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].ports.is_empty());
+        assert_eq!(updates.last().unwrap().scanned, 0);
+    }
+
+    #[tokio::test]
+    async fn test_closed_and_unreachable_ports_differ() {
+        // Grab a free port, then release it so nothing is listening there
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let closed = probe_port("127.0.0.1".to_string(), port, Duration::from_millis(500), 0, None)
+            .await
+            .unwrap();
+        assert!(matches!(closed.status, PortStatus::Closed));
+
+        // Timeouts and unreachable hosts are both reported as filtered
+        assert!(matches!(tcp_connect_status(None), PortStatus::Filtered));
+        let unreachable = std::io::Error::new(std::io::ErrorKind::HostUnreachable, "no route");
+        assert!(matches!(tcp_connect_status(Some(Err(unreachable))), PortStatus::Filtered));
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        assert!(matches!(tcp_connect_status(Some(Err(refused))), PortStatus::Closed));
+        assert!(matches!(tcp_connect_status(Some(Ok(()))), PortStatus::Open));
+    }
+
+    #[tokio::test]
+    async fn test_retry_while_filtered_stops_at_definitive_status() {
+        for (result, expected_attempts) in [(PortStatus::Open, 1), (PortStatus::Closed, 1), (PortStatus::Filtered, 3)] {
+            let mut attempts = 0;
+            let status = retry_while_filtered(2, || {
+                attempts += 1;
+                async move { Some(result) }
+            })
+            .await;
+            assert_eq!(status, Some(result));
+            assert_eq!(attempts, expected_attempts, "{result:?}");
+        }
+
+        // Filtered, then refused: the refusal wins and ends the retries
+        let mut attempts = 0;
+        let status = retry_while_filtered(5, || {
+            attempts += 1;
+            let status = if attempts == 1 { PortStatus::Filtered } else { PortStatus::Closed };
+            async move { Some(status) }
+        })
+        .await;
+        assert_eq!(status, Some(PortStatus::Closed));
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_probe_port_with_retries() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let open = probe_port("127.0.0.1".to_string(), port, Duration::from_millis(500), 2, None)
+            .await
+            .unwrap();
+        assert!(matches!(open.status, PortStatus::Open));
+
+        drop(listener);
+        let closed = probe_port("127.0.0.1".to_string(), port, Duration::from_millis(500), 2, None)
+            .await
+            .unwrap();
+        assert!(matches!(closed.status, PortStatus::Closed));
+    }
+
+    #[tokio::test]
+    async fn test_scan_host_streaming_reports_each_open_port() {
+        let mut listeners = Vec::new();
+        for _ in 0..3 {
+            listeners.push(tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap());
+        }
+        let mut open: Vec<u16> = listeners.iter().map(|l| l.local_addr().unwrap().port()).collect();
+        let closed = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+
+        let mut ports = open.clone();
+        ports.push(closed);
+        let options = ScanOptions {
+            ports: Some(ports),
+            timeout: Duration::from_millis(500),
+            ..Default::default()
+        };
+
+        let mut found = Vec::new();
+        let complete = scan_host_streaming("127.0.0.1", &options, |event| found.push(event))
+            .await
+            .unwrap();
+
+        assert!(found.iter().all(|event| event.host == "127.0.0.1"));
+        let mut found_ports: Vec<u16> = found.iter().map(|event| event.port.port).collect();
+        found_ports.sort_unstable();
+        open.sort_unstable();
+        assert_eq!(found_ports, open);
+        assert_eq!(complete.host, "127.0.0.1");
+        assert_eq!(complete.open_count, 3);
+        assert_eq!(complete.ports_scanned, 4);
+    }
+
+    #[tokio::test]
+    async fn test_probe_port_with_bind_addr() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let loopback = std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+        let open = probe_port("127.0.0.1".to_string(), port, Duration::from_millis(500), 0, Some(loopback))
+            .await
+            .unwrap();
+        assert!(matches!(open.status, PortStatus::Open));
+
+        // The connection really came from the bound address
+        let (_, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer.ip(), loopback);
+
+        // A bind address of the wrong family can't reach the target, which
+        // leaves the port out instead of calling it filtered
+        let v6 = Some(std::net::IpAddr::V6(std::net::Ipv6Addr::LOCALHOST));
+        assert!(connect_tcp(&socket_addr("127.0.0.1", port), v6).await.is_err());
+        assert!(probe_port("127.0.0.1".to_string(), port, Duration::from_millis(500), 0, v6)
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_validate_bind_addr() {
+        assert!(validate_bind_addr(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST)).is_ok());
+        // TEST-NET-1 is never assigned to a local interface
+        let err = validate_bind_addr("192.0.2.1".parse().unwrap()).unwrap_err();
+        assert!(err.starts_with("Can't scan from 192.0.2.1: "), "{err}");
+    }
+
+    #[test]
+    fn test_certificate_summary_describe() {
+        let summary = CertificateSummary {
+            subject_cn: Some("example.com".to_string()),
+            issuer: "CN=Test CA".to_string(),
+            not_after: "2030-01-01T00:00:00Z".to_string(),
+        };
+        assert_eq!(
+            summary.describe(),
+            "CN=example.com; issuer=CN=Test CA; notAfter=2030-01-01T00:00:00Z"
+        );
+        assert!(summarize_certificate(b"not a certificate").is_none());
+    }
+
+    #[cfg(feature = "tls-integration-tests")]
+    #[tokio::test]
+    async fn test_tls_certificate_from_known_endpoint() {
+        let port = PortInfo {
+            port: 443,
+            status: PortStatus::Open,
+            protocol: "tcp".to_string(),
+            service: Some("https".to_string()),
+            version: None,
+            address_family: None,
+        };
+        let service = detect_service_version("example.com", &port, Duration::from_secs(2))
+            .await
+            .expect("TLS endpoint should yield service info");
+        assert!(service.info.unwrap().contains("CN="));
+        assert!(service.cert_expiry.unwrap().ends_with('Z'));
+    }
+
+    #[tokio::test]
+    async fn test_localhost_is_alive() {
+        assert!(ping_host("127.0.0.1", Duration::from_millis(500)).await);
+        assert!(tcp_liveness_check("127.0.0.1".parse().unwrap(), Duration::from_millis(500)).await);
+    }
+
+    #[test]
+    fn test_expand_cidr() {
+        let hosts = expand_cidr("192.168.1.0/30", DEFAULT_MAX_SUBNET_HOSTS).unwrap();
+        let hosts: Vec<String> = hosts.iter().map(|ip| ip.to_string()).collect();
+        assert_eq!(hosts, vec!["192.168.1.1", "192.168.1.2"]);
+
+        assert_eq!(expand_cidr("192.168.1.0/24", DEFAULT_MAX_SUBNET_HOSTS).unwrap().len(), 254);
+        assert_eq!(expand_cidr("10.0.0.5/32", DEFAULT_MAX_SUBNET_HOSTS).unwrap().len(), 1);
+        assert_eq!(expand_cidr("10.0.0.0/16", DEFAULT_MAX_SUBNET_HOSTS).unwrap().len(), 65534);
+
+        assert!(expand_cidr("10.0.0.0/15", DEFAULT_MAX_SUBNET_HOSTS).is_err());
+        assert!(expand_cidr("fd00::/64", DEFAULT_MAX_SUBNET_HOSTS).is_err());
+        assert!(expand_cidr("192.168.1.0/24", 16).is_err());
+        assert!(expand_cidr("192.168.1.0", DEFAULT_MAX_SUBNET_HOSTS).is_err());
+        assert!(expand_cidr("not-a-cidr", DEFAULT_MAX_SUBNET_HOSTS).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_subnet_reports_responsive_hosts() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let options = ScanOptions {
+            ports: Some(vec![port]),
+            host_parallelism: 2,
+            ..Default::default()
+        };
+        let results = scan_subnet("127.0.0.1/32".to_string(), options).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].host, "127.0.0.1");
+        assert_eq!(results[0].ports[0].port, port);
+    }
+
+    #[test]
+    fn test_os_from_ttl() {
+        assert_eq!(os_from_ttl(64), Some("Unix/Linux"));
+        // Hops along the route decrement the initial TTL
+        assert_eq!(os_from_ttl(57), Some("Unix/Linux"));
+        assert_eq!(os_from_ttl(128), Some("Windows"));
+        assert_eq!(os_from_ttl(113), Some("Windows"));
+        assert_eq!(os_from_ttl(255), Some("Network device"));
+        assert_eq!(os_from_ttl(240), Some("Network device"));
+        assert_eq!(os_from_ttl(0), None);
+    }
+
+    #[test]
+    fn test_detect_os_from_ports_fallback() {
+        let port = |port| PortInfo {
+            port,
+            status: PortStatus::Open,
+            protocol: "tcp".to_string(),
+            service: None,
+            version: None,
+            address_family: None,
+        };
+        assert_eq!(detect_os_from_ports(&[port(3389)]).as_deref(), Some("Windows"));
+        assert_eq!(detect_os_from_ports(&[port(22)]).as_deref(), Some("Unix/Linux"));
+        assert_eq!(detect_os_from_ports(&[]), None);
+    }
+
+    fn sample_result() -> ScanResult {
+        ScanResult {
+            host: "127.0.0.1".to_string(),
+            hostname: None,
+            ports: Vec::new(),
+            os_guess: None,
+            os_confidence: None,
+            services: Vec::new(),
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn test_scan_cache_hit_marks_cached() {
+        let mut cache = ScanCache::default();
+        let key = ScanCacheKey::new("127.0.0.1", Some(&[80, 443]), false, false, false);
+        cache.insert(key.clone(), &[sample_result()]);
+
+        let hit = cache.get(&key, DEFAULT_SCAN_CACHE_TTL).expect("should be cached");
+        assert_eq!(hit.len(), 1);
+        assert!(hit[0].cached);
+
+        // Port order and duplicates don't change the key
+        let reordered = ScanCacheKey::new("127.0.0.1", Some(&[443, 80, 80]), false, false, false);
+        assert!(cache.get(&reordered, DEFAULT_SCAN_CACHE_TTL).is_some());
+
+        cache.clear();
+        assert!(cache.get(&key, DEFAULT_SCAN_CACHE_TTL).is_none());
+    }
+
+    #[test]
+    fn test_scan_cache_respects_ttl_and_flags() {
+        let mut cache = ScanCache::default();
+        let quick = ScanCacheKey::new("127.0.0.1", None, false, false, false);
+        cache.insert(quick.clone(), &[sample_result()]);
+
+        // A detailed scan must not be served from a quick-scan entry
+        let detailed = ScanCacheKey::new("127.0.0.1", None, true, true, false);
+        assert!(cache.get(&detailed, DEFAULT_SCAN_CACHE_TTL).is_none());
+        let other_host = ScanCacheKey::new("192.168.1.50", None, false, false, false);
+        let slow = ScanCacheKey::new("127.0.0.1", None, false, false, false).with_timeout(Duration::from_secs(2));
+        assert_ne!(quick, slow);
+        assert!(cache.get(&other_host, DEFAULT_SCAN_CACHE_TTL).is_none());
+
+        // A zero TTL always misses and evicts the entry
+        assert!(cache.get(&quick, Duration::ZERO).is_none());
+        assert!(cache.get(&quick, DEFAULT_SCAN_CACHE_TTL).is_none());
+    }
+
+    #[test]
+    fn test_default_options_are_tcp_only() {
+        assert_eq!(ScanOptions::default().protocols, vec![Protocol::Tcp]);
+    }
+
     #[test]
     fn test_service_fingerprints() {
-        assert!(SERVICE_FINGERPRINTS.iter().any(|(p, _)| *p == 22));
-        assert!(SERVICE_FINGERPRINTS.iter().any(|(p, _)| *p == 8787));
+        let builtin = builtin_fingerprints();
+        assert_eq!(builtin.get(&22), Some(&"ssh"));
+        assert_eq!(builtin.get(&8787), Some(&"side-ide"));
+        assert_eq!(builtin.get(&6379), Some(&"redis"));
+        assert_eq!(builtin.get(&27017), Some(&"mongodb"));
+        assert_eq!(builtin.get(&9200), Some(&"elasticsearch"));
+
+        // Without an override file the built-in table is used as-is
+        let merged = load_fingerprints(Some(Path::new("/nonexistent/fingerprints.json")));
+        assert_eq!(merged.len(), builtin.len());
+    }
+
+    #[test]
+    fn test_user_fingerprint_override_shadows_builtin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join("fingerprints.json");
+        std::fs::write(&path, r#"{ "3000": "grafana", "4000": "my-service", "bogus": "x" }"#).unwrap();
+
+        let merged = load_fingerprints(Some(&path));
+        assert_eq!(merged.get(&3000).map(String::as_str), Some("grafana"));
+        assert_eq!(merged.get(&4000).map(String::as_str), Some("my-service"));
+        assert_eq!(merged.get(&22).map(String::as_str), Some("ssh"));
+
+        std::fs::write(&path, "not json").unwrap();
+        let merged = load_fingerprints(Some(&path));
+        assert_eq!(merged.get(&3000).map(String::as_str), Some("nodejs"));
     }
 
     #[test]
@@ -552,9 +2753,326 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_attr() {
-        let xml = r#"<port protocol="tcp" portid="80">"#;
-        assert_eq!(extract_attr(xml, "protocol"), Some("tcp".to_string()));
-        assert_eq!(extract_attr(xml, "portid"), Some("80".to_string()));
+    fn test_parse_nmap_xml_multiple_hosts() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<nmaprun scanner="nmap" args="nmap -sV -O -oX - 192.168.1.0/30">
+  <host starttime="1" endtime="2">
+    <status state="up" reason="arp-response"/>
+    <address addr="192.168.1.1" addrtype="ipv4"/>
+    <address addr="AA:BB:CC:DD:EE:FF" addrtype="mac" vendor="Router"/>
+    <hostnames><hostname name="router.lan" type="PTR"/></hostnames>
+    <ports>
+      <port protocol="tcp"
+            portid="80">
+        <state state="open" reason="syn-ack"/>
+        <service name="http" product="nginx" version="1.18.0"/>
+      </port>
+      <port protocol="udp" portid="53">
+        <state state="open|filtered" reason="no-response"/>
+      </port>
+    </ports>
+    <os><osmatch name="Linux 5.4" accuracy="98"/><osmatch name="Linux 4.15" accuracy="90"/></os>
+  </host>
+  <host>
+    <address addr="fe80::1" addrtype="ipv6"/>
+    <ports>
+      <port protocol="tcp" portid="22">
+        <state state="closed" reason="reset"/>
+        <service name="ssh"/>
+      </port>
+    </ports>
+  </host>
+  <host>
+    <hostnames><hostname name="nas.lan" type="user"/></hostnames>
+    <ports/>
+  </host>
+</nmaprun>"#;
+
+        let results = parse_nmap_xml(xml).unwrap();
+        assert_eq!(results.len(), 3);
+
+        let router = &results[0];
+        assert_eq!(router.host, "192.168.1.1");
+        assert_eq!(router.ports.len(), 2);
+        assert_eq!(router.ports[0].port, 80);
+        assert!(matches!(router.ports[0].status, PortStatus::Open));
+        assert_eq!(router.ports[0].service.as_deref(), Some("http"));
+        assert_eq!(router.ports[0].version.as_deref(), Some("1.18.0"));
+        assert_eq!(router.ports[1].protocol, "udp");
+        assert_eq!(router.ports[1].status, PortStatus::OpenFiltered);
+        assert_eq!(router.services.len(), 1);
+        assert_eq!(router.services[0].name, "nginx");
+        assert_eq!(router.os_guess.as_deref(), Some("Linux 5.4"));
+        assert_eq!(router.os_confidence.as_deref(), Some("high"));
+
+        let second = &results[1];
+        assert_eq!(second.host, "fe80::1");
+        assert_eq!(second.ports.len(), 1);
+        assert_eq!(second.ports[0].port, 22);
+        assert!(matches!(second.ports[0].status, PortStatus::Closed));
+        assert!(second.os_guess.is_none());
+
+        assert_eq!(results[2].host, "nas.lan");
+        assert!(results[2].ports.is_empty());
+    }
+
+    #[test]
+    fn test_parse_nmap_xml_rejects_malformed() {
+        assert!(parse_nmap_xml("<nmaprun><host>").is_err());
+        assert!(parse_nmap_xml("<nmaprun></nmaprun>").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_extract_ssh_version() {
+        assert_eq!(
+            extract_ssh_version("SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13\r\n").as_deref(),
+            Some("OpenSSH_9.6p1")
+        );
+        assert_eq!(extract_ssh_version("SSH-1.99-dropbear_2022.83\r\n").as_deref(), Some("dropbear_2022.83"));
+        assert_eq!(extract_ssh_version("HTTP/1.1 400 Bad Request\r\n"), None);
+    }
+
+    #[test]
+    fn test_extract_smtp_version() {
+        assert_eq!(
+            extract_smtp_version("220 mail.example.com ESMTP Exim 4.96 Mon, 01 Jan 2024 10:00:00 +0000\r\n").as_deref(),
+            Some("Exim 4.96")
+        );
+        assert_eq!(
+            extract_smtp_version("220 mx.example.org ESMTP Sendmail 8.15.2/8.15.2; Mon, 1 Jan 2024\r\n").as_deref(),
+            Some("Sendmail 8.15.2")
+        );
+        // Greetings that only name the protocol
+        assert_eq!(
+            extract_smtp_version("220 mx.example.org ESMTP\r\n250-mx.example.org\r\n").as_deref(),
+            None
+        );
+        assert_eq!(extract_smtp_version("220 mail.example.com ESMTP Postfix (Ubuntu)\r\n"), None);
+    }
+
+    #[test]
+    fn test_extract_http_version() {
+        let response = "HTTP/1.0 200 OK\r\nContent-Type: text/html\r\nserver: nginx/1.25.3 \r\n\r\n";
+        assert_eq!(extract_http_version(response).as_deref(), Some("nginx/1.25.3"));
+        assert_eq!(extract_http_version("HTTP/1.0 200 OK\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn test_detect_reverse_proxy_from_server_header() {
+        let nginx = "HTTP/1.1 301 Moved Permanently\r\nServer: nginx/1.25.3\r\nLocation: https://example.com/\r\n\r\n";
+        assert_eq!(detect_reverse_proxy(nginx).as_deref(), Some("nginx"));
+
+        let caddy = "HTTP/1.1 200 OK\r\nserver: Caddy\r\nContent-Type: text/html\r\n\r\n";
+        assert_eq!(detect_reverse_proxy(caddy).as_deref(), Some("caddy"));
+
+        let traefik = "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nServer: Traefik\r\n\r\n";
+        assert_eq!(detect_reverse_proxy(traefik).as_deref(), Some("traefik"));
+    }
+
+    #[test]
+    fn test_detect_reverse_proxy_prefers_via() {
+        // The app behind the proxy sets Server; the proxy adds Via
+        let response = "HTTP/1.1 200 OK\r\nServer: gunicorn\r\nVia: 1.1 Caddy\r\n\r\n";
+        assert_eq!(detect_reverse_proxy(response).as_deref(), Some("caddy"));
+
+        let response = "HTTP/1.1 200 OK\r\nServer: nginx\r\nVia: 1.1 traefik-edge (traefik)\r\n\r\n";
+        assert_eq!(detect_reverse_proxy(response).as_deref(), Some("traefik"));
+    }
+
+    #[test]
+    fn test_detect_reverse_proxy_ignores_app_servers() {
+        let express = "HTTP/1.1 200 OK\r\nX-Powered-By: Express\r\nServer: Werkzeug/3.0.1 Python/3.12.1\r\n\r\n";
+        assert_eq!(detect_reverse_proxy(express), None);
+        // Only headers count, not the body
+        assert_eq!(detect_reverse_proxy("HTTP/1.1 200 OK\r\n\r\n<p>Served by nginx</p>"), None);
+    }
+
+    #[test]
+    fn test_banner_probe_for_port() {
+        assert_eq!(BannerProbe::for_port(22), Some(BannerProbe::Ssh));
+        assert_eq!(BannerProbe::for_port(587), Some(BannerProbe::Smtp));
+        assert_eq!(BannerProbe::for_port(8080), Some(BannerProbe::Http));
+        assert_eq!(BannerProbe::for_port(6379), None);
+        assert!(BannerProbe::Ssh.payload().is_empty());
+        assert_eq!(BannerProbe::Smtp.payload(), b"EHLO side-ide\r\n");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_spaces_probes() {
+        let limiter = RateLimiter::new(Some(10)).unwrap();
+        let started = tokio::time::Instant::now();
+
+        let mut probes = Vec::new();
+        for _ in 0..20 {
+            limiter.acquire().await;
+            probes.push(tokio::spawn(async {}));
+        }
+        for probe in probes {
+            probe.await.unwrap();
+        }
+
+        // The first probe starts immediately, the other 19 are 100ms apart
+        assert!(started.elapsed() >= Duration::from_millis(1900));
+        assert!(RateLimiter::new(None).is_none());
+        assert!(RateLimiter::new(Some(0)).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_above_one_per_nanosecond() {
+        let limiter = RateLimiter::new(Some(u32::MAX)).unwrap();
+        limiter.acquire().await;
+        limiter.acquire().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_socket_budget_caps_concurrent_probes() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let budget = SocketBudget::new(5);
+        let open = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        // As if several hosts each fired a batch of probes at once
+        let mut probes = Vec::new();
+        for _ in 0..60 {
+            let (open, peak) = (open.clone(), peak.clone());
+            probes.push(tokio::spawn(budget.clone().run(async move {
+                let now_open = open.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now_open, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                open.fetch_sub(1, Ordering::SeqCst);
+            })));
+        }
+        for probe in probes {
+            probe.await.unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 5);
+        assert_eq!(open.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_with_timeout_kills_hung_process() {
+        let started = std::time::Instant::now();
+        let mut cmd = tokio::process::Command::new("sleep");
+        cmd.arg("30");
+
+        let err = run_with_timeout(cmd, Duration::from_millis(200)).await.err().unwrap();
+
+        assert_eq!(err, "sleep did not finish within 200ms and was stopped");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_with_timeout_captures_output() {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", "echo '<nmaprun/>'; echo 'Stats: 0:00:01 elapsed' >&2"]);
+
+        let output = run_with_timeout(cmd, Duration::from_secs(10)).await.unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"<nmaprun/>\n");
+        assert_eq!(output.stderr, "Stats: 0:00:01 elapsed\n");
+    }
+
+    #[test]
+    fn test_parse_nmap_version() {
+        let output = "Nmap version 7.94 ( https://nmap.org )\n\
+                      Platform: x86_64-pc-linux-gnu\n\
+                      Compiled with: liblua-5.4.6 openssl-3.0.13 libssh2-1.11.0\n";
+        assert_eq!(parse_nmap_version(output).as_deref(), Some("7.94"));
+        assert_eq!(parse_nmap_version("\nNmap version 7.95SVN ( https://nmap.org )\n").as_deref(), Some("7.95SVN"));
+        assert_eq!(parse_nmap_version("nmap: command not found"), None);
+        assert_eq!(parse_nmap_version(""), None);
+    }
+
+    #[test]
+    fn test_nmap_status_is_cached_until_refreshed() {
+        let cache = crate::common::CommandCache::new();
+        let status = |version: Option<&str>| NmapStatus {
+            available: version.is_some(),
+            version: version.map(str::to_string),
+            install_hint: nmap_install_hint().to_string(),
+        };
+        let mut probes = 0;
+
+        assert_eq!(nmap_status_with(&cache, false, || { probes += 1; status(None) }), status(None));
+        // Installed since, but the cached "missing" result still stands
+        assert_eq!(nmap_status_with(&cache, false, || { probes += 1; status(Some("7.94")) }), status(None));
+        assert_eq!(probes, 1);
+
+        assert_eq!(nmap_status_with(&cache, true, || { probes += 1; status(Some("7.94")) }), status(Some("7.94")));
+        assert_eq!(probes, 2);
+        assert!(!nmap_install_hint().is_empty());
+    }
+
+    #[test]
+    fn test_validate_nmap_flags_accepts_allowlisted() {
+        let flags: Vec<String> = [
+            "-sV",
+            "-T2",
+            "--script=http-title,ssl-cert",
+            "--top-ports=100",
+            "--script-args=http.useragent=side-ide",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        assert!(validate_nmap_flags(&flags).is_ok());
+        assert!(validate_nmap_flags(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_nmap_flags_rejects_unsafe() {
+        let rejected = |flag: &str| validate_nmap_flags(&["-sV".to_string(), flag.to_string()]).unwrap_err();
+
+        assert_eq!(rejected("-oN"), "nmap output flags are not allowed: -oN");
+        assert!(rejected("-oX=/etc/passwd").contains("output flags"));
+        assert!(rejected("--append-output").contains("output flags"));
+        assert_eq!(rejected("-iL"), "nmap flag is not allowed: -iL");
+        assert_eq!(rejected("/etc/passwd"), "nmap flag is not allowed: /etc/passwd");
+        assert_eq!(rejected("--script=/tmp/evil.nse"), "nmap flag value is not allowed: --script=/tmp/evil.nse");
+        assert!(rejected("--script").contains("not allowed"));
+        assert!(rejected("--datadir=/tmp").contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_reverse_lookup() {
+        // Most systems map 127.0.0.1 to `localhost` in their hosts file
+        if let Some(name) = reverse_lookup("127.0.0.1").await {
+            assert!(name.starts_with("localhost"), "unexpected PTR name {name}");
+        }
+        // TEST-NET-1 is reserved for documentation and has no PTR records
+        assert_eq!(reverse_lookup("192.0.2.1").await, None);
+        assert_eq!(reverse_lookup("not-an-ip").await, None);
+    }
+
+    #[test]
+    fn test_scan_summary_counts_open_ports() {
+        let port = |port: u16, status: PortStatus| PortInfo {
+            port,
+            status,
+            protocol: "tcp".to_string(),
+            service: None,
+            version: None,
+            address_family: None,
+        };
+        let mut first = sample_result();
+        first.ports = vec![port(22, PortStatus::Open), port(80, PortStatus::Open), port(25, PortStatus::Filtered)];
+        let mut second = sample_result();
+        second.host = "127.0.0.2".to_string();
+        second.ports = vec![port(8787, PortStatus::Open)];
+
+        let summary = ScanSummary::new(vec![first, second], 1000, Duration::from_millis(3200));
+
+        assert_eq!(summary.results.len(), 2);
+        assert_eq!(summary.open_count, 3);
+        assert_eq!(summary.ports_scanned, 1000);
+        assert_eq!(summary.duration_ms, 3200);
+        assert_eq!(scanned_port_count(Some(&[80, 443])), 2);
+        assert_eq!(scanned_port_count(None), COMMON_PORTS.len());
     }
 }
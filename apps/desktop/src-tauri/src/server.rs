@@ -1,8 +1,16 @@
 //! Server process management for the Node.js backend
 
 use crate::common;
+use crate::log_file;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::{Command, Child};
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 /// Maximum number of parent directories to search when finding project root
 pub const MAX_SEARCH_DEPTH: usize = 10;
@@ -10,25 +18,439 @@ pub const MAX_SEARCH_DEPTH: usize = 10;
 /// Maximum number of parent directories to search from exe
 pub const MAX_EXE_SEARCH_DEPTH: usize = 5;
 
+/// Default number of automatic restarts after unexpected exits
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// Delay before the first automatic restart; doubled for each further attempt
+pub const RESTART_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Upper bound for the delay between automatic restarts
+pub const RESTART_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a server gets to exit after a shutdown request before it is killed
+pub const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Event emitted after the supervisor restarts a crashed server
+pub const SERVER_RESTARTED_EVENT: &str = "server-restarted";
+
+/// Health check endpoint used unless the config names another
+pub const DEFAULT_HEALTH_PATH: &str = "/health";
+
+/// Time between health requests while waiting for the server
+pub const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long a single health request may take
+pub const HEALTH_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Package manager used to run the development server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    #[default]
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+}
+
+impl PackageManager {
+    /// Name of the package manager's executable
+    pub fn executable_name(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Bun => "bun",
+        }
+    }
+
+    /// Finds the package manager's command on the system
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package manager is not installed
+    pub fn find_command(&self) -> Result<String, String> {
+        match self {
+            PackageManager::Npm => common::find_npm_command(),
+            PackageManager::Pnpm => common::find_pnpm_command(),
+            PackageManager::Yarn => common::find_yarn_command(),
+            PackageManager::Bun => common::find_bun_command(),
+        }
+    }
+}
+
+/// Configuration for a managed server
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// The port to run the server on
+    pub port: u16,
+    /// How many times to restart the server after it crashes
+    pub max_restarts: u32,
+    /// Extra environment variables for the server process
+    ///
+    /// `PORT` and `DB_PATH` are always set by us and can't be overridden.
+    pub extra_env: HashMap<String, String>,
+    /// How long to wait for a clean exit before killing the server
+    pub shutdown_grace_period: Duration,
+    /// Package manager running `run dev` in development mode
+    pub package_manager: PackageManager,
+    /// Accept ports below 1024 (needs root or `CAP_NET_BIND_SERVICE`)
+    pub allow_privileged_port: bool,
+}
+
+impl ServerConfig {
+    /// Configuration with default restart behavior
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            extra_env: HashMap::new(),
+            shutdown_grace_period: SHUTDOWN_GRACE_PERIOD,
+            package_manager: PackageManager::default(),
+            allow_privileged_port: false,
+        }
+    }
+}
+
+/// Rejects environment variable names that are empty or contain whitespace,
+/// `=` or NUL
+///
+/// # Errors
+///
+/// Returns an error naming the first invalid key
+pub fn validate_env_keys(env: &HashMap<String, String>) -> Result<(), String> {
+    for key in env.keys() {
+        if key.is_empty() {
+            return Err("Environment variable name must not be empty".to_string());
+        }
+        if key.chars().any(|c| c.is_whitespace() || c == '=' || c == '\0') {
+            return Err(format!("Invalid environment variable name: {key:?}"));
+        }
+    }
+    Ok(())
+}
+
+/// Payload of [`SERVER_RESTARTED_EVENT`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServerRestarted {
+    /// The port the server runs on
+    pub port: u16,
+    /// Restart attempt number, starting at 1
+    pub attempt: u32,
+}
+
+/// Delay before restart attempt `attempt` (0-based), doubling up to [`RESTART_MAX_DELAY`]
+pub fn restart_backoff(attempt: u32) -> Duration {
+    RESTART_BASE_DELAY
+        .checked_mul(2u32.saturating_pow(attempt))
+        .map_or(RESTART_MAX_DELAY, |delay| delay.min(RESTART_MAX_DELAY))
+}
+
+/// Maximum number of server output lines kept in memory
+pub const MAX_LOG_LINES: usize = 1000;
+
+/// Bounded ring buffer of server output lines
+#[derive(Clone)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+    /// Also persists every line when set
+    file: Option<log_file::LogFileSink>,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(MAX_LOG_LINES)
+    }
+}
+
+impl LogBuffer {
+    /// Creates an empty buffer keeping at most `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+            file: None,
+        }
+    }
+
+    /// Also appends every pushed line to `file`
+    pub fn with_file(mut self, file: Option<log_file::LogFileSink>) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Appends a line, dropping the oldest one when full
+    pub fn push(&self, line: String) {
+        if let Some(file) = &self.file {
+            file.write_line(&line);
+        }
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Returns all buffered lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.tail(self.capacity)
+    }
+
+    /// Returns the last `n` buffered lines, oldest first
+    pub fn tail(&self, n: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().skip(lines.len().saturating_sub(n)).cloned().collect()
+    }
+
+    /// Feeds the child's piped stdout and stderr into this buffer
+    ///
+    /// Streams that weren't configured as piped are ignored.
+    pub fn capture(&self, child: &mut Child) {
+        if let Some(stdout) = child.stdout.take() {
+            self.spawn_reader(stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.spawn_reader(stderr);
+        }
+    }
+
+    fn spawn_reader<R: AsyncRead + Unpin + Send + 'static>(&self, stream: R) {
+        let buffer = self.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stream).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                buffer.push(line);
+            }
+        });
+    }
+}
+
+/// The currently running server process
+#[derive(Debug, Clone, Copy)]
+pub struct ServerProcess {
+    /// OS process id
+    pub pid: u32,
+    /// When the process was spawned
+    pub started_at: Instant,
+}
+
+/// Shared slot for the current server process, `None` once it has exited
+type ProcessSlot = Arc<Mutex<Option<ServerProcess>>>;
+
+/// Record a freshly spawned child in the process slot
+fn record_process(slot: &ProcessSlot, child: &Child) {
+    *slot.lock().unwrap_or_else(|e| e.into_inner()) = child.id().map(|pid| ServerProcess {
+        pid,
+        started_at: Instant::now(),
+    });
+}
+
+/// Whole seconds elapsed between `started_at` and `now`
+pub fn uptime_secs(started_at: Instant, now: Instant) -> u64 {
+    now.saturating_duration_since(started_at).as_secs()
+}
+
 /// Handle to a running server process
+///
+/// The child process is owned by a supervisor task that restarts it after
+/// unexpected exits; the handle only controls that task.
 pub struct ServerHandle {
-    /// The child process
-    child: Child,
     /// The port the server is running on
     pub port: u16,
+    /// Captured stdout/stderr of the server
+    logs: LogBuffer,
+    /// The current child process, updated on restart
+    process: ProcessSlot,
+    /// Set before a requested stop so the exit isn't treated as a crash
+    intentional_stop: Arc<AtomicBool>,
+    /// Cancels the supervisor, which then kills the child
+    stop_token: CancellationToken,
+    /// The supervisor task
+    supervisor: Option<JoinHandle<Result<(), String>>>,
+    /// When the server was first spawned; restarts don't reset it
+    started_at: Instant,
+}
+
+impl ServerHandle {
+    /// Captured server output
+    pub fn logs(&self) -> &LogBuffer {
+        &self.logs
+    }
+
+    /// When the server was first spawned
+    pub fn started_at(&self) -> Instant {
+        self.started_at
+    }
+
+    /// The running server process, or `None` if it has exited
+    pub fn process(&self) -> Option<ServerProcess> {
+        *self.process.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 // Implement Drop to ensure process cleanup on orphaning
 impl Drop for ServerHandle {
     fn drop(&mut self) {
-        // Try to kill the child process when handle is dropped
+        // Stop the supervisor, which kills the child process
         // This prevents process orphaning
-        let _ = self.child.start_kill();
+        self.intentional_stop.store(true, Ordering::SeqCst);
+        self.stop_token.cancel();
+    }
+}
+
+/// Environment variable pointing at a custom server script
+pub const SERVER_PATH_ENV: &str = "S_IDE_SERVER_PATH";
+
+/// Persisted server settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ServerSettings {
+    /// Custom server script used instead of the bundled/downloaded server
+    #[serde(default)]
+    pub server_path: Option<PathBuf>,
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    Ok(common::home_dir()?.join(".side-ide").join("server.json"))
+}
+
+/// Loads the persisted server settings, or defaults if missing or invalid
+pub fn load_settings() -> ServerSettings {
+    settings_path()
+        .and_then(|path| std::fs::read_to_string(path).map_err(|e| e.to_string()))
+        .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+        .unwrap_or_default()
+}
+
+/// Persists the server settings
+///
+/// # Errors
+///
+/// Returns an error if the settings file can't be written
+pub fn save_settings(settings: &ServerSettings) -> Result<(), String> {
+    let path = settings_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {e}"))?;
     }
+
+    let data = serde_json::to_string_pretty(settings).map_err(|e| format!("Invalid JSON: {e}"))?;
+    std::fs::write(&path, data).map_err(|e| format!("Failed to write settings: {e}"))?;
+    SERVER_PATH_OVERRIDE.clear();
+    Ok(())
+}
+
+/// Server state remembered between runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RuntimeState {
+    /// Port of the most recently started server
+    #[serde(default)]
+    pub last_port: Option<u16>,
+}
+
+fn runtime_state_path() -> Result<PathBuf, String> {
+    Ok(common::home_dir()?.join(".side-ide").join("state.json"))
+}
+
+/// Loads the runtime state from `path`, or defaults if missing or corrupt
+pub fn load_runtime_state_from(path: &Path) -> RuntimeState {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Persists the runtime state to `path`
+///
+/// # Errors
+///
+/// Returns an error if the state file can't be written
+pub fn save_runtime_state_to(path: &Path, state: &RuntimeState) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {e}"))?;
+    }
+
+    let data = serde_json::to_string_pretty(state).map_err(|e| format!("Invalid JSON: {e}"))?;
+    std::fs::write(path, data).map_err(|e| format!("Failed to write state: {e}"))
+}
+
+/// Remembers `port` as the last started server port
+///
+/// # Errors
+///
+/// Returns an error if the state file can't be written
+pub fn save_last_port(port: u16) -> Result<(), String> {
+    let path = runtime_state_path()?;
+    let mut state = load_runtime_state_from(&path);
+    state.last_port = Some(port);
+    save_runtime_state_to(&path, &state)
+}
+
+/// Port of the most recently started server, or [`common::DEFAULT_PORT`]
+pub fn last_known_port() -> u16 {
+    runtime_state_path()
+        .ok()
+        .and_then(|path| load_runtime_state_from(&path).last_port)
+        .unwrap_or(common::DEFAULT_PORT)
+}
+
+/// Checks that `path` is an existing `.js` file
+///
+/// # Errors
+///
+/// Returns an error describing why the path can't be used as a server script
+pub fn validate_server_script(path: &Path) -> Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("Server script not found: {}", path.display()));
+    }
+    if path.extension().and_then(|e| e.to_str()) != Some("js") {
+        return Err(format!("Server script must be a .js file: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Resolved [`server_path_override`], cleared when the settings are saved
+static SERVER_PATH_OVERRIDE: common::CommandCache<Option<PathBuf>> = common::CommandCache::new();
+
+/// Custom server script from [`SERVER_PATH_ENV`] or the persisted setting
+///
+/// Resolved once and cached until [`save_settings`] changes the setting.
+pub fn server_path_override() -> Option<PathBuf> {
+    SERVER_PATH_OVERRIDE.get_or_resolve(|| {
+        let env_path = std::env::var_os(SERVER_PATH_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+        resolve_server_path_override(env_path, load_settings().server_path)
+    })
+}
+
+/// Picks the custom server script from the environment and settings values
+///
+/// The environment variable takes precedence. Invalid paths are logged and
+/// ignored so the regular search still runs.
+pub fn resolve_server_path_override(env_path: Option<PathBuf>, settings_path: Option<PathBuf>) -> Option<PathBuf> {
+    let candidates = [(SERVER_PATH_ENV, env_path), ("server settings", settings_path)];
+
+    for (source, path) in candidates {
+        let Some(path) = path else { continue };
+        match validate_server_script(&path) {
+            Ok(()) => {
+                tracing::info!(target: "Server", "Using server from {source}: {}", path.display());
+                return Some(path);
+            }
+            Err(e) => tracing::warn!(target: "Server", "Ignoring server path from {source}: {e}"),
+        }
+    }
+    None
 }
 
 /// Path to the bundled Node.js server executable
-fn get_server_path() -> Result<PathBuf, String> {
+///
+/// A path configured via [`server_path_override`] skips the search.
+pub fn get_server_path() -> Result<PathBuf, String> {
+    if let Some(path) = server_path_override() {
+        return Ok(path);
+    }
+
     let exe_path = std::env::current_exe()
         .map_err(|e| format!("Failed to get exe path: {e}"))?;
 
@@ -66,21 +488,194 @@ fn get_server_path() -> Result<PathBuf, String> {
     Ok(exe_dir.join("resources").join("server").join("index.js"))
 }
 
-/// Starts the server on the specified port
+/// [`get_server_path`], failing if nothing is there
+///
+/// # Errors
+///
+/// Returns an error if the path can't be resolved or the script is missing
+pub fn find_server_script() -> Result<PathBuf, String> {
+    let server_path = get_server_path()?;
+    if !server_path.exists() {
+        return Err(format!(
+            "Server executable not found at: {}. Ensure resources are bundled correctly.",
+            server_path.display()
+        ));
+    }
+    Ok(server_path)
+}
+
+/// Directory of the server package, whose `dev` script runs in development
+///
+/// # Errors
+///
+/// Returns an error if the project root or `apps/server` can't be found
+pub fn find_dev_server_dir() -> Result<PathBuf, String> {
+    let project_root = find_project_root().map_err(|e| format!("Failed to find project root: {e}"))?;
+    let server_dir = project_root.join("apps").join("server");
+    if !server_dir.is_dir() {
+        return Err(format!("Dev server not found at: {}", server_dir.display()));
+    }
+    Ok(server_dir)
+}
+
+/// Checks that `path` can be appended to a server's base URL
+///
+/// # Errors
+///
+/// Returns an error unless `path` starts with a single `/` and has no
+/// whitespace, query or fragment
+pub fn validate_health_path(path: &str) -> Result<(), String> {
+    if !path.starts_with('/') || path.starts_with("//") {
+        return Err(format!("Health check path must start with a single '/', got {path:?}"));
+    }
+    if path.chars().any(|c| c.is_whitespace() || c.is_control() || c == '?' || c == '#') {
+        return Err(format!("Health check path must be a plain URL path, got {path:?}"));
+    }
+    Ok(())
+}
+
+/// Result of waiting for a server to become ready
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ServerReadiness {
+    /// Whether the health endpoint answered successfully before the timeout
+    pub ready: bool,
+    /// How long the wait took
+    pub elapsed_ms: u64,
+}
+
+/// Polls `http://localhost:{port}{health_path}` until it succeeds or `timeout` passes
+pub async fn wait_for_ready(port: u16, health_path: &str, timeout: Duration) -> ServerReadiness {
+    wait_for_health(&format!("http://localhost:{port}"), health_path, timeout, HEALTH_POLL_INTERVAL).await
+}
+
+/// Polls `{base_url}{health_path}` every `interval` until it succeeds or `timeout` passes
+pub async fn wait_for_health(
+    base_url: &str,
+    health_path: &str,
+    timeout: Duration,
+    interval: Duration,
+) -> ServerReadiness {
+    let started = Instant::now();
+    let url = format!("{base_url}{health_path}");
+    let poll = async {
+        loop {
+            if let Ok(resp) = common::http_client().get(&url).timeout(HEALTH_REQUEST_TIMEOUT).send().await {
+                if resp.status().is_success() {
+                    return;
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    };
+    let ready = tokio::time::timeout(timeout, poll).await.is_ok();
+
+    ServerReadiness {
+        ready,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// How often [`wait_for_listening_port`] re-reads the captured output
+const LISTENING_PORT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Port announced by a startup log line, e.g. `Server listening on :8787`
+///
+/// Tolerant of the usual Node formats: `Listening on http://localhost:8787`,
+/// `running at http://127.0.0.1:8787/`, `started on port 8787`. Lines that
+/// mention a port without saying the server is listening on it (e.g.
+/// `Port 8787 is in use`) are ignored.
+pub fn parse_listening_port(line: &str) -> Option<u16> {
+    static RE: OnceLock<regex::Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        regex::Regex::new(r"(?i)\b(?:listening|running|started|serving|ready)\b.*?(?:\bport\b\s*:?\s*|:)(\d{1,5})\b")
+            .expect("listening port regex is valid")
+    });
+    re.captures(line)?[1].parse().ok().filter(|&port| port != 0)
+}
+
+/// Waits for the server output in `logs` to announce its listening port
+///
+/// Uses the most recent announcement, so a server that fell back to another
+/// port reports the one it actually bound. Returns None if nothing is
+/// announced within `timeout`.
+pub async fn wait_for_listening_port(logs: &LogBuffer, timeout: Duration) -> Option<u16> {
+    let poll = async {
+        loop {
+            if let Some(port) = logs.lines().iter().rev().find_map(|line| parse_listening_port(line)) {
+                return port;
+            }
+            tokio::time::sleep(LISTENING_PORT_POLL_INTERVAL).await;
+        }
+    };
+    tokio::time::timeout(timeout, poll).await.ok()
+}
+
+/// Reads the `version` field from the contents of a `package.json`
+///
+/// Returns None if the JSON is invalid or has no string `version`.
+pub fn parse_package_version(contents: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(contents).ok()?;
+    json.get("version")?.as_str().map(str::to_string)
+}
+
+/// Version of the server that [`start`] would run
+///
+/// Read from the server's `package.json`: `apps/server` in development, next
+/// to the bundled script otherwise. Returns None if it is missing or unparsable.
+pub fn server_version() -> Option<String> {
+    let package_json = if is_development_mode() {
+        find_project_root().ok()?.join("apps").join("server").join("package.json")
+    } else {
+        get_server_path().ok()?.parent()?.join("package.json")
+    };
+    let contents = std::fs::read_to_string(package_json).ok()?;
+    parse_package_version(&contents)
+}
+
+/// Starts the server and a supervisor that restarts it after crashes
+///
+/// `on_restart` is called after each automatic restart.
 ///
 /// # Errors
 ///
 /// Returns an error if the server fails to start
-pub fn start(port: u16) -> Result<ServerHandle, String> {
+pub fn start<F>(config: ServerConfig, on_restart: F) -> Result<ServerHandle, String>
+where
+    F: Fn(ServerRestarted) + Send + Sync + 'static,
+{
     // Validate port range
-    common::validate_port(port)?;
+    common::validate_port_allowing_privileged(config.port, config.allow_privileged_port)?;
+    validate_env_keys(&config.extra_env)?;
 
-    // Check if we're running in development mode
-    if is_development_mode() {
-        Ok(start_dev_server(port)?)
-    } else {
-        Ok(start_production_server(port)?)
-    }
+    let logs = LogBuffer::default().with_file(log_file::server_log_sink());
+    let mut child = spawn_server(&config)?;
+    let started_at = Instant::now();
+    logs.capture(&mut child);
+    let process = Arc::new(Mutex::new(None));
+    record_process(&process, &child);
+
+    let intentional_stop = Arc::new(AtomicBool::new(false));
+    let stop_token = CancellationToken::new();
+
+    let supervisor = tokio::spawn(supervise(
+        child,
+        config.clone(),
+        logs.clone(),
+        process.clone(),
+        intentional_stop.clone(),
+        stop_token.clone(),
+        on_restart,
+    ));
+
+    Ok(ServerHandle {
+        port: config.port,
+        logs,
+        process,
+        intentional_stop,
+        stop_token,
+        supervisor: Some(supervisor),
+        started_at,
+    })
 }
 
 /// Stops the server
@@ -89,41 +684,342 @@ pub fn start(port: u16) -> Result<ServerHandle, String> {
 ///
 /// Returns an error if the server process fails to stop
 pub async fn stop(mut handle: ServerHandle) -> Result<(), String> {
-    handle.child.kill()
+    handle.intentional_stop.store(true, Ordering::SeqCst);
+    handle.stop_token.cancel();
+
+    match handle.supervisor.take() {
+        Some(supervisor) => supervisor
+            .await
+            .map_err(|e| format!("Failed to stop server: {e}"))?,
+        None => Ok(()),
+    }
+}
+
+/// Asks the child to exit and kills it if it's still running after `grace_period`
+///
+/// On Unix the request is a `SIGTERM`; on Windows a `CTRL_BREAK` event sent to
+/// the child's process group. Giving Node.js the chance to exit cleanly avoids
+/// interrupting SQLite mid-write.
+///
+/// # Errors
+///
+/// Returns an error if the process can't be killed
+pub async fn terminate_gracefully(child: &mut Child, grace_period: Duration) -> Result<(), String> {
+    if let Some(pid) = child.id() {
+        match request_shutdown(pid) {
+            Ok(()) => {
+                if let Ok(status) = tokio::time::timeout(grace_period, child.wait()).await {
+                    return status
+                        .map(|_| ())
+                        .map_err(|e| format!("Failed to stop server: {e}"));
+                }
+                tracing::warn!(target: "Server", "Still running after {grace_period:?}, killing");
+            }
+            Err(e) => tracing::error!(target: "Server", "Failed to request shutdown: {e}"),
+        }
+    }
+
+    child.kill()
         .await
-        .map_err(|e| format!("Failed to stop server: {e}"))?;
-    Ok(())
+        .map_err(|e| format!("Failed to stop server: {e}"))
+}
+
+/// Sends `SIGTERM` to the process
+#[cfg(unix)]
+fn request_shutdown(pid: u32) -> std::io::Result<()> {
+    let pid = libc::pid_t::try_from(pid)
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "pid out of range"))?;
+    // SAFETY: kill(2) has no memory-safety preconditions
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Sends `CTRL_BREAK` to the process group led by the process
+///
+/// Console control events only reach processes sharing the caller's console,
+/// and the child has its own hidden one (`CREATE_NO_WINDOW`) while release
+/// builds have none at all. So this briefly attaches to the child's console
+/// to send the event, then goes back to the console it had before, if any.
+/// We aren't in the child's process group, so the event doesn't reach us.
+#[cfg(windows)]
+fn request_shutdown(pid: u32) -> std::io::Result<()> {
+    use windows_sys::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, GetConsoleWindow, ATTACH_PARENT_PROCESS,
+        CTRL_BREAK_EVENT,
+    };
+
+    // Console attachment is per process, so concurrent stops must take turns
+    static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = CONSOLE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    // SAFETY: these console functions take no pointers and have no
+    // memory-safety preconditions; they only change which console this
+    // process is attached to, which CONSOLE_LOCK serialises.
+    unsafe {
+        let had_console = !GetConsoleWindow().is_null();
+        FreeConsole();
+        let result = if AttachConsole(pid) == 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid);
+            let result = if sent != 0 { Ok(()) } else { Err(std::io::Error::last_os_error()) };
+            FreeConsole();
+            result
+        };
+        if had_console {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+        result
+    }
+}
+
+/// Hides the child's console window and gives it its own process group
+///
+/// The group is what [`request_shutdown`] sends `CTRL_BREAK` to. Not
+/// `DETACHED_PROCESS`: a child without a console never receives it.
+#[cfg(windows)]
+pub fn hide_console(cmd: &mut Command) {
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+    cmd.creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP);
+}
+
+/// Process names that may be killed to free a port
+///
+/// Only Node.js, so an unrelated program that happens to hold the port is never killed.
+pub const KILLABLE_PROCESS_NAMES: &[&str] = &["node", "node.exe"];
+
+/// A process terminated to free a port
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct KilledProcess {
+    /// Process ID
+    pub pid: u32,
+    /// Executable name (e.g. `node`)
+    pub name: String,
+}
+
+/// Whether a process named `name` may be killed to free a port
+pub fn is_killable_process(name: &str) -> bool {
+    KILLABLE_PROCESS_NAMES
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(name))
+}
+
+/// Parses `lsof -t` output (one PID per line)
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+pub fn parse_lsof_pids(output: &str) -> Vec<u32> {
+    let mut pids: Vec<u32> = output
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// Parses `netstat -ano` output for PIDs listening on `port`
+///
+/// Local addresses are IPv4 (`0.0.0.0:8787`) or bracketed IPv6
+/// (`[::]:8787`, `[fe80::1%12]:8787`); both count.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn parse_netstat_pids(output: &str, port: u16) -> Vec<u32> {
+    let mut pids: Vec<u32> = output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [proto, local, _, state, pid]
+                    if proto.eq_ignore_ascii_case("TCP")
+                        && local.rsplit_once(':').and_then(|(_, p)| p.parse().ok()) == Some(port)
+                        && state.eq_ignore_ascii_case("LISTENING") =>
+                {
+                    pid.parse().ok()
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// PIDs of processes listening on TCP `port`
+///
+/// # Errors
+///
+/// Returns an error if `lsof` (or `netstat` on Windows) can't be run
+#[cfg(not(target_os = "windows"))]
+pub fn listening_pids(port: u16) -> Result<Vec<u32>, String> {
+    let output = std::process::Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN", "-t"])
+        .output()
+        .map_err(|e| format!("Failed to run lsof: {e}"))?;
+    // lsof exits with 1 when nothing matches
+    Ok(parse_lsof_pids(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// PIDs of processes listening on TCP `port`
+///
+/// # Errors
+///
+/// Returns an error if `lsof` (or `netstat` on Windows) can't be run
+#[cfg(target_os = "windows")]
+pub fn listening_pids(port: u16) -> Result<Vec<u32>, String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    // No `-p TCP`: that lists IPv4 sockets only and would miss `[::]` listeners
+    let output = std::process::Command::new("netstat")
+        .args(["-ano"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to run netstat: {e}"))?;
+    Ok(parse_netstat_pids(&String::from_utf8_lossy(&output.stdout), port))
+}
+
+/// Name of process `pid`, if it exists
+pub fn process_name(pid: u32) -> Option<String> {
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    if !system.refresh_process(pid) {
+        return None;
+    }
+    system.process(pid).map(|process| process.name().to_string())
+}
+
+/// Forcefully terminates process `pid`
+///
+/// # Errors
+///
+/// Returns an error if the process is gone or can't be killed
+pub fn kill_process(pid: u32) -> Result<(), String> {
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    system.refresh_process(sys_pid);
+    match system.process(sys_pid) {
+        Some(process) if process.kill() => Ok(()),
+        Some(_) => Err(format!("Failed to kill process {pid}")),
+        None => Err(format!("Process {pid} not found")),
+    }
+}
+
+/// Spawns the server process for the current mode
+fn spawn_server(config: &ServerConfig) -> Result<Child, String> {
+    // Check if we're running in development mode
+    if is_development_mode() {
+        start_dev_server(config.package_manager, config.port, &config.extra_env)
+    } else {
+        start_production_server(config.port, &config.extra_env)
+    }
+}
+
+/// Waits for the server to exit and restarts it after unexpected exits
+///
+/// Returns once the server is stopped via `stop_token`, exits cleanly, or has
+/// crashed more than `config.max_restarts` times.
+async fn supervise<F>(
+    mut child: Child,
+    config: ServerConfig,
+    logs: LogBuffer,
+    process: ProcessSlot,
+    intentional_stop: Arc<AtomicBool>,
+    stop_token: CancellationToken,
+    on_restart: F,
+) -> Result<(), String>
+where
+    F: Fn(ServerRestarted) + Send + Sync + 'static,
+{
+    let mut restarts = 0;
+
+    loop {
+        let status = tokio::select! {
+            status = child.wait() => status,
+            _ = stop_token.cancelled() => {
+                let result = terminate_gracefully(&mut child, config.shutdown_grace_period).await;
+                *process.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                return result;
+            }
+        };
+        *process.lock().unwrap_or_else(|e| e.into_inner()) = None;
+
+        if intentional_stop.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        match status {
+            Ok(status) if status.success() => {
+                tracing::info!(target: "Server", "Exited cleanly");
+                return Ok(());
+            }
+            Ok(status) => tracing::error!(target: "Server", "Exited unexpectedly: {status}"),
+            Err(e) => tracing::error!(target: "Server", "Failed to wait for server: {e}"),
+        }
+
+        if restarts >= config.max_restarts {
+            tracing::error!(target: "Server", "Giving up after {restarts} restart(s)");
+            return Err(format!("Server crashed {} time(s)", restarts + 1));
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(restart_backoff(restarts)) => {}
+            _ = stop_token.cancelled() => return Ok(()),
+        }
+
+        restarts += 1;
+        child = spawn_server(&config)?;
+        logs.capture(&mut child);
+        record_process(&process, &child);
+        tracing::info!(target: "Server", "Restarted (attempt {restarts}/{})", config.max_restarts);
+        on_restart(ServerRestarted {
+            port: config.port,
+            attempt: restarts,
+        });
+    }
 }
 
 /// Checks if we're running in development mode
+///
+/// A custom server path always runs the script directly, as in production.
 pub fn is_development_mode() -> bool {
-    // Check if we're running in development environment
-    std::env::var("TAURI_DEV")
-        .or_else(|_| std::env::var("DEBUG"))
-        .is_ok()
-        || !std::env::current_exe()
-            .map(|p| p.extension().is_some())
-            .unwrap_or(false)
+    development_mode(
+        server_path_override().is_some(),
+        std::env::var("TAURI_DEV").or_else(|_| std::env::var("DEBUG")).is_ok(),
+        std::env::current_exe().map(|p| p.extension().is_some()).unwrap_or(false),
+    )
+}
+
+/// Decides the mode from the server override, `TAURI_DEV`/`DEBUG` and
+/// whether the executable has an extension (an installed `.exe`/`.app`)
+pub fn development_mode(has_server_override: bool, dev_env_set: bool, exe_has_extension: bool) -> bool {
+    !has_server_override && (dev_env_set || !exe_has_extension)
 }
 
 /// Starts the server in development mode
 ///
+/// `PORT` is set after `extra_env`, as in production, so each managed dev
+/// server binds its own port.
+///
 /// # Errors
 ///
-/// Returns an error if the project root cannot be found or npm fails to start
-fn start_dev_server(port: u16) -> Result<ServerHandle, String> {
-    // Find the project root (where package.json exists)
-    let project_root = find_project_root()
-        .map_err(|e| format!("Failed to find project root: {e}"))?;
-
-    let server_dir = project_root.join("apps").join("server");
+/// Returns an error if the server package cannot be found or the package
+/// manager fails to start
+fn start_dev_server(
+    package_manager: PackageManager,
+    port: u16,
+    extra_env: &HashMap<String, String>,
+) -> Result<Child, String> {
+    let server_dir = find_dev_server_dir()?;
 
-    // Find npm command using common module
-    let npm_cmd = common::find_npm_command()?;
+    // Find the package manager command using common module
+    let npm_cmd = package_manager.find_command()?;
 
-    println!("[Server] Using npm: {npm_cmd}");
+    tracing::info!(target: "Server", "Using {}: {npm_cmd}", package_manager.executable_name());
 
-    // On Windows, always use cmd.exe /c to run npm with hidden console
+    // On Windows, always use cmd.exe /c to run the package manager with hidden console
     #[cfg(target_os = "windows")]
     let spawn_result = {
         let mut cmd = Command::new("cmd.exe");
@@ -132,15 +1028,13 @@ fn start_dev_server(port: u16) -> Result<ServerHandle, String> {
             .current_dir(&server_dir)
             .arg("run")
             .arg("dev")
+            .envs(extra_env)
+            .env("PORT", port.to_string())
             .env("DB_PATH", server_dir.join("data").join("deck-ide.db").to_string_lossy().to_string())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
             .kill_on_drop(true);
-        
-        // Hide console window
-        #[allow(unused_imports)]
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        
+        hide_console(&mut cmd);
         cmd.spawn()
     };
 
@@ -149,14 +1043,23 @@ fn start_dev_server(port: u16) -> Result<ServerHandle, String> {
         .current_dir(&server_dir)
         .arg("run")
         .arg("dev")
+        .envs(extra_env)
+        .env("PORT", port.to_string())
         .env("DB_PATH", server_dir.join("data").join("deck-ide.db").to_string_lossy().to_string())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
         .kill_on_drop(true)
         .spawn();
 
     let child = spawn_result
-        .map_err(|e| format!("Failed to start dev server: {e}. Ensure npm is in PATH"))?;
+        .map_err(|e| {
+            format!(
+                "Failed to start dev server: {e}. Ensure {} is in PATH",
+                package_manager.executable_name()
+            )
+        })?;
 
-    Ok(ServerHandle { child, port })
+    Ok(child)
 }
 
 /// Starts the server in production mode
@@ -164,15 +1067,8 @@ fn start_dev_server(port: u16) -> Result<ServerHandle, String> {
 /// # Errors
 ///
 /// Returns an error if the server executable is not found or fails to start
-fn start_production_server(port: u16) -> Result<ServerHandle, String> {
-    let server_path = get_server_path()?;
-
-    if !server_path.exists() {
-        return Err(format!(
-            "Server executable not found at: {}. Ensure resources are bundled correctly.",
-            server_path.display()
-        ));
-    }
+fn start_production_server(port: u16, extra_env: &HashMap<String, String>) -> Result<Child, String> {
+    let server_path = find_server_script()?;
 
     // Find Node.js executable using common module
     let node_exe = common::find_node_executable()?;
@@ -188,26 +1084,38 @@ fn start_production_server(port: u16) -> Result<ServerHandle, String> {
         .unwrap_or_else(|| PathBuf::from("resources/data"));
     let db_path = data_dir.join("deck-ide.db");
 
-    let mut cmd = Command::new(&node_exe);
-    cmd.arg(&server_script)
-        .env("PORT", port.to_string())
-        .env("DB_PATH", db_path.to_string_lossy().to_string())
-        .kill_on_drop(true);
+    let mut cmd = production_command(&node_exe, &server_script, port, &db_path, extra_env);
     
     // Hide console window on Windows in production
     #[cfg(target_os = "windows")]
-    {
-        #[allow(unused_imports)]
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        const DETACHED_PROCESS: u32 = 0x00000008;
-        cmd.creation_flags(CREATE_NO_WINDOW | DETACHED_PROCESS);
-    }
+    hide_console(&mut cmd);
     
     let child = cmd.spawn()
         .map_err(|e| format!("Failed to start server: {e} (node: '{node_exe}', script: '{server_script}')"))?;
 
-    Ok(ServerHandle { child, port })
+    Ok(child)
+}
+
+/// Builds the production server command
+///
+/// `extra_env` is applied first so `PORT` and `DB_PATH` always win.
+pub fn production_command(
+    node_exe: &str,
+    server_script: &str,
+    port: u16,
+    db_path: &Path,
+    extra_env: &HashMap<String, String>,
+) -> Command {
+    let mut cmd = Command::new(node_exe);
+    cmd.arg(server_script)
+        .envs(extra_env)
+        .env("PORT", port.to_string())
+        .env("DB_PATH", db_path.to_string_lossy().to_string())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    cmd
 }
 
 /// Finds the project root by searching for package.json
@@ -4,7 +4,10 @@
 
 use crate::server::*;
 use crate::common;
+use crate::test_support::HttpResponder;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 #[cfg(test)]
 mod tests {
@@ -58,35 +61,14 @@ mod tests {
 
     #[test]
     fn test_is_development_mode_detection() {
-        // Test that is_development_mode() checks environment variables
-        // We can't mock env vars easily in tests, but we can verify the logic
-
-        // Save original vars
-        let original_tauri_dev = std::env::var("TAURI_DEV");
-        let original_debug = std::env::var("DEBUG");
-
-        // Test TAURI_DEV
-        std::env::set_var("TAURI_DEV", "1");
-        let _result = is_development_mode();
-        std::env::remove_var("TAURI_DEV");
-
-        // Reset
-        if let Ok(val) = original_tauri_dev {
-            std::env::set_var("TAURI_DEV", val);
-        }
-
-        // Test DEBUG
-        std::env::set_var("DEBUG", "1");
-        let _result2 = is_development_mode();
-        std::env::remove_var("DEBUG");
-
-        // Reset
-        if let Ok(val) = original_debug {
-            std::env::set_var("DEBUG", val);
-        }
-
-        // At least one should return true (if vars were set correctly)
-        // Or both false if neither was set in test environment
+        // TAURI_DEV or DEBUG set
+        assert!(development_mode(false, true, true));
+        // An executable without extension is a dev build
+        assert!(development_mode(false, false, false));
+        // An installed app
+        assert!(!development_mode(false, false, true));
+        // A custom server path always runs like production
+        assert!(!development_mode(true, true, false));
     }
 
     #[test]
@@ -232,4 +214,395 @@ mod tests {
         assert!(exe_error.contains("exe"));
         assert!(exe_error.len() > 10);
     }
+
+    #[test]
+    fn test_log_buffer_keeps_last_lines() {
+        let buffer = LogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(format!("line {i}"));
+        }
+
+        assert_eq!(buffer.lines(), vec!["line 2", "line 3", "line 4"]);
+        assert_eq!(buffer.tail(2), vec!["line 3", "line 4"]);
+        assert_eq!(buffer.tail(10).len(), 3);
+        assert!(buffer.tail(0).is_empty());
+        assert_eq!(LogBuffer::default().lines().len(), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_log_buffer_captures_child_output() {
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg("echo from stdout; echo from stderr >&2")
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .expect("failed to spawn fake server");
+
+        let buffer = LogBuffer::default();
+        buffer.capture(&mut child);
+        child.wait().await.unwrap();
+
+        // Reader tasks finish shortly after the pipes close
+        for _ in 0..50 {
+            if buffer.lines().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        let lines = buffer.lines();
+        assert!(lines.contains(&"from stdout".to_string()));
+        assert!(lines.contains(&"from stderr".to_string()));
+    }
+
+    #[test]
+    fn test_restart_backoff_schedule() {
+        assert_eq!(restart_backoff(0), Duration::from_millis(500));
+        assert_eq!(restart_backoff(1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(2), Duration::from_secs(2));
+        assert_eq!(restart_backoff(3), Duration::from_secs(4));
+        // Capped at the maximum delay, including for huge attempt counts
+        assert_eq!(restart_backoff(6), RESTART_MAX_DELAY);
+        assert_eq!(restart_backoff(u32::MAX), RESTART_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_server_config_defaults() {
+        let config = ServerConfig::new(8787);
+        assert_eq!(config.port, 8787);
+        assert_eq!(config.max_restarts, DEFAULT_MAX_RESTARTS);
+    }
+
+    #[test]
+    fn test_uptime_secs_from_start_instant() {
+        let started_at = Instant::now();
+
+        assert_eq!(uptime_secs(started_at, started_at), 0);
+        assert_eq!(uptime_secs(started_at, started_at + Duration::from_millis(1500)), 1);
+        assert_eq!(uptime_secs(started_at, started_at + Duration::from_secs(3600)), 3600);
+        // A `now` before the start saturates instead of panicking
+        assert_eq!(uptime_secs(started_at + Duration::from_secs(5), started_at), 0);
+    }
+
+    #[test]
+    fn test_validate_env_keys() {
+        let env = |key: &str| HashMap::from([(key.to_string(), "value".to_string())]);
+
+        assert!(validate_env_keys(&HashMap::new()).is_ok());
+        assert!(validate_env_keys(&env("NODE_OPTIONS")).is_ok());
+        assert!(validate_env_keys(&env("")).is_err());
+        assert!(validate_env_keys(&env("HTTP PROXY")).is_err());
+        assert!(validate_env_keys(&env("S_IDE_CONFIG\t")).is_err());
+        assert!(validate_env_keys(&env("A=B")).is_err());
+    }
+
+    #[test]
+    fn test_production_command_applies_extra_env() {
+        let extra_env = HashMap::from([
+            ("NODE_OPTIONS".to_string(), "--max-old-space-size=4096".to_string()),
+            ("PORT".to_string(), "1".to_string()),
+            ("DB_PATH".to_string(), "/tmp/other.db".to_string()),
+        ]);
+        let db_path = PathBuf::from("/data/deck-ide.db");
+        let cmd = production_command("node", "index.js", 8787, &db_path, &extra_env);
+
+        let envs: HashMap<_, _> = cmd
+            .as_std()
+            .get_envs()
+            .map(|(k, v)| (k.to_owned(), v.map(|v| v.to_owned())))
+            .collect();
+        let get = |key: &str| envs.get(std::ffi::OsStr::new(key)).cloned().flatten();
+
+        assert_eq!(get("NODE_OPTIONS").as_deref(), Some(std::ffi::OsStr::new("--max-old-space-size=4096")));
+        // Built-in variables take precedence over user-supplied ones
+        assert_eq!(get("PORT").as_deref(), Some(std::ffi::OsStr::new("8787")));
+        assert_eq!(get("DB_PATH"), Some(db_path.into_os_string()));
+    }
+
+    #[cfg(unix)]
+    fn spawn_shell(script: &str) -> tokio::process::Child {
+        tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .kill_on_drop(true)
+            .spawn()
+            .expect("failed to spawn mock server")
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_terminate_gracefully_exits_on_sigterm() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let mut child = spawn_shell("exec sleep 30");
+        terminate_gracefully(&mut child, SHUTDOWN_GRACE_PERIOD).await.unwrap();
+
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_terminate_gracefully_kills_after_grace_period() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // Ignores SIGTERM, so only the forced kill can stop it
+        let mut child = spawn_shell("trap '' TERM; exec sleep 30");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let started = Instant::now();
+        terminate_gracefully(&mut child, Duration::from_millis(200)).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(started.elapsed() < SHUTDOWN_GRACE_PERIOD);
+
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn test_terminate_gracefully_sends_ctrl_break() {
+        // Spawned like the server, so the CTRL_BREAK has to reach a hidden console
+        let mut cmd = tokio::process::Command::new("cmd.exe");
+        cmd.args(["/c", "ping -n 30 127.0.0.1 > NUL"]).kill_on_drop(true);
+        hide_console(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // The forced kill would only happen after the full grace period
+        let started = Instant::now();
+        terminate_gracefully(&mut child, SHUTDOWN_GRACE_PERIOD).await.unwrap();
+        assert!(started.elapsed() < SHUTDOWN_GRACE_PERIOD);
+    }
+
+    #[test]
+    fn test_validate_server_script() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let script = dir.join("index.js");
+        let not_js = dir.join("index.txt");
+        std::fs::write(&script, "").unwrap();
+        std::fs::write(&not_js, "").unwrap();
+
+        assert!(validate_server_script(&script).is_ok());
+        assert!(validate_server_script(&not_js).is_err());
+        assert!(validate_server_script(&dir.join("missing.js")).is_err());
+        assert!(validate_server_script(dir).is_err());
+    }
+
+    #[test]
+    fn test_server_path_env_override_wins_over_settings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let env_script = dir.join("env.js");
+        let settings_script = dir.join("settings.js");
+        std::fs::write(&env_script, "").unwrap();
+        std::fs::write(&settings_script, "").unwrap();
+
+        assert_eq!(
+            resolve_server_path_override(Some(env_script.clone()), Some(settings_script.clone())),
+            Some(env_script)
+        );
+        // An invalid environment path falls through to the setting
+        assert_eq!(
+            resolve_server_path_override(Some(dir.join("missing.js")), Some(settings_script.clone())),
+            Some(settings_script)
+        );
+        assert_eq!(resolve_server_path_override(None, None), None);
+    }
+
+    #[test]
+    fn test_package_manager_executable_names() {
+        assert_eq!(PackageManager::Npm.executable_name(), "npm");
+        assert_eq!(PackageManager::Pnpm.executable_name(), "pnpm");
+        assert_eq!(PackageManager::Yarn.executable_name(), "yarn");
+        assert_eq!(PackageManager::Bun.executable_name(), "bun");
+    }
+
+    #[test]
+    fn test_package_manager_defaults_to_npm() {
+        assert_eq!(PackageManager::default(), PackageManager::Npm);
+        assert_eq!(ServerConfig::new(8787).package_manager, PackageManager::Npm);
+
+        let parsed: PackageManager = serde_json::from_str("\"pnpm\"").unwrap();
+        assert_eq!(parsed, PackageManager::Pnpm);
+    }
+
+    #[test]
+    fn test_parse_package_version() {
+        let contents = r#"{
+            "name": "server",
+            "private": true,
+            "version": "0.1.0",
+            "scripts": { "dev": "tsx watch src/index.ts" }
+        }"#;
+        assert_eq!(parse_package_version(contents), Some("0.1.0".to_string()));
+    }
+
+    #[test]
+    fn test_parse_package_version_missing_or_invalid() {
+        assert_eq!(parse_package_version(r#"{"name": "server"}"#), None);
+        assert_eq!(parse_package_version(r#"{"version": 1}"#), None);
+        assert_eq!(parse_package_version("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_health_succeeds_once_server_comes_up() {
+        let port = HttpResponder::new("200 OK", "").after(Duration::from_millis(300)).spawn();
+        let base_url = format!("http://127.0.0.1:{port}");
+
+        let readiness =
+            wait_for_health(&base_url, "/health", Duration::from_secs(5), Duration::from_millis(50)).await;
+
+        assert!(readiness.ready);
+        assert!(readiness.elapsed_ms >= 300);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_health_times_out() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let readiness = wait_for_health(
+            &format!("http://127.0.0.1:{port}"),
+            DEFAULT_HEALTH_PATH,
+            Duration::from_millis(200),
+            Duration::from_millis(50),
+        )
+        .await;
+
+        assert!(!readiness.ready);
+        assert!(readiness.elapsed_ms >= 200);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_health_targets_configured_path() {
+        let port = HttpResponder::new("200 OK", "").only_on("/api/health").spawn();
+        let base_url = format!("http://127.0.0.1:{port}");
+
+        let readiness =
+            wait_for_health(&base_url, "/api/health", Duration::from_secs(5), Duration::from_millis(50)).await;
+        assert!(readiness.ready);
+
+        let readiness =
+            wait_for_health(&base_url, DEFAULT_HEALTH_PATH, Duration::from_millis(300), Duration::from_millis(50)).await;
+        assert!(!readiness.ready);
+    }
+
+    #[test]
+    fn test_validate_health_path() {
+        assert!(validate_health_path("/health").is_ok());
+        assert!(validate_health_path("/api/health").is_ok());
+        assert!(validate_health_path("/healthz").is_ok());
+
+        assert!(validate_health_path("health").is_err());
+        assert!(validate_health_path("").is_err());
+        assert!(validate_health_path("//evil.example/health").is_err());
+        assert!(validate_health_path("/health check").is_err());
+        assert!(validate_health_path("/health?verbose=1").is_err());
+    }
+
+
+    #[test]
+    fn test_runtime_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let state = RuntimeState { last_port: Some(9123) };
+
+        save_runtime_state_to(&path, &state).unwrap();
+
+        assert_eq!(load_runtime_state_from(&path), state);
+    }
+
+    #[test]
+    fn test_runtime_state_missing_or_corrupt_uses_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        assert_eq!(load_runtime_state_from(&path).last_port, None);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load_runtime_state_from(&path).last_port, None);
+    }
+
+    #[test]
+    fn test_only_node_processes_are_killable() {
+        assert!(is_killable_process("node"));
+        assert!(is_killable_process("node.exe"));
+        assert!(is_killable_process("Node.exe"));
+        assert!(!is_killable_process("postgres"));
+        assert!(!is_killable_process("nodemon"));
+        assert!(!is_killable_process(""));
+    }
+
+    #[test]
+    fn test_parse_lsof_pids() {
+        assert_eq!(parse_lsof_pids("4321\n1234\n4321\n"), vec![1234, 4321]);
+        assert!(parse_lsof_pids("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_netstat_pids_matches_listening_port() {
+        let output = "
+Active Connections
+
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    0.0.0.0:8787           0.0.0.0:0              LISTENING       4242
+  TCP    [::]:8787              [::]:0                 LISTENING       4242
+  TCP    0.0.0.0:18787          0.0.0.0:0              LISTENING       5555
+  TCP    127.0.0.1:8787         127.0.0.1:50000        ESTABLISHED     4242
+  TCP    127.0.0.1:50000        127.0.0.1:8787         ESTABLISHED     7777
+";
+        assert_eq!(parse_netstat_pids(output, 8787), vec![4242]);
+    }
+
+    #[test]
+    fn test_parse_netstat_pids_finds_ipv6_only_listeners() {
+        let output = "
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    [::]:8787              [::]:0                 LISTENING       4242
+  TCP    [::1]:8788             [::]:0                 LISTENING       5555
+  TCP    [fe80::1%12]:8789      [::]:0                 LISTENING       6666
+  TCP    [::1]:8787             [::1]:50000            ESTABLISHED     7777
+  UDP    [::]:8787              *:*                                    8888
+";
+        assert_eq!(parse_netstat_pids(output, 8787), vec![4242]);
+        assert_eq!(parse_netstat_pids(output, 8788), vec![5555]);
+        assert_eq!(parse_netstat_pids(output, 8789), vec![6666]);
+        assert!(parse_netstat_pids(output, 878).is_empty());
+    }
+
+    #[test]
+    fn test_parse_listening_port_formats() {
+        assert_eq!(parse_listening_port("Server listening on :8787"), Some(8787));
+        assert_eq!(parse_listening_port("[server] Listening on http://localhost:8788"), Some(8788));
+        assert_eq!(parse_listening_port("Server running at http://127.0.0.1:3000/"), Some(3000));
+        assert_eq!(parse_listening_port("S-IDE server started on port 8789"), Some(8789));
+        assert_eq!(parse_listening_port("listening on [::]:8790"), Some(8790));
+        assert_eq!(parse_listening_port("12:30:45 INFO ready, port: 8791"), Some(8791));
+    }
+
+    #[test]
+    fn test_parse_listening_port_ignores_other_lines() {
+        assert_eq!(parse_listening_port("Port 8787 is in use, trying another one..."), None);
+        assert_eq!(parse_listening_port("Connecting to database at db:5432"), None);
+        assert_eq!(parse_listening_port("Listening on :0"), None);
+        assert_eq!(parse_listening_port("Listening on :99999"), None);
+        assert_eq!(parse_listening_port(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_listening_port_uses_latest_announcement() {
+        let logs = LogBuffer::new(10);
+        assert_eq!(wait_for_listening_port(&logs, Duration::from_millis(150)).await, None);
+
+        logs.push("Listening on :8787".to_string());
+        logs.push("Port 8787 is in use, trying another one...".to_string());
+        logs.push("Listening on :8788".to_string());
+        assert_eq!(wait_for_listening_port(&logs, Duration::from_millis(150)).await, Some(8788));
+    }
 }
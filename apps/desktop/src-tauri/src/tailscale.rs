@@ -3,14 +3,26 @@
 //! This module intentionally treats Tailscale as an external dependency:
 //! we shell out to the `tailscale` CLI when available.
 
+use crate::common;
 use serde::Serialize;
 use serde_json::Value;
 use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// How long to wait for `tailscale up` to print a login URL.
+pub const LOGIN_URL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a pending `tailscale up` may keep waiting for the browser login.
+const LOGIN_COMPLETION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct TailscaleStatusSummary {
     /// Whether the `tailscale` CLI appears to be installed and executable.
     pub installed: bool,
+    /// CLI version from `tailscale version` (e.g. "1.62.0"), if available.
+    pub version: Option<String>,
     /// Backend state (e.g. "Running", "NeedsLogin", ...), if available.
     pub backend_state: Option<String>,
     /// Login URL to authorize this device (when logged out), if available.
@@ -21,6 +33,65 @@ pub struct TailscaleStatusSummary {
     pub self_dns_name: Option<String>,
     /// Device Tailscale IPs (IPv4/IPv6).
     pub tailscale_ips: Vec<String>,
+    /// Whether MagicDNS is enabled for the tailnet.
+    pub magic_dns_enabled: bool,
+    /// Whether the tailnet can issue HTTPS certificates (non-empty `CertDomains`).
+    pub https_enabled: bool,
+    /// Other devices on the tailnet, sorted by hostname.
+    pub peers: Vec<TailscalePeer>,
+}
+
+/// Another device on the tailnet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TailscalePeer {
+    /// Peer hostname.
+    pub hostname: String,
+    /// Peer MagicDNS name, if available.
+    pub dns_name: Option<String>,
+    /// Peer Tailscale IPs (IPv4/IPv6).
+    pub tailscale_ips: Vec<String>,
+    /// Whether the peer is currently connected to the tailnet.
+    pub online: bool,
+}
+
+/// Collect the string entries of a JSON array field.
+fn string_array(obj: Option<&Value>, key: &str) -> Vec<String> {
+    obj.and_then(|o| o.get(key))
+        .and_then(|x| x.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|ip| ip.as_str().map(|s| s.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the `Peer` map of `tailscale status --json` (keyed by node key).
+fn parse_peers(v: &Value) -> Vec<TailscalePeer> {
+    let Some(peer_map) = v.get("Peer").and_then(|x| x.as_object()) else {
+        return vec![];
+    };
+
+    let mut peers: Vec<TailscalePeer> = peer_map
+        .values()
+        .map(|peer| TailscalePeer {
+            hostname: peer
+                .get("HostName")
+                .and_then(|x| x.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            dns_name: peer
+                .get("DNSName")
+                .and_then(|x| x.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string()),
+            tailscale_ips: string_array(Some(peer), "TailscaleIPs"),
+            online: peer.get("Online").and_then(|x| x.as_bool()).unwrap_or(false),
+        })
+        .collect();
+
+    peers.sort_by(|a, b| a.hostname.cmp(&b.hostname));
+    peers
 }
 
 /// Parse `tailscale status --json` output into a stable summary shape.
@@ -52,15 +123,18 @@ pub fn parse_status_json(json: &str) -> Result<TailscaleStatusSummary, String> {
         .and_then(|x| x.as_str())
         .map(|s| s.to_string());
 
-    let tailscale_ips = self_obj
-        .and_then(|s| s.get("TailscaleIPs"))
-        .and_then(|x| x.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|ip| ip.as_str().map(|s| s.to_string()))
-                .collect::<Vec<_>>()
-        })
-        .unwrap_or_default();
+    let tailscale_ips = string_array(self_obj, "TailscaleIPs");
+
+    // Older clients have no `CurrentTailnet`; a MagicDNS suffix implies it is on.
+    let magic_dns_enabled = match v.get("CurrentTailnet").and_then(|t| t.get("MagicDNSEnabled")) {
+        Some(enabled) => enabled.as_bool().unwrap_or(false),
+        None => v
+            .get("MagicDNSSuffix")
+            .and_then(|x| x.as_str())
+            .is_some_and(|suffix| !suffix.is_empty()),
+    };
+
+    let https_enabled = !string_array(Some(&v), "CertDomains").is_empty();
 
     Ok(TailscaleStatusSummary {
         installed: true,
@@ -69,11 +143,228 @@ pub fn parse_status_json(json: &str) -> Result<TailscaleStatusSummary, String> {
         self_hostname,
         self_dns_name,
         tailscale_ips,
+        magic_dns_enabled,
+        https_enabled,
+        peers: parse_peers(&v),
+        version: None,
     })
 }
 
+/// First version whose `tailscale serve` supports `--bg`/`--https`/`--set-path`.
+pub const MIN_SERVE_SET_PATH_VERSION: (u32, u32, u32) = (1, 52, 0);
+
+/// Extract the version from `tailscale version` output.
+///
+/// The first line is the version (e.g. "1.62.0", or "1.63.0-dev20240315" on
+/// unstable builds); following lines list commit hashes and the Go version.
+pub fn parse_version_output(output: &str) -> Option<String> {
+    let first = output.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let version = first.split_whitespace().next()?;
+    parse_semver(version).map(|_| version.to_string())
+}
+
+/// Parse the leading `major.minor.patch` of a version string.
+pub fn parse_semver(version: &str) -> Option<(u32, u32, u32)> {
+    let core = version.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    Some((major, minor, patch))
+}
+
+/// Whether `version` is at least `minimum`; unknown versions are assumed to be recent.
+pub fn version_at_least(version: Option<&str>, minimum: (u32, u32, u32)) -> bool {
+    match version.and_then(parse_semver) {
+        Some(v) => v >= minimum,
+        None => true,
+    }
+}
+
+/// Cached result of [`get_version`], cleared with the command path.
+static TAILSCALE_VERSION: common::CommandCache<Option<String>> = common::CommandCache::new();
+
+/// Get the installed Tailscale CLI version (`tailscale version`).
+///
+/// Status polls ask on every call, so the result is cached; see
+/// [`refresh_tailscale_command`].
+pub async fn get_version() -> Option<String> {
+    if let Some(version) = TAILSCALE_VERSION.get() {
+        return version;
+    }
+    let version = query_version().await;
+    TAILSCALE_VERSION.get_or_resolve(|| version)
+}
+
+async fn query_version() -> Option<String> {
+    let cmd = find_tailscale_command()?;
+    let output = tailscale_command(&cmd).arg("version").output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_version_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Result of scanning one line of `tailscale up --json` output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpOutput {
+    /// The device must be authorized at this URL.
+    AuthUrl(String),
+    /// The device is already logged in and connected.
+    Running,
+}
+
+/// Parse one line printed by `tailscale up --json` (or plain `tailscale up`).
+///
+/// JSON lines carry `AuthURL`/`BackendState`; older CLIs print the URL as
+/// plain text after "To authenticate, visit:".
+pub fn parse_up_output_line(line: &str) -> Option<UpOutput> {
+    if let Ok(v) = serde_json::from_str::<Value>(line) {
+        if let Some(url) = v.get("AuthURL").and_then(|x| x.as_str()).filter(|s| !s.is_empty()) {
+            return Some(UpOutput::AuthUrl(url.to_string()));
+        }
+        if v.get("BackendState").and_then(|x| x.as_str()) == Some("Running") {
+            return Some(UpOutput::Running);
+        }
+        return None;
+    }
+
+    line.split_whitespace()
+        .find(|token| token.starts_with("https://login.tailscale.com/"))
+        .map(|url| UpOutput::AuthUrl(url.to_string()))
+}
+
+/// Build a `tailscale` command without a console window on Windows.
+fn tailscale_command(cmd: &str) -> tokio::process::Command {
+    #[allow(unused_mut)]
+    let mut command = tokio::process::Command::new(cmd);
+
+    #[cfg(target_os = "windows")]
+    {
+        #[allow(unused_imports)]
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+}
+
+/// Start a Tailscale login and return the status including the login URL.
+///
+/// Runs `tailscale up --json` and returns as soon as it prints an auth URL; the
+/// CLI keeps running in the background until the login completes in the browser.
+/// When the device is already logged in, the current status is returned.
+pub async fn login() -> Result<TailscaleStatusSummary, String> {
+    let cmd = find_tailscale_command().ok_or_else(|| "Tailscale is not installed".to_string())?;
+
+    let status = get_status_summary().await;
+    if status.backend_state.as_deref() == Some("Running") {
+        return Ok(status);
+    }
+
+    let mut child = tailscale_command(&cmd)
+        .args(["up", "--json"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run tailscale up: {e}"))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to capture tailscale output")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture tailscale output")?;
+
+    // The URL may be printed on either stream depending on CLI version
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    for stream in [
+        Box::new(stdout) as Box<dyn tokio::io::AsyncRead + Unpin + Send>,
+        Box::new(stderr),
+    ] {
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stream).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Some(found) = parse_up_output_line(&line) {
+                    let _ = tx.send(found);
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    let found = tokio::time::timeout(LOGIN_URL_TIMEOUT, rx.recv()).await;
+
+    match found {
+        Ok(Some(UpOutput::AuthUrl(url))) => {
+            // Let `tailscale up` finish once the user logs in, without leaving a zombie
+            tokio::spawn(async move {
+                if tokio::time::timeout(LOGIN_COMPLETION_TIMEOUT, child.wait()).await.is_err() {
+                    let _ = child.kill().await;
+                }
+            });
+            let mut status = get_status_summary().await;
+            status.auth_url = Some(url);
+            Ok(status)
+        }
+        Ok(Some(UpOutput::Running)) => {
+            let _ = child.wait().await;
+            Ok(get_status_summary().await)
+        }
+        Ok(None) => {
+            let exit = child.wait().await.map_err(|e| format!("tailscale up failed: {e}"))?;
+            if exit.success() {
+                Ok(get_status_summary().await)
+            } else {
+                Err(format!("tailscale up failed ({exit})"))
+            }
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            Err(format!(
+                "tailscale up did not print a login URL within {} seconds",
+                LOGIN_URL_TIMEOUT.as_secs()
+            ))
+        }
+    }
+}
+
+/// Log this device out of Tailscale (`tailscale logout`).
+pub async fn logout() -> Result<TailscaleStatusSummary, String> {
+    let cmd = find_tailscale_command().ok_or_else(|| "Tailscale is not installed".to_string())?;
+
+    let output = tailscale_command(&cmd)
+        .arg("logout")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run tailscale logout: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("tailscale logout failed: {}", stderr.trim()));
+    }
+
+    Ok(get_status_summary().await)
+}
+
+/// Cached result of [`find_tailscale_command`].
+static TAILSCALE_COMMAND: common::CommandCache<Option<String>> = common::CommandCache::new();
+
 /// Find a usable `tailscale` CLI command.
+///
+/// The result (including "not found") is cached; see [`refresh_tailscale_command`].
 pub fn find_tailscale_command() -> Option<String> {
+    TAILSCALE_COMMAND.get_or_resolve(resolve_tailscale_command)
+}
+
+/// Forget the cached `tailscale` path and version and search again.
+pub fn refresh_tailscale_command() -> Option<String> {
+    TAILSCALE_COMMAND.clear();
+    TAILSCALE_VERSION.clear();
+    find_tailscale_command()
+}
+
+fn resolve_tailscale_command() -> Option<String> {
     #[cfg(target_os = "windows")]
     {
         let common_paths = [
@@ -130,6 +421,10 @@ pub async fn get_status_summary() -> TailscaleStatusSummary {
                 self_hostname: None,
                 self_dns_name: None,
                 tailscale_ips: vec![],
+                magic_dns_enabled: false,
+                https_enabled: false,
+                peers: vec![],
+                version: None,
             }
         }
     };
@@ -156,23 +451,33 @@ pub async fn get_status_summary() -> TailscaleStatusSummary {
                 self_hostname: None,
                 self_dns_name: None,
                 tailscale_ips: vec![],
+                magic_dns_enabled: false,
+                https_enabled: false,
+                peers: vec![],
+                version: None,
             }
         }
     };
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let version = get_version().await;
     match parse_status_json(&stdout) {
         Ok(mut s) => {
             s.installed = true;
+            s.version = version;
             s
         }
         Err(_) => TailscaleStatusSummary {
             installed: true,
+            version,
             backend_state: None,
             auth_url: None,
             self_hostname: None,
             self_dns_name: None,
             tailscale_ips: vec![],
+            magic_dns_enabled: false,
+            https_enabled: false,
+            peers: vec![],
         },
     }
 }
@@ -1,6 +1,10 @@
 //! Unit tests for Tailscale integration (status parsing and auth URL handling).
 
-use crate::tailscale::{parse_status_json, TailscaleStatusSummary};
+use crate::common::CommandCache;
+use crate::tailscale::{
+    parse_semver, parse_status_json, parse_up_output_line, parse_version_output, version_at_least,
+    TailscalePeer, TailscaleStatusSummary, UpOutput, MIN_SERVE_SET_PATH_VERSION,
+};
 
 #[test]
 fn parse_status_json_logged_in_extracts_ips_and_dns() {
@@ -21,11 +25,15 @@ fn parse_status_json_logged_in_extracts_ips_and_dns() {
         status,
         TailscaleStatusSummary {
             installed: true,
+            version: None,
             backend_state: Some("Running".to_string()),
             auth_url: None,
             self_hostname: Some("home-pc".to_string()),
             self_dns_name: Some("home-pc.tailnet-123.ts.net".to_string()),
             tailscale_ips: vec!["100.64.12.34".to_string(), "fd7a:115c:a1e0:ab12::1234".to_string()],
+            magic_dns_enabled: false,
+            https_enabled: false,
+            peers: vec![],
         }
     );
 }
@@ -52,3 +60,151 @@ fn parse_status_json_needs_login_extracts_auth_url() {
     );
 }
 
+
+#[test]
+fn parse_up_output_json_extracts_auth_url() {
+    let line = r#"{"AuthURL":"https://login.tailscale.com/a/1f2e3d4c5b6a","QR":"","BackendState":"NeedsLogin"}"#;
+    assert_eq!(
+        parse_up_output_line(line),
+        Some(UpOutput::AuthUrl("https://login.tailscale.com/a/1f2e3d4c5b6a".to_string()))
+    );
+
+    // Progress lines before the URL is known
+    assert_eq!(parse_up_output_line(r#"{"AuthURL":"","BackendState":"NeedsLogin"}"#), None);
+    assert_eq!(parse_up_output_line(r#"{"BackendState":"Running"}"#), Some(UpOutput::Running));
+}
+
+#[test]
+fn parse_up_output_plain_text_extracts_auth_url() {
+    assert_eq!(parse_up_output_line("To authenticate, visit:"), None);
+    assert_eq!(parse_up_output_line(""), None);
+    assert_eq!(
+        parse_up_output_line("\thttps://login.tailscale.com/a/abcdef123456"),
+        Some(UpOutput::AuthUrl("https://login.tailscale.com/a/abcdef123456".to_string()))
+    );
+}
+
+#[test]
+fn parse_status_json_extracts_peers_with_online_flags() {
+    let json = r#"
+    {
+      "BackendState": "Running",
+      "Self": { "HostName": "home-pc", "TailscaleIPs": ["100.64.12.34"] },
+      "Peer": {
+        "nodekey:bbbb": {
+          "HostName": "work-laptop",
+          "DNSName": "work-laptop.tailnet-123.ts.net.",
+          "TailscaleIPs": ["100.64.0.2"],
+          "Online": false
+        },
+        "nodekey:aaaa": {
+          "HostName": "nas",
+          "DNSName": "nas.tailnet-123.ts.net.",
+          "TailscaleIPs": ["100.64.0.3", "fd7a:115c:a1e0::3"],
+          "Online": true
+        }
+      }
+    }
+    "#;
+
+    let status = parse_status_json(json).expect("should parse");
+    assert_eq!(
+        status.peers,
+        vec![
+            TailscalePeer {
+                hostname: "nas".to_string(),
+                dns_name: Some("nas.tailnet-123.ts.net.".to_string()),
+                tailscale_ips: vec!["100.64.0.3".to_string(), "fd7a:115c:a1e0::3".to_string()],
+                online: true,
+            },
+            TailscalePeer {
+                hostname: "work-laptop".to_string(),
+                dns_name: Some("work-laptop.tailnet-123.ts.net.".to_string()),
+                tailscale_ips: vec!["100.64.0.2".to_string()],
+                online: false,
+            },
+        ]
+    );
+    assert_eq!(status.tailscale_ips, vec!["100.64.12.34".to_string()]);
+}
+
+#[test]
+fn parse_status_json_without_peers_has_empty_list() {
+    let status = parse_status_json(r#"{ "BackendState": "NeedsLogin" }"#).expect("should parse");
+    assert!(status.peers.is_empty());
+
+    let status = parse_status_json(r#"{ "BackendState": "Running", "Peer": null }"#).expect("should parse");
+    assert!(status.peers.is_empty());
+}
+
+#[test]
+fn parse_status_json_extracts_magic_dns_and_https() {
+    let json = r#"
+    {
+      "BackendState": "Running",
+      "CurrentTailnet": { "Name": "user@example.com", "MagicDNSSuffix": "tailnet-123.ts.net", "MagicDNSEnabled": true },
+      "CertDomains": ["home-pc.tailnet-123.ts.net"]
+    }
+    "#;
+    let status = parse_status_json(json).expect("should parse");
+    assert!(status.magic_dns_enabled);
+    assert!(status.https_enabled);
+
+    let json = r#"
+    {
+      "BackendState": "Running",
+      "CurrentTailnet": { "MagicDNSSuffix": "tailnet-123.ts.net", "MagicDNSEnabled": false },
+      "CertDomains": null
+    }
+    "#;
+    let status = parse_status_json(json).expect("should parse");
+    assert!(!status.magic_dns_enabled);
+    assert!(!status.https_enabled);
+
+    // Older clients only report the suffix
+    let status = parse_status_json(r#"{ "MagicDNSSuffix": "tailnet-123.ts.net" }"#).expect("should parse");
+    assert!(status.magic_dns_enabled);
+}
+
+#[test]
+fn command_cache_reuses_value_until_cleared() {
+    let cache: CommandCache<Option<String>> = CommandCache::new();
+    let mut lookups = 0;
+
+    // "Not found" is cached too
+    assert_eq!(cache.get_or_resolve(|| { lookups += 1; None }), None);
+    assert_eq!(cache.get_or_resolve(|| { lookups += 1; Some("tailscale".to_string()) }), None);
+    assert_eq!(lookups, 1);
+
+    cache.clear();
+    assert_eq!(
+        cache.get_or_resolve(|| { lookups += 1; Some("tailscale".to_string()) }),
+        Some("tailscale".to_string())
+    );
+    assert_eq!(lookups, 2);
+}
+
+#[test]
+fn parse_version_output_takes_first_line() {
+    let output = "1.62.0\n  tailscale commit: 4b4b8f8b6b1e2c7e9a0d1f2e3a4b5c6d7e8f9a0b\n  other commit: 0a1b2c3d4e5f\n  go version: go1.22.1\n";
+    assert_eq!(parse_version_output(output).as_deref(), Some("1.62.0"));
+
+    assert_eq!(
+        parse_version_output("\n1.63.0-dev20240315\n  go version: go1.22.1\n").as_deref(),
+        Some("1.63.0-dev20240315")
+    );
+    assert_eq!(parse_version_output(""), None);
+    assert_eq!(parse_version_output("command not found\n"), None);
+}
+
+#[test]
+fn version_comparison_gates_newer_flags() {
+    assert_eq!(parse_semver("1.62.0"), Some((1, 62, 0)));
+    assert_eq!(parse_semver("1.63.0-dev20240315"), Some((1, 63, 0)));
+    assert_eq!(parse_semver("garbage"), None);
+
+    assert!(version_at_least(Some("1.62.0"), MIN_SERVE_SET_PATH_VERSION));
+    assert!(!version_at_least(Some("1.50.1"), MIN_SERVE_SET_PATH_VERSION));
+    // Unknown versions don't block the feature
+    assert!(version_at_least(None, MIN_SERVE_SET_PATH_VERSION));
+}
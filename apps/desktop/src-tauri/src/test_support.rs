@@ -0,0 +1,74 @@
+//! Shared test fixtures
+//!
+//! Canned loopback HTTP server used by the server and command tests.
+
+use std::time::Duration;
+
+/// Minimal HTTP server that answers every request with a fixed response
+pub struct HttpResponder {
+    status: &'static str,
+    body: &'static str,
+    path: Option<&'static str>,
+    delay: Duration,
+}
+
+impl HttpResponder {
+    /// Responds with `status` and `body` to every request
+    pub fn new(status: &'static str, body: &'static str) -> Self {
+        Self {
+            status,
+            body,
+            path: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Only responds this way to `GET path`; other requests get `404 Not Found`
+    pub fn only_on(mut self, path: &'static str) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Refuses connections until `delay` has passed
+    pub fn after(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Starts serving on a free loopback port and returns the port
+    pub fn spawn(self) -> u16 {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // A delayed server gives the port back so connections are refused meanwhile
+        let listener = self.delay.is_zero().then_some(listener);
+
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let listener = match listener {
+                Some(listener) => tokio::net::TcpListener::from_std(listener).unwrap(),
+                None => {
+                    tokio::time::sleep(self.delay).await;
+                    tokio::net::TcpListener::bind(("127.0.0.1", port)).await.unwrap()
+                }
+            };
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let (status, body) = match self.path {
+                    Some(path) if !request.starts_with(&format!("GET {path} ")) => ("404 Not Found", ""),
+                    _ => (self.status, self.body),
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        port
+    }
+}
@@ -0,0 +1,175 @@
+//! System tray with quick server and tunnel toggles
+//!
+//! Menu actions call the same commands as the frontend, against the same
+//! managed state, so the tray and the UI agree about what is running.
+
+use crate::commands::{self, CommandError};
+use crate::config;
+use crate::server;
+use crate::window;
+use crate::ServerState;
+use crate::TunnelState;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager};
+
+/// Id of the app's tray icon
+pub const TRAY_ID: &str = "main";
+
+/// Action behind a tray menu item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    ToggleWindow,
+    StartServer,
+    StopServer,
+    StartTunnel,
+    StopTunnel,
+    Quit,
+}
+
+impl TrayAction {
+    /// Every action, in menu order
+    pub const ALL: [TrayAction; 6] = [
+        TrayAction::ToggleWindow,
+        TrayAction::StartServer,
+        TrayAction::StopServer,
+        TrayAction::StartTunnel,
+        TrayAction::StopTunnel,
+        TrayAction::Quit,
+    ];
+
+    /// Menu item id
+    pub fn id(self) -> &'static str {
+        match self {
+            TrayAction::ToggleWindow => "toggle-window",
+            TrayAction::StartServer => "start-server",
+            TrayAction::StopServer => "stop-server",
+            TrayAction::StartTunnel => "start-tunnel",
+            TrayAction::StopTunnel => "stop-tunnel",
+            TrayAction::Quit => "quit",
+        }
+    }
+
+    /// Menu item label
+    pub fn label(self) -> &'static str {
+        match self {
+            TrayAction::ToggleWindow => "Show/Hide Window",
+            TrayAction::StartServer => "Start Server",
+            TrayAction::StopServer => "Stop Server",
+            TrayAction::StartTunnel => "Start Tunnel",
+            TrayAction::StopTunnel => "Stop Tunnel",
+            TrayAction::Quit => "Quit S-IDE",
+        }
+    }
+
+    /// Action for a menu item id
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+}
+
+/// Builds the tray icon and its menu
+///
+/// # Errors
+///
+/// Returns an error if the menu or tray icon can't be created
+pub fn setup(app: &tauri::App) -> tauri::Result<()> {
+    let item = |action: TrayAction| MenuItem::with_id(app, action.id(), action.label(), true, None::<&str>);
+    let separator = || PredefinedMenuItem::separator(app);
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &item(TrayAction::ToggleWindow)?,
+            &separator()?,
+            &item(TrayAction::StartServer)?,
+            &item(TrayAction::StopServer)?,
+            &separator()?,
+            &item(TrayAction::StartTunnel)?,
+            &item(TrayAction::StopTunnel)?,
+            &separator()?,
+            &item(TrayAction::Quit)?,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip("S-IDE")
+        .show_menu_on_left_click(true)
+        .on_menu_event(|app, event| {
+            if let Some(action) = TrayAction::from_id(event.id().as_ref()) {
+                handle_action(app, action);
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Whether the tray icon exists, so hiding the window can't strand the app
+pub fn is_available(app: &AppHandle) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+}
+
+fn handle_action(app: &AppHandle, action: TrayAction) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match run_action(&app, action).await {
+            Ok(message) => tracing::info!(target: "Desktop", "Tray: {message}"),
+            Err(e) => tracing::error!(target: "Desktop", "Tray action '{}' failed: {e}", action.label()),
+        }
+    });
+}
+
+async fn run_action(app: &AppHandle, action: TrayAction) -> Result<String, String> {
+    match action {
+        TrayAction::ToggleWindow => {
+            toggle_window(app);
+            Ok("Window toggled".to_string())
+        }
+        TrayAction::StartServer => {
+            let port = config::load().default_port;
+            commands::start_server(app.clone(), app.state::<ServerState>(), port, None, None, None, None)
+                .await
+                .map_err(|e| e.to_string())
+        }
+        TrayAction::StopServer => {
+            // The server started at launch isn't in `ServerState`
+            let stopped_startup_server = window::stop_startup_server().await?;
+            match commands::stop_server(app.state::<ServerState>(), None).await {
+                Err(CommandError::NotRunning(_)) if stopped_startup_server => Ok("Server stopped".to_string()),
+                result => result.map_err(|e| e.to_string()),
+            }
+        }
+        TrayAction::StartTunnel => {
+            let port = server::last_known_port();
+            commands::start_tunnel(app.clone(), app.state::<TunnelState>(), port, None, None, None)
+                .await
+                .map_err(|e| e.to_string())
+        }
+        TrayAction::StopTunnel => commands::stop_tunnel(app.state::<TunnelState>())
+            .await
+            .map_err(|e| e.to_string()),
+        TrayAction::Quit => {
+            commands::shutdown_everything(app).await;
+            app.exit(0);
+            Ok("Quitting".to_string())
+        }
+    }
+}
+
+/// Hides the main window if it is visible, otherwise shows and focuses it
+fn toggle_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(window::WINDOW_LABEL) else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
@@ -0,0 +1,39 @@
+//! Unit tests for the system tray
+//!
+//! Tests the mapping between tray menu item ids and actions.
+
+use crate::tray::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_ids_map_back_to_their_action() {
+        for action in TrayAction::ALL {
+            assert_eq!(TrayAction::from_id(action.id()), Some(action));
+        }
+        assert_eq!(TrayAction::from_id("start-server"), Some(TrayAction::StartServer));
+        assert_eq!(TrayAction::from_id("stop-tunnel"), Some(TrayAction::StopTunnel));
+        assert_eq!(TrayAction::from_id("quit"), Some(TrayAction::Quit));
+    }
+
+    #[test]
+    fn test_unknown_menu_ids_are_ignored() {
+        assert_eq!(TrayAction::from_id(""), None);
+        assert_eq!(TrayAction::from_id("Start Server"), None);
+        assert_eq!(TrayAction::from_id("restart-server"), None);
+    }
+
+    #[test]
+    fn test_menu_ids_and_labels_are_unique() {
+        let mut ids: Vec<_> = TrayAction::ALL.iter().map(|a| a.id()).collect();
+        let mut labels: Vec<_> = TrayAction::ALL.iter().map(|a| a.label()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        labels.sort_unstable();
+        labels.dedup();
+        assert_eq!(ids.len(), TrayAction::ALL.len());
+        assert_eq!(labels.len(), TrayAction::ALL.len());
+    }
+}
@@ -1,86 +1,731 @@
-//! Tunnel management for remote access via localtunnel
+//! Tunnel management for remote access via localtunnel, ngrok or cloudflared
 
 use crate::common;
+use crate::server::LogBuffer;
+use serde::{Deserialize, Serialize};
 use tokio::process::Child;
 use tokio::sync::Mutex;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Delay before checking tunnel URL (seconds)
 #[allow(dead_code)]
 pub const TUNNEL_URL_DELAY_SECS: u64 = 2;
 
+/// Default time to wait for the tunnel to report its public URL
+///
+/// localtunnel can take 10+ seconds on slow networks.
+pub const DEFAULT_TUNNEL_URL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Interval between checks for the tunnel URL while waiting
+pub const TUNNEL_URL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Interval between tunnel health checks
+pub const TUNNEL_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout for a single health check request through the tunnel
+pub const TUNNEL_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Consecutive failed health checks before the tunnel is respawned
+pub const TUNNEL_HEALTH_FAILURE_THRESHOLD: u32 = 3;
+
+/// Event emitted after the tunnel was respawned
+pub const TUNNEL_RECONNECTED_EVENT: &str = "tunnel-reconnected";
+
+/// ngrok's local inspection API listing the active tunnels
+pub const NGROK_API_URL: &str = "http://127.0.0.1:4040/api/tunnels";
+
+/// Returns the localtunnel gate password, which is this machine's public IP
+pub const LOCALTUNNEL_PASSWORD_URL: &str = "https://loca.lt/mytunnelpassword";
+
+/// Timeout for the request that checks for localtunnel's reminder page
+pub const INTERSTITIAL_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Status localtunnel answers with while showing its reminder page
+/// (511 Network Authentication Required)
+pub const LOCALTUNNEL_INTERSTITIAL_STATUS: u16 = 511;
+
+/// Text on localtunnel's reminder page
+const LOCALTUNNEL_INTERSTITIAL_MARKERS: &[&str] = &[
+    "loca.lt/mytunnelpassword",
+    "Tunnel website ahead",
+    "bypass-tunnel-reminder",
+];
+
+/// How many times to poll the ngrok API before giving up on the URL
+pub const NGROK_URL_POLL_ATTEMPTS: u32 = 20;
+
+/// Delay between ngrok API polls
+pub const NGROK_URL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Timeout for each request to ngrok's local API
+pub const NGROK_API_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Timeout for fetching the localtunnel password
+pub const LOCALTUNNEL_PASSWORD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Service used to expose the local server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelProvider {
+    /// `npx localtunnel`
+    #[default]
+    LocalTunnel,
+    /// `ngrok http`
+    Ngrok,
+    /// `cloudflared tunnel`
+    Cloudflared,
+}
+
+/// Shortest subdomain localtunnel accepts
+pub const MIN_SUBDOMAIN_LEN: usize = 4;
+
+/// Longest subdomain localtunnel accepts
+pub const MAX_SUBDOMAIN_LEN: usize = 63;
+
+/// Configuration for a tunnel
+#[derive(Debug, Clone)]
+pub struct TunnelConfig {
+    /// The local port to expose
+    pub port: u16,
+    /// Service used to expose the port
+    pub provider: TunnelProvider,
+    /// Stable subdomain to request (localtunnel only)
+    pub subdomain: Option<String>,
+    /// How long to wait for the public URL after (re)starting
+    pub url_timeout: Duration,
+}
+
+impl TunnelConfig {
+    /// Configuration for a localtunnel with a random subdomain
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            provider: TunnelProvider::default(),
+            subdomain: None,
+            url_timeout: DEFAULT_TUNNEL_URL_TIMEOUT,
+        }
+    }
+}
+
+/// Checks a requested subdomain against localtunnel's rules
+///
+/// Subdomains are 4-63 lowercase letters, digits and hyphens, and can't start
+/// or end with a hyphen.
+///
+/// # Errors
+///
+/// Returns an error describing why the subdomain is invalid
+pub fn validate_subdomain(subdomain: &str) -> Result<(), String> {
+    if subdomain.len() < MIN_SUBDOMAIN_LEN || subdomain.len() > MAX_SUBDOMAIN_LEN {
+        return Err(format!(
+            "Subdomain must be between {MIN_SUBDOMAIN_LEN} and {MAX_SUBDOMAIN_LEN} characters long"
+        ));
+    }
+    if !subdomain
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err(format!(
+            "Invalid subdomain '{subdomain}': only lowercase letters, digits and hyphens are allowed"
+        ));
+    }
+    if subdomain.starts_with('-') || subdomain.ends_with('-') {
+        return Err(format!("Invalid subdomain '{subdomain}': can't start or end with a hyphen"));
+    }
+    Ok(())
+}
+
+/// Builds the npx arguments for running localtunnel
+pub fn localtunnel_args(port: u16, subdomain: Option<&str>) -> Vec<String> {
+    let mut args = vec!["localtunnel".to_string(), "--port".to_string(), port.to_string()];
+    if let Some(subdomain) = subdomain {
+        args.push("--subdomain".to_string());
+        args.push(subdomain.to_string());
+    }
+    args
+}
+
 /// Find npx command to run localtunnel
 fn find_npx_command() -> Result<String, String> {
     common::find_npx_command()
 }
 
+/// Find a tunnel client executable (ngrok, cloudflared) in PATH
+fn find_tunnel_command(name: &str, install_url: &str) -> Result<String, String> {
+    which::which(name)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|_| format!("{name} not found in PATH. Please install it from {install_url}"))
+}
+
+/// Find the ngrok executable in PATH
+fn find_ngrok_command() -> Result<String, String> {
+    find_tunnel_command("ngrok", "https://ngrok.com/download")
+}
+
+/// Find the cloudflared executable in PATH
+fn find_cloudflared_command() -> Result<String, String> {
+    find_tunnel_command(
+        "cloudflared",
+        "https://developers.cloudflare.com/cloudflare-one/connections/connect-networks/downloads/",
+    )
+}
+
+/// Extracts the tunnel URL from a localtunnel output line
+///
+/// localtunnel outputs: "your url is: https://xxx.loca.lt"
+pub fn parse_localtunnel_url(line: &str) -> Option<String> {
+    let url = line.split("your url is:").nth(1)?.trim();
+    url.starts_with("https://").then(|| url.to_string())
+}
+
+/// Extracts the quick-tunnel URL from a cloudflared log line
+///
+/// cloudflared logs the URL to stderr inside a banner, e.g.
+/// `INF |  https://some-random-words.trycloudflare.com  |`
+pub fn parse_cloudflared_url(line: &str) -> Option<String> {
+    line.split(|c: char| c.is_whitespace() || c == '|')
+        .find(|token| token.starts_with("https://") && token.ends_with(".trycloudflare.com"))
+        .map(str::to_string)
+}
+
+/// Response of ngrok's `/api/tunnels` endpoint
+#[derive(Debug, Deserialize)]
+struct NgrokTunnelList {
+    tunnels: Vec<NgrokTunnel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NgrokTunnel {
+    public_url: String,
+    #[serde(default)]
+    proto: String,
+    #[serde(default)]
+    config: Option<NgrokTunnelConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NgrokTunnelConfig {
+    #[serde(default)]
+    addr: String,
+}
+
+/// Extracts the public URL for `port` from an ngrok `/api/tunnels` response
+///
+/// Tunnels forwarding to other ports (e.g. from another ngrok session) are
+/// ignored, and an https tunnel is preferred over plain http.
+pub fn parse_ngrok_tunnels(body: &str, port: u16) -> Option<String> {
+    let list: NgrokTunnelList = serde_json::from_str(body).ok()?;
+    let port_suffix = format!(":{port}");
+
+    let matching: Vec<&NgrokTunnel> = list
+        .tunnels
+        .iter()
+        .filter(|t| !t.public_url.is_empty())
+        .filter(|t| {
+            t.config
+                .as_ref()
+                .is_none_or(|c| c.addr.trim_end_matches('/').ends_with(&port_suffix))
+        })
+        .collect();
+
+    matching
+        .iter()
+        .find(|t| t.proto == "https")
+        .or_else(|| matching.first())
+        .map(|t| t.public_url.clone())
+}
+
+/// Payload of [`TUNNEL_RECONNECTED_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+pub struct TunnelReconnected {
+    /// The new public URL, if the respawned tunnel reported one in time
+    pub url: Option<String>,
+}
+
+/// Tracks consecutive failed health checks
+#[derive(Debug, Default)]
+pub struct TunnelHealth {
+    consecutive_failures: u32,
+}
+
+impl TunnelHealth {
+    /// Records a health check result
+    ///
+    /// Returns true once [`TUNNEL_HEALTH_FAILURE_THRESHOLD`] checks in a row
+    /// have failed and the tunnel should be reconnected. A success resets the count.
+    pub fn record(&mut self, success: bool) -> bool {
+        if success {
+            self.consecutive_failures = 0;
+            return false;
+        }
+        self.consecutive_failures += 1;
+        self.consecutive_failures >= TUNNEL_HEALTH_FAILURE_THRESHOLD
+    }
+
+    /// Whether the last health check succeeded
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+
+    /// Clears the failure count after a reconnect
+    pub fn reset(&mut self) {
+        self.consecutive_failures = 0;
+    }
+}
+
+/// Whether a response received through the tunnel means it is still connected
+///
+/// All providers answer 404 for a tunnel whose client has gone away, and 5xx
+/// when they can't reach the local server.
+pub fn is_healthy_status(status: u16) -> bool {
+    status != 404 && status < 500
+}
+
 /// Handle to a running tunnel process
+///
+/// The child process is shared with a health monitor task that respawns it
+/// when the tunnel stops responding; the handle only controls that task.
 pub struct TunnelHandle {
-    /// The child process
-    child: Child,
+    /// The current child process, replaced on reconnect
+    child: Arc<Mutex<Child>>,
     /// The URL of the tunnel (available after startup)
     url: Arc<Mutex<Option<String>>>,
+    /// How long [`wait_for_url`] waits for the URL
+    url_timeout: Duration,
+    /// Captured stdout/stderr of the tunnel process
+    logs: LogBuffer,
+    /// Password for the localtunnel reminder page (localtunnel only)
+    password: Arc<Mutex<Option<String>>>,
+    /// Whether visitors hit the localtunnel reminder page and need the password
+    requires_password: Arc<AtomicBool>,
+    /// Result of the most recent health check
+    healthy: Arc<AtomicBool>,
+    /// Cancels the health monitor
+    stop_token: CancellationToken,
+    /// The health monitor task
+    monitor: Option<JoinHandle<()>>,
+    /// When the tunnel was first spawned; reconnects don't reset it
+    started_at: Instant,
 }
 
 // Implement Drop to ensure process cleanup on orphaning
 impl Drop for TunnelHandle {
     fn drop(&mut self) {
+        self.stop_token.cancel();
         // Try to kill the child process when handle is dropped
         // This prevents process orphaning
-        let _ = self.child.start_kill();
+        if let Ok(mut child) = self.child.try_lock() {
+            let _ = child.start_kill();
+        }
     }
 }
 
-/// Starts a localtunnel on the specified port
+/// Starts a tunnel as described by `config`
+///
+/// The tunnel is checked every [`TUNNEL_HEALTH_CHECK_INTERVAL`] and respawned
+/// after [`TUNNEL_HEALTH_FAILURE_THRESHOLD`] consecutive failures, calling
+/// `on_reconnect` with the new URL.
 ///
 /// # Errors
 ///
-/// Returns an error if npx cannot be found or the tunnel fails to start
-pub fn start(port: u16) -> Result<TunnelHandle, String> {
+/// Returns an error if the subdomain is invalid, the provider's executable
+/// cannot be found or the tunnel fails to start
+pub fn start<F>(config: TunnelConfig, on_reconnect: F) -> Result<TunnelHandle, String>
+where
+    F: Fn(TunnelReconnected) + Send + Sync + 'static,
+{
     // Validate port range
-    common::validate_port(port)?;
+    common::validate_port(config.port)?;
+    if let Some(subdomain) = &config.subdomain {
+        if config.provider != TunnelProvider::LocalTunnel {
+            return Err("Custom subdomains are only supported with localtunnel".to_string());
+        }
+        validate_subdomain(subdomain)?;
+    }
 
-    let npx_cmd = find_npx_command()?;
+    let url = Arc::new(Mutex::new(None));
+    let logs = LogBuffer::default();
+    let child = Arc::new(Mutex::new(spawn_provider(&config, &logs, &url)?));
+    let started_at = Instant::now();
 
-    let mut child = tokio::process::Command::new(&npx_cmd)
-        .arg("localtunnel")
-        .arg("--port")
-        .arg(port.to_string())
+    let password = Arc::new(Mutex::new(None));
+    if config.provider == TunnelProvider::LocalTunnel {
+        // Visitors must enter the tunnel password on localtunnel's reminder page
+        let password = password.clone();
+        tokio::spawn(async move {
+            match fetch_localtunnel_password().await {
+                Ok(value) => *password.lock().await = Some(value),
+                Err(e) => tracing::error!(target: "Tunnel", "Failed to fetch tunnel password: {}", e),
+            }
+        });
+    }
+
+    let requires_password = Arc::new(AtomicBool::new(false));
+    if config.provider == TunnelProvider::LocalTunnel {
+        // Best-effort: find out whether visitors will be asked for the password
+        let url = url.clone();
+        let requires_password = requires_password.clone();
+        let url_timeout = config.url_timeout;
+        tokio::spawn(async move {
+            if let Some(tunnel_url) = poll_url(&url, url_timeout, TUNNEL_URL_POLL_INTERVAL).await {
+                let detected = probe_interstitial(&tunnel_url).await;
+                tracing::debug!(target: "Tunnel", "Reminder page detected: {}", detected);
+                requires_password.store(detected, Ordering::SeqCst);
+            }
+        });
+    }
+
+    let healthy = Arc::new(AtomicBool::new(true));
+    let stop_token = CancellationToken::new();
+    let url_timeout = config.url_timeout;
+
+    let monitor = tokio::spawn(monitor_health(
+        config,
+        child.clone(),
+        logs.clone(),
+        url.clone(),
+        healthy.clone(),
+        stop_token.clone(),
+        on_reconnect,
+    ));
+
+    Ok(TunnelHandle {
+        child,
+        url,
+        url_timeout,
+        logs,
+        password,
+        requires_password,
+        healthy,
+        stop_token,
+        monitor: Some(monitor),
+        started_at,
+    })
+}
+
+/// Spawns the provider's tunnel process, which reports its URL into `url`
+fn spawn_provider(
+    config: &TunnelConfig,
+    logs: &LogBuffer,
+    url: &Arc<Mutex<Option<String>>>,
+) -> Result<Child, String> {
+    let output = TunnelOutput { logs, url };
+    match config.provider {
+        TunnelProvider::LocalTunnel => start_localtunnel(config.port, config.subdomain.as_deref(), output),
+        TunnelProvider::Ngrok => start_ngrok(config.port, output),
+        TunnelProvider::Cloudflared => start_cloudflared(config.port, output),
+    }
+}
+
+/// Where a tunnel process reports its output and URL
+#[derive(Clone, Copy)]
+struct TunnelOutput<'a> {
+    logs: &'a LogBuffer,
+    url: &'a Arc<Mutex<Option<String>>>,
+}
+
+/// Spawns a task that stores each line of `stream` in `logs` and the first
+/// URL `parse` finds in `url`
+pub fn capture_output<R>(
+    stream: R,
+    logs: LogBuffer,
+    url: Arc<Mutex<Option<String>>>,
+    parse: fn(&str) -> Option<String>,
+) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stream).lines();
+
+        // Keep draining after the URL is found so the pipe never fills up
+        while let Ok(Some(line)) = lines.next_line().await {
+            tracing::debug!(target: "Tunnel", "{}", line);
+            let parsed = parse(&line);
+            logs.push(line);
+            if let Some(captured_url) = parsed {
+                let mut guard = url.lock().await;
+                if guard.is_none() {
+                    tracing::info!(target: "Tunnel", "URL captured: {}", captured_url);
+                    *guard = Some(captured_url);
+                }
+            }
+        }
+    })
+}
+
+/// Spawns `command` and captures the output and tunnel URL from both its stdout and stderr
+fn spawn_with_url_capture(
+    mut command: tokio::process::Command,
+    output: TunnelOutput<'_>,
+    parse: fn(&str) -> Option<String>,
+) -> Result<Child, String> {
+    let mut child = command
+        .stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .kill_on_drop(true)
         .spawn()
         .map_err(|e| format!("Failed to start tunnel: {e}"))?;
 
-    // Take stdout before moving child into TunnelHandle
     let stdout = child.stdout.take()
         .ok_or_else(|| "Failed to capture stdout from tunnel process".to_string())?;
+    let stderr = child.stderr.take()
+        .ok_or_else(|| "Failed to capture stderr from tunnel process".to_string())?;
 
-    // Create the URL holder
-    let url = Arc::new(Mutex::new(None));
-    let url_clone = url.clone();
+    capture_output(stdout, output.logs.clone(), output.url.clone(), parse);
+    capture_output(stderr, output.logs.clone(), output.url.clone(), parse);
 
-    // Spawn background task to capture URL from stdout
+    Ok(child)
+}
+
+/// Starts a localtunnel on the specified port
+fn start_localtunnel(
+    port: u16,
+    subdomain: Option<&str>,
+    output: TunnelOutput<'_>,
+) -> Result<Child, String> {
+    let npx_cmd = find_npx_command()?;
+
+    let mut command = tokio::process::Command::new(&npx_cmd);
+    command.args(localtunnel_args(port, subdomain));
+
+    spawn_with_url_capture(command, output, parse_localtunnel_url)
+}
+
+/// Fetches the password localtunnel asks visitors for
+async fn fetch_localtunnel_password() -> Result<String, String> {
+    let body = common::http_client()
+        .get(LOCALTUNNEL_PASSWORD_URL)
+        .timeout(LOCALTUNNEL_PASSWORD_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?
+        .error_for_status()
+        .map_err(|e| format!("Request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {e}"))?;
+
+    let password = body.trim();
+    if password.is_empty() {
+        return Err("Empty response".to_string());
+    }
+    Ok(password.to_string())
+}
+
+/// Whether a response from a tunnel URL is localtunnel's reminder page
+///
+/// The page is served with [`LOCALTUNNEL_INTERSTITIAL_STATUS`]; `body` is
+/// checked for its text when available (it isn't for HEAD requests).
+pub fn is_localtunnel_interstitial(status: u16, body: Option<&str>) -> bool {
+    status == LOCALTUNNEL_INTERSTITIAL_STATUS
+        || body.is_some_and(|body| LOCALTUNNEL_INTERSTITIAL_MARKERS.iter().any(|marker| body.contains(marker)))
+}
+
+/// Checks whether visitors to `url` are shown localtunnel's reminder page
+///
+/// Sends a HEAD request, falling back to GET when HEAD isn't allowed.
+/// Any failure counts as "no", since this only decides how prominently the
+/// password is shown.
+async fn probe_interstitial(url: &str) -> bool {
+    let client = common::http_client();
+    let Ok(response) = client.head(url).timeout(INTERSTITIAL_PROBE_TIMEOUT).send().await else {
+        return false;
+    };
+    let status = response.status().as_u16();
+    if status != 405 {
+        return is_localtunnel_interstitial(status, None);
+    }
+
+    let Ok(response) = client.get(url).timeout(INTERSTITIAL_PROBE_TIMEOUT).send().await else {
+        return false;
+    };
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    is_localtunnel_interstitial(status, Some(&body))
+}
+
+/// Starts a cloudflared quick tunnel on the specified port
+///
+/// cloudflared prints the trycloudflare.com URL to stderr.
+fn start_cloudflared(port: u16, output: TunnelOutput<'_>) -> Result<Child, String> {
+    let cloudflared_cmd = find_cloudflared_command()?;
+
+    let mut command = tokio::process::Command::new(&cloudflared_cmd);
+    command
+        .arg("tunnel")
+        .arg("--url")
+        .arg(format!("http://localhost:{port}"));
+
+    spawn_with_url_capture(command, output, parse_cloudflared_url)
+}
+
+/// Starts an ngrok tunnel on the specified port
+///
+/// ngrok's URL is read from its local API; `--log stdout` replaces its
+/// full-screen UI with log lines that end up in the tunnel logs.
+fn start_ngrok(port: u16, output: TunnelOutput<'_>) -> Result<Child, String> {
+    let ngrok_cmd = find_ngrok_command()?;
+
+    let mut command = tokio::process::Command::new(&ngrok_cmd);
+    command
+        .arg("http")
+        .arg(port.to_string())
+        .arg("--log")
+        .arg("stdout");
+    let child = spawn_with_url_capture(command, output, |_| None)?;
+
+    let url_clone = output.url.clone();
+
+    // Spawn background task to poll the ngrok API for the public URL
     tokio::spawn(async move {
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
+        let client = common::http_client();
 
-        // Read lines looking for the tunnel URL
-        while let Ok(Some(line)) = lines.next_line().await {
-            println!("[Tunnel] {}", line);
-            // localtunnel outputs: "your url is: https://xxx.loca.lt"
-            if line.contains("your url is:") {
-                if let Some(url_str) = line.split("your url is:").nth(1) {
-                    let captured_url = url_str.trim().to_string();
-                    println!("[Tunnel] URL captured: {}", captured_url);
-                    *url_clone.lock().await = Some(captured_url);
-                }
+        for _ in 0..NGROK_URL_POLL_ATTEMPTS {
+            tokio::time::sleep(NGROK_URL_POLL_INTERVAL).await;
+
+            let body = match client.get(NGROK_API_URL).timeout(NGROK_API_TIMEOUT).send().await {
+                Ok(response) => match response.text().await {
+                    Ok(body) => body,
+                    Err(_) => continue,
+                },
+                // API not up yet
+                Err(_) => continue,
+            };
+
+            if let Some(captured_url) = parse_ngrok_tunnels(&body, port) {
+                tracing::info!(target: "Tunnel", "URL captured: {}", captured_url);
+                *url_clone.lock().await = Some(captured_url);
+                return;
             }
         }
+
+        tracing::warn!(target: "Tunnel", "ngrok did not report a public URL for port {}", port);
     });
 
-    Ok(TunnelHandle { child, url })
+    Ok(child)
+}
+
+/// Requests the tunnel URL and reports whether the tunnel answered
+async fn check_health(client: &reqwest::Client, url: &str) -> bool {
+    // Skip localtunnel's reminder page so the request reaches the local server
+    match client
+        .get(url)
+        .header("bypass-tunnel-reminder", "true")
+        .timeout(TUNNEL_HEALTH_CHECK_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => is_healthy_status(response.status().as_u16()),
+        Err(_) => false,
+    }
+}
+
+/// Periodically checks the tunnel and respawns it after repeated failures
+///
+/// Returns once `stop_token` is cancelled.
+async fn monitor_health<F>(
+    config: TunnelConfig,
+    child: Arc<Mutex<Child>>,
+    logs: LogBuffer,
+    url: Arc<Mutex<Option<String>>>,
+    healthy: Arc<AtomicBool>,
+    stop_token: CancellationToken,
+    on_reconnect: F,
+) where
+    F: Fn(TunnelReconnected) + Send + Sync + 'static,
+{
+    let client = common::http_client();
+    let mut health = TunnelHealth::default();
+
+    loop {
+        tokio::select! {
+            _ = stop_token.cancelled() => return,
+            _ = tokio::time::sleep(TUNNEL_HEALTH_CHECK_INTERVAL) => {}
+        }
+
+        // Nothing to check until the provider reports a URL
+        let Some(current_url) = url.lock().await.clone() else {
+            continue;
+        };
+
+        let success = tokio::select! {
+            _ = stop_token.cancelled() => return,
+            success = check_health(client, &current_url) => success,
+        };
+        let reconnect = health.record(success);
+        healthy.store(health.is_healthy(), Ordering::SeqCst);
+        if !reconnect {
+            continue;
+        }
+
+        tracing::warn!(target: "Tunnel", "{} health checks failed, reconnecting", TUNNEL_HEALTH_FAILURE_THRESHOLD);
+        {
+            let mut child = child.lock().await;
+            if stop_token.is_cancelled() {
+                return;
+            }
+            let _ = child.kill().await;
+            *url.lock().await = None;
+
+            logs.push(format!("Reconnecting after {TUNNEL_HEALTH_FAILURE_THRESHOLD} failed health checks"));
+            match spawn_provider(&config, &logs, &url) {
+                Ok(new_child) => *child = new_child,
+                Err(e) => {
+                    // Keep the failure count so the next check retries the spawn
+                    tracing::error!(target: "Tunnel", "Failed to reconnect: {}", e);
+                    continue;
+                }
+            }
+        }
+        health.reset();
+
+        let new_url = tokio::select! {
+            _ = stop_token.cancelled() => return,
+            new_url = poll_url(&url, config.url_timeout, TUNNEL_URL_POLL_INTERVAL) => new_url,
+        };
+        healthy.store(new_url.is_some(), Ordering::SeqCst);
+        tracing::info!(target: "Tunnel", "Reconnected: {}", new_url.as_deref().unwrap_or("URL pending"));
+        on_reconnect(TunnelReconnected { url: new_url });
+    }
+}
+
+/// Polls `url` every `interval` until the provider reports it or `timeout` elapses
+///
+/// A `timeout` too large to represent as a deadline waits indefinitely.
+pub async fn poll_url(
+    url: &Arc<Mutex<Option<String>>>,
+    timeout: Duration,
+    interval: Duration,
+) -> Option<String> {
+    let deadline = tokio::time::Instant::now().checked_add(timeout);
+    loop {
+        if let Some(found) = url.lock().await.clone() {
+            return Some(found);
+        }
+        let now = tokio::time::Instant::now();
+        let remaining = match deadline {
+            Some(deadline) if now >= deadline => return None,
+            Some(deadline) => deadline - now,
+            None => interval,
+        };
+        tokio::time::sleep(interval.min(remaining)).await;
+    }
+}
+
+/// Waits up to the configured `url_timeout` for the tunnel URL
+///
+/// # Returns
+///
+/// Returns None if the tunnel didn't report a URL in time
+pub async fn wait_for_url(handle: &TunnelHandle) -> Option<String> {
+    poll_url(&handle.url, handle.url_timeout, TUNNEL_URL_POLL_INTERVAL).await
 }
 
 /// Stops the tunnel
@@ -89,7 +734,12 @@ pub fn start(port: u16) -> Result<TunnelHandle, String> {
 ///
 /// Returns an error if the tunnel process fails to stop
 pub async fn stop(mut handle: TunnelHandle) -> Result<(), String> {
-    handle.child.kill()
+    handle.stop_token.cancel();
+    if let Some(monitor) = handle.monitor.take() {
+        let _ = monitor.await;
+    }
+
+    handle.child.lock().await.kill()
         .await
         .map_err(|e| format!("Failed to stop tunnel: {e}"))?;
     Ok(())
@@ -103,3 +753,34 @@ pub async fn stop(mut handle: TunnelHandle) -> Result<(), String> {
 pub async fn get_url(handle: &TunnelHandle) -> Option<String> {
     handle.url.lock().await.clone()
 }
+
+/// Gets the password for the localtunnel reminder page
+///
+/// # Returns
+///
+/// Returns the password once fetched, None for other providers
+pub async fn get_password(handle: &TunnelHandle) -> Option<String> {
+    handle.password.lock().await.clone()
+}
+
+/// Whether visitors must get past localtunnel's reminder page with the password
+///
+/// False until the tunnel URL has been probed, and always for other providers.
+pub fn requires_password(handle: &TunnelHandle) -> bool {
+    handle.requires_password.load(Ordering::SeqCst)
+}
+
+/// When the tunnel was first spawned
+pub fn started_at(handle: &TunnelHandle) -> Instant {
+    handle.started_at
+}
+
+/// Gets the captured output of the tunnel process
+pub fn logs(handle: &TunnelHandle) -> &LogBuffer {
+    &handle.logs
+}
+
+/// Whether the most recent health check through the tunnel succeeded
+pub fn is_healthy(handle: &TunnelHandle) -> bool {
+    handle.healthy.load(Ordering::SeqCst)
+}
@@ -1,6 +1,7 @@
 //! Unit tests for tunnel management
 //!
-//! Tests localtunnel spawning, URL capture, and cleanup.
+//! Tests localtunnel spawning, URL capture, ngrok/cloudflared URL parsing, health checks,
+//! reminder page detection, and cleanup.
 
 use crate::tunnel::*;
 use std::sync::Arc;
@@ -77,6 +78,7 @@ mod tests {
 
         // Verify the port is converted to string correctly
         assert_eq!(expected_args[2], "3000");
+        assert_eq!(localtunnel_args(port, None), expected_args);
     }
 
     #[test]
@@ -203,6 +205,252 @@ mod tests {
         // Just verify this compiles
         assert!(true);
     }
+
+    #[test]
+    fn test_default_provider_is_localtunnel() {
+        assert_eq!(TunnelProvider::default(), TunnelProvider::LocalTunnel);
+        assert_eq!(serde_json::to_string(&TunnelProvider::Ngrok).unwrap(), "\"ngrok\"");
+        assert_eq!(
+            serde_json::from_str::<TunnelProvider>("\"localtunnel\"").unwrap(),
+            TunnelProvider::LocalTunnel
+        );
+    }
+
+    #[test]
+    fn test_parse_ngrok_tunnels_prefers_https() {
+        let body = r#"{
+            "tunnels": [
+                { "public_url": "http://abc123.ngrok-free.app", "proto": "http",
+                  "config": { "addr": "http://localhost:8787" } },
+                { "public_url": "https://abc123.ngrok-free.app", "proto": "https",
+                  "config": { "addr": "http://localhost:8787" } }
+            ],
+            "uri": "/api/tunnels"
+        }"#;
+
+        assert_eq!(
+            parse_ngrok_tunnels(body, 8787).as_deref(),
+            Some("https://abc123.ngrok-free.app")
+        );
+    }
+
+    #[test]
+    fn test_parse_ngrok_tunnels_ignores_other_ports() {
+        let body = r#"{ "tunnels": [
+            { "public_url": "https://other.ngrok-free.app", "proto": "https",
+              "config": { "addr": "http://localhost:3000" } }
+        ] }"#;
+
+        assert!(parse_ngrok_tunnels(body, 8787).is_none());
+        assert_eq!(
+            parse_ngrok_tunnels(body, 3000).as_deref(),
+            Some("https://other.ngrok-free.app")
+        );
+    }
+
+    #[test]
+    fn test_parse_localtunnel_url() {
+        assert_eq!(
+            parse_localtunnel_url("your url is: https://abc123.loca.lt").as_deref(),
+            Some("https://abc123.loca.lt")
+        );
+        assert!(parse_localtunnel_url("your url is:").is_none());
+        assert!(parse_localtunnel_url("url: https://example.com").is_none());
+    }
+
+    #[test]
+    fn test_parse_cloudflared_url() {
+        let banner = "2024-05-01T10:00:00Z INF |  https://calm-river-demo-words.trycloudflare.com                                    |";
+        assert_eq!(
+            parse_cloudflared_url(banner).as_deref(),
+            Some("https://calm-river-demo-words.trycloudflare.com")
+        );
+
+        // Other cloudflared log lines mention the domain without a tunnel URL
+        assert!(parse_cloudflared_url(
+            "2024-05-01T10:00:00Z INF Requesting new quick Tunnel on trycloudflare.com..."
+        )
+        .is_none());
+        assert!(parse_cloudflared_url(
+            "2024-05-01T10:00:00Z INF +--------------------------------------------------+"
+        )
+        .is_none());
+        assert!(parse_cloudflared_url("").is_none());
+    }
+
+    #[test]
+    fn test_localtunnel_args_with_subdomain() {
+        assert_eq!(localtunnel_args(3000, None), vec!["localtunnel", "--port", "3000"]);
+        assert_eq!(
+            localtunnel_args(8787, Some("my-ide")),
+            vec!["localtunnel", "--port", "8787", "--subdomain", "my-ide"]
+        );
+    }
+
+    #[test]
+    fn test_validate_subdomain() {
+        assert!(validate_subdomain("my-ide").is_ok());
+        assert!(validate_subdomain("dev2024").is_ok());
+        assert!(validate_subdomain(&"a".repeat(MAX_SUBDOMAIN_LEN)).is_ok());
+
+        assert!(validate_subdomain("abc").is_err());
+        assert!(validate_subdomain(&"a".repeat(MAX_SUBDOMAIN_LEN + 1)).is_err());
+        assert!(validate_subdomain("My-IDE").is_err());
+        assert!(validate_subdomain("my_ide").is_err());
+        assert!(validate_subdomain("my.ide").is_err());
+        assert!(validate_subdomain("-my-ide").is_err());
+        assert!(validate_subdomain("my-ide-").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_capture_output_buffers_all_lines() {
+        use crate::server::LogBuffer;
+        use tokio::sync::Mutex;
+
+        let output = "your url is: https://abc123.loca.lt\n\
+                      connection refused: localtunnel.me:443 (check your firewall settings)\n\
+                      some other diagnostic\n";
+        let logs = LogBuffer::default();
+        let url = Arc::new(Mutex::new(None));
+
+        capture_output(
+            std::io::Cursor::new(output.as_bytes().to_vec()),
+            logs.clone(),
+            url.clone(),
+            parse_localtunnel_url,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            logs.lines(),
+            vec![
+                "your url is: https://abc123.loca.lt",
+                "connection refused: localtunnel.me:443 (check your firewall settings)",
+                "some other diagnostic",
+            ]
+        );
+        assert_eq!(url.lock().await.as_deref(), Some("https://abc123.loca.lt"));
+    }
+
+    #[test]
+    fn test_default_url_timeout_is_generous() {
+        assert_eq!(TunnelConfig::new(8787).url_timeout, DEFAULT_TUNNEL_URL_TIMEOUT);
+        assert!(DEFAULT_TUNNEL_URL_TIMEOUT >= std::time::Duration::from_secs(15));
+    }
+
+    #[tokio::test]
+    async fn test_poll_url_times_out_without_url() {
+        use std::time::Duration;
+        use tokio::sync::Mutex;
+
+        let url = Arc::new(Mutex::new(None));
+        let started = tokio::time::Instant::now();
+
+        let result = poll_url(&url, Duration::from_millis(100), Duration::from_millis(20)).await;
+
+        assert!(result.is_none());
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_poll_url_returns_once_populated() {
+        use std::time::Duration;
+        use tokio::sync::Mutex;
+
+        let url = Arc::new(Mutex::new(None));
+        let writer = url.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            *writer.lock().await = Some("https://abc123.loca.lt".to_string());
+        });
+
+        let result = poll_url(&url, Duration::from_secs(5), Duration::from_millis(10)).await;
+
+        assert_eq!(result.as_deref(), Some("https://abc123.loca.lt"));
+    }
+
+    #[tokio::test]
+    async fn test_poll_url_accepts_huge_timeout() {
+        use std::time::Duration;
+        use tokio::sync::Mutex;
+
+        let url = Arc::new(Mutex::new(Some("https://abc123.loca.lt".to_string())));
+
+        let result = poll_url(&url, Duration::from_secs(u64::MAX), Duration::from_millis(10)).await;
+
+        assert_eq!(result.as_deref(), Some("https://abc123.loca.lt"));
+    }
+
+    #[test]
+    fn test_consecutive_failures_trigger_reconnect() {
+        let mut health = TunnelHealth::default();
+        assert!(health.is_healthy());
+
+        for _ in 1..TUNNEL_HEALTH_FAILURE_THRESHOLD {
+            assert!(!health.record(false));
+            assert!(!health.is_healthy());
+        }
+        assert!(health.record(false));
+
+        health.reset();
+        assert!(health.is_healthy());
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let mut health = TunnelHealth::default();
+
+        for _ in 1..TUNNEL_HEALTH_FAILURE_THRESHOLD {
+            assert!(!health.record(false));
+        }
+        assert!(!health.record(true));
+        assert!(health.is_healthy());
+
+        // Failures must be consecutive again after a success
+        assert!(!health.record(false));
+    }
+
+    #[test]
+    fn test_is_healthy_status() {
+        assert!(is_healthy_status(200));
+        assert!(is_healthy_status(302));
+        assert!(is_healthy_status(401));
+        // Tunnel client gone
+        assert!(!is_healthy_status(404));
+        assert!(!is_healthy_status(502));
+        assert!(!is_healthy_status(530));
+    }
+
+    #[test]
+    fn test_is_localtunnel_interstitial() {
+        // HEAD: only the status is available
+        assert!(is_localtunnel_interstitial(511, None));
+        assert!(!is_localtunnel_interstitial(200, None));
+        assert!(!is_localtunnel_interstitial(404, None));
+
+        // GET: the reminder page's text gives it away
+        let reminder = r#"<html><body><h1>Friendly Reminder</h1>
+            <p>Tunnel website ahead!</p>
+            <p>To access the website, enter the password from
+            <a href="https://loca.lt/mytunnelpassword">loca.lt/mytunnelpassword</a></p>
+            <p>Or set a <code>bypass-tunnel-reminder</code> request header.</p></body></html>"#;
+        assert!(is_localtunnel_interstitial(200, Some(reminder)));
+        assert!(is_localtunnel_interstitial(511, Some(reminder)));
+
+        let app_page = "<html><head><title>S-IDE</title></head><body><div id=\"root\"></div></body></html>";
+        assert!(!is_localtunnel_interstitial(200, Some(app_page)));
+        assert!(!is_localtunnel_interstitial(405, Some("")));
+    }
+
+    #[test]
+    fn test_parse_ngrok_tunnels_not_ready_or_malformed() {
+        // ngrok API is up but the tunnel has not been established yet
+        assert!(parse_ngrok_tunnels(r#"{ "tunnels": [] }"#, 8787).is_none());
+        assert!(parse_ngrok_tunnels("", 8787).is_none());
+        assert!(parse_ngrok_tunnels("<html>502 Bad Gateway</html>", 8787).is_none());
+    }
 }
 
 // Helper function for URL parsing tests
@@ -0,0 +1,456 @@
+//! Update checks for the desktop app
+//!
+//! Wraps the Tauri updater plugin and runs a background task that checks for
+//! updates periodically, notifying the frontend without installing anything.
+
+use crate::common;
+use crate::config;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::UpdaterExt;
+use tokio_util::sync::CancellationToken;
+
+/// Event emitted with an [`UpdateInfo`] when a newer version is found
+pub const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+
+/// Event emitted with an [`UpdateProgress`] for each downloaded chunk
+pub const UPDATE_PROGRESS_EVENT: &str = "update-progress";
+
+/// Event emitted with an [`UpdateDownloaded`] once the download completes
+pub const UPDATE_DOWNLOADED_EVENT: &str = "update-downloaded";
+
+/// Event emitted with the version when an update download is cancelled
+pub const UPDATE_CANCELLED_EVENT: &str = "update-cancelled";
+
+/// Default time between automatic update checks
+pub const DEFAULT_CHECK_INTERVAL_HOURS: u64 = 6;
+
+/// Longest allowed time between automatic update checks (30 days)
+pub const MAX_CHECK_INTERVAL_HOURS: u64 = 30 * 24;
+
+/// Delay before the first automatic check, so startup isn't slowed down
+pub const INITIAL_CHECK_DELAY: Duration = Duration::from_secs(60);
+
+/// Update manifest for the stable channel (the latest GitHub release)
+pub const STABLE_ENDPOINT: &str =
+    "https://github.com/S-IDE-studio/S-IDE/releases/latest/download/latest.json";
+
+/// Update manifest for the beta channel (the rolling `beta` release)
+pub const BETA_ENDPOINT: &str =
+    "https://github.com/S-IDE-studio/S-IDE/releases/download/beta/latest.json";
+
+/// Release channel to take updates from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Regular releases
+    #[default]
+    Stable,
+    /// Pre-releases, published ahead of stable
+    Beta,
+}
+
+impl std::str::FromStr for UpdateChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "stable" => Ok(Self::Stable),
+            "beta" => Ok(Self::Beta),
+            other => Err(format!(
+                "Unknown update channel '{other}'. Use 'stable' or 'beta'."
+            )),
+        }
+    }
+}
+
+/// Update manifest URL for `channel`
+pub fn update_endpoint(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+/// An available update
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UpdateInfo {
+    /// The version that can be installed
+    pub version: String,
+    /// The version currently running
+    pub current_version: String,
+    /// Release notes, if any
+    pub body: Option<String>,
+    /// Release date, if known
+    pub date: Option<String>,
+    /// The channel the update was found on
+    pub channel: UpdateChannel,
+    /// Installer for this platform, for users who install manually
+    pub download_url: String,
+}
+
+/// Persisted updater settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UpdaterSettings {
+    /// Whether to check for updates in the background
+    #[serde(default = "default_auto_check")]
+    pub auto_check: bool,
+    /// Hours between background checks
+    #[serde(default = "default_check_interval_hours")]
+    pub check_interval_hours: u64,
+    /// Release channel to take updates from, stored as the `update_channel` config key
+    #[serde(default)]
+    pub channel: UpdateChannel,
+    /// Versions the user chose to skip; background checks don't announce them
+    #[serde(default)]
+    pub dismissed_versions: Vec<String>,
+}
+
+fn default_auto_check() -> bool {
+    true
+}
+
+fn default_check_interval_hours() -> u64 {
+    DEFAULT_CHECK_INTERVAL_HOURS
+}
+
+impl Default for UpdaterSettings {
+    fn default() -> Self {
+        Self {
+            auto_check: default_auto_check(),
+            check_interval_hours: default_check_interval_hours(),
+            channel: UpdateChannel::default(),
+            dismissed_versions: Vec::new(),
+        }
+    }
+}
+
+impl UpdaterSettings {
+    /// Time between background checks, clamped to one hour..=[`MAX_CHECK_INTERVAL_HOURS`]
+    pub fn check_interval(&self) -> Duration {
+        let hours = self.check_interval_hours.clamp(1, MAX_CHECK_INTERVAL_HOURS);
+        Duration::from_secs(hours.saturating_mul(60 * 60))
+    }
+
+    /// Checks that the settings are in range before they are saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `check_interval_hours` is outside 1..=[`MAX_CHECK_INTERVAL_HOURS`]
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_CHECK_INTERVAL_HOURS).contains(&self.check_interval_hours) {
+            return Err(format!(
+                "check_interval_hours must be between 1 and {MAX_CHECK_INTERVAL_HOURS}, got {}",
+                self.check_interval_hours
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the user chose to skip `version`
+    pub fn is_dismissed(&self, version: &str) -> bool {
+        self.dismissed_versions.iter().any(|v| v == version)
+    }
+
+    /// Adds `version` to the skip list (once)
+    pub fn dismiss(&mut self, version: &str) {
+        if !self.is_dismissed(version) {
+            self.dismissed_versions.push(version.to_string());
+        }
+    }
+
+    /// Drops `update` if its version was dismissed
+    pub fn filter_dismissed(&self, update: Option<UpdateInfo>) -> Option<UpdateInfo> {
+        update.filter(|info| !self.is_dismissed(&info.version))
+    }
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    Ok(common::home_dir()?.join(".side-ide").join("updater.json"))
+}
+
+/// Reads `updater.json` as is, whose channel may predate the config
+pub fn load_stored_settings() -> UpdaterSettings {
+    settings_path()
+        .and_then(|path| std::fs::read_to_string(path).map_err(|e| e.to_string()))
+        .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+        .unwrap_or_default()
+}
+
+/// Loads the persisted updater settings, or defaults if missing or invalid
+///
+/// The channel comes from the app config.
+pub fn load_settings() -> UpdaterSettings {
+    UpdaterSettings {
+        channel: config::load().update_channel,
+        ..load_stored_settings()
+    }
+}
+
+/// Persists the updater settings, saving the channel to the app config
+///
+/// # Errors
+///
+/// Returns an error if the settings or config file can't be written
+pub fn save_settings(settings: &UpdaterSettings) -> Result<(), String> {
+    let mut app_config = config::load();
+    if app_config.update_channel != settings.channel {
+        app_config.update_channel = settings.channel;
+        config::save(&app_config)?;
+    }
+
+    let path = settings_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {e}"))?;
+    }
+
+    let data = serde_json::to_string_pretty(settings).map_err(|e| format!("Invalid JSON: {e}"))?;
+    std::fs::write(&path, data).map_err(|e| format!("Failed to write settings: {e}"))
+}
+
+/// Queries the configured channel's endpoint, returning the update and its channel
+async fn find_update(
+    app: &AppHandle,
+) -> Result<Option<(tauri_plugin_updater::Update, UpdateChannel)>, String> {
+    let channel = load_settings().channel;
+    let endpoint = reqwest::Url::parse(update_endpoint(channel))
+        .map_err(|e| format!("Invalid update endpoint: {e}"))?;
+    let updater = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Failed to initialize updater: {e}"))?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {e}"))?;
+    Ok(update.map(|update| (update, channel)))
+}
+
+/// Checks the configured channel's update endpoint for a newer version
+///
+/// Dismissed versions are still reported, since the user asked explicitly.
+///
+/// # Errors
+///
+/// Returns an error if the updater can't be configured or the check fails
+pub async fn check_for_updates(app: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    Ok(find_update(app).await?.map(|(update, channel)| UpdateInfo {
+        version: update.version,
+        current_version: update.current_version,
+        body: update.body,
+        date: update.date.map(|date| date.to_string()),
+        channel,
+        download_url: update.download_url.to_string(),
+    }))
+}
+
+/// Progress of an update download
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct UpdateProgress {
+    /// Bytes downloaded so far
+    pub downloaded: u64,
+    /// Total size, if the server sent a content length
+    pub total: Option<u64>,
+    /// Percentage downloaded, or 0 when the total is unknown
+    pub percent: f64,
+    /// Download speed measured over the latest chunk
+    pub bytes_per_sec: f64,
+}
+
+/// Summary emitted when an update download completes
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct UpdateDownloaded {
+    /// Total bytes downloaded
+    pub downloaded: u64,
+    /// Time the download took
+    pub elapsed_ms: u64,
+}
+
+/// Turns the updater's chunk callbacks into [`UpdateProgress`] reports
+#[derive(Debug)]
+pub struct ProgressTracker {
+    started: Instant,
+    last_chunk: Instant,
+    downloaded: u64,
+    bytes_per_sec: f64,
+}
+
+impl ProgressTracker {
+    /// Starts tracking a download that began at `now`
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            last_chunk: now,
+            downloaded: 0,
+            bytes_per_sec: 0.0,
+        }
+    }
+
+    /// Records a chunk of `len` bytes received at `now`
+    ///
+    /// The speed is the chunk size over the time since the previous chunk;
+    /// chunks arriving in the same instant keep the previous speed.
+    pub fn record(&mut self, len: usize, total: Option<u64>, now: Instant) -> UpdateProgress {
+        self.downloaded += len as u64;
+
+        let elapsed = now.saturating_duration_since(self.last_chunk).as_secs_f64();
+        if elapsed > 0.0 {
+            self.bytes_per_sec = len as f64 / elapsed;
+        }
+        self.last_chunk = now;
+
+        let percent = match total {
+            Some(total) if total > 0 => (self.downloaded as f64 / total as f64 * 100.0).min(100.0),
+            _ => 0.0,
+        };
+
+        UpdateProgress {
+            downloaded: self.downloaded,
+            total,
+            percent,
+            bytes_per_sec: self.bytes_per_sec,
+        }
+    }
+
+    /// Summarizes the download, finished at `now`
+    pub fn finish(&self, now: Instant) -> UpdateDownloaded {
+        UpdateDownloaded {
+            downloaded: self.downloaded,
+            elapsed_ms: now.saturating_duration_since(self.started).as_millis() as u64,
+        }
+    }
+}
+
+/// Builds the per-chunk download callback
+///
+/// Records each chunk in `tracker` and passes the progress to `emit`. Once
+/// `token` is cancelled, chunks are ignored so no progress is reported for
+/// a download that is being torn down.
+pub fn progress_callback<E>(
+    tracker: Arc<Mutex<ProgressTracker>>,
+    token: CancellationToken,
+    emit: E,
+) -> impl FnMut(usize, Option<u64>)
+where
+    E: Fn(UpdateProgress),
+{
+    move |len, total| {
+        if token.is_cancelled() {
+            return;
+        }
+        let progress = tracker
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(len, total, Instant::now());
+        emit(progress);
+    }
+}
+
+/// Downloads and installs the available update, reporting progress
+///
+/// Emits [`UPDATE_PROGRESS_EVENT`] per chunk and [`UPDATE_DOWNLOADED_EVENT`]
+/// once the download completes. The app must be restarted afterwards.
+///
+/// Cancelling `token` aborts the download and emits [`UPDATE_CANCELLED_EVENT`].
+/// The package is downloaded into memory and only written out by the install
+/// step, so a cancelled download leaves no partial files and is never applied.
+///
+/// # Errors
+///
+/// Returns an error if no update is available, the download is cancelled, or
+/// the download or install fails
+pub async fn download_and_install(app: &AppHandle, token: CancellationToken) -> Result<(), String> {
+    let (update, _) = find_update(app)
+        .await?
+        .ok_or_else(|| "No update available".to_string())?;
+
+    let tracker = Arc::new(Mutex::new(ProgressTracker::new(Instant::now())));
+    let on_chunk = {
+        let app = app.clone();
+        progress_callback(Arc::clone(&tracker), token.clone(), move |progress| {
+            let _ = app.emit(UPDATE_PROGRESS_EVENT, progress);
+        })
+    };
+    let on_finish = {
+        let app = app.clone();
+        let tracker = Arc::clone(&tracker);
+        move || {
+            let summary = tracker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .finish(Instant::now());
+            tracing::warn!(
+                target: "Desktop",
+                "Update downloaded: {} bytes in {} ms",
+                summary.downloaded, summary.elapsed_ms
+            );
+            let _ = app.emit(UPDATE_DOWNLOADED_EVENT, summary);
+        }
+    };
+
+    let bytes = tokio::select! {
+        result = update.download(on_chunk, on_finish) => {
+            result.map_err(|e| format!("Failed to download update: {e}"))?
+        }
+        _ = token.cancelled() => Vec::new(),
+    };
+
+    // Also covers a cancel that lands between the download finishing and here
+    if token.is_cancelled() {
+        tracing::info!(target: "Desktop", "Update to {} cancelled", update.version);
+        let _ = app.emit(UPDATE_CANCELLED_EVENT, update.version.clone());
+        return Err("Update cancelled".to_string());
+    }
+
+    update
+        .install(bytes)
+        .map_err(|e| format!("Failed to install update: {e}"))
+}
+
+/// Remembers which version was last announced so it isn't announced again
+#[derive(Debug, Default)]
+pub struct UpdateNotifier {
+    last_notified: Option<String>,
+}
+
+impl UpdateNotifier {
+    /// Whether `version` should be announced; records it if so
+    pub fn should_notify(&mut self, version: &str) -> bool {
+        if self.last_notified.as_deref() == Some(version) {
+            return false;
+        }
+        self.last_notified = Some(version.to_string());
+        true
+    }
+}
+
+/// Starts the background update checks
+///
+/// Settings are re-read before every check, so disabling checks, changing
+/// the interval or dismissing a version takes effect without a restart.
+pub fn spawn_update_checks(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(INITIAL_CHECK_DELAY).await;
+
+        let mut notifier = UpdateNotifier::default();
+        loop {
+            let settings = load_settings();
+            if settings.auto_check {
+                match check_for_updates(&app).await.map(|u| settings.filter_dismissed(u)) {
+                    Ok(Some(info)) => {
+                        if notifier.should_notify(&info.version) {
+                            tracing::info!(target: "Desktop", "Update available: {}", info.version);
+                            let _ = app.emit(UPDATE_AVAILABLE_EVENT, info);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!(target: "Desktop", "Automatic update check failed: {e}"),
+                }
+            }
+            tokio::time::sleep(settings.check_interval()).await;
+        }
+    });
+}
@@ -0,0 +1,196 @@
+//! Unit tests for update checks
+//!
+//! Tests update notification debouncing, updater settings, channels,
+//! dismissed versions, download progress and cancellation.
+
+use crate::updater::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_version_is_not_renotified() {
+        let mut notifier = UpdateNotifier::default();
+        assert!(notifier.should_notify("1.2.0"));
+        assert!(!notifier.should_notify("1.2.0"));
+        assert!(!notifier.should_notify("1.2.0"));
+    }
+
+    #[test]
+    fn test_new_version_is_notified() {
+        let mut notifier = UpdateNotifier::default();
+        assert!(notifier.should_notify("1.2.0"));
+        assert!(notifier.should_notify("1.3.0"));
+        assert!(!notifier.should_notify("1.3.0"));
+    }
+
+    #[test]
+    fn test_settings_default_to_six_hourly_checks() {
+        let settings = UpdaterSettings::default();
+        assert!(settings.auto_check);
+        assert_eq!(settings.check_interval(), Duration::from_secs(6 * 60 * 60));
+    }
+
+    #[test]
+    fn test_settings_fill_missing_fields() {
+        let settings: UpdaterSettings = serde_json::from_str(r#"{"auto_check": false}"#).unwrap();
+        assert!(!settings.auto_check);
+        assert_eq!(settings.check_interval_hours, DEFAULT_CHECK_INTERVAL_HOURS);
+    }
+
+    #[test]
+    fn test_check_interval_is_at_least_an_hour() {
+        let settings = UpdaterSettings {
+            check_interval_hours: 0,
+            ..UpdaterSettings::default()
+        };
+        assert_eq!(settings.check_interval(), Duration::from_secs(60 * 60));
+    }
+
+    #[test]
+    fn test_check_interval_does_not_overflow() {
+        let settings = UpdaterSettings {
+            check_interval_hours: u64::MAX,
+            ..UpdaterSettings::default()
+        };
+        assert_eq!(
+            settings.check_interval(),
+            Duration::from_secs(MAX_CHECK_INTERVAL_HOURS * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn test_validate_check_interval_bounds() {
+        let with_hours = |check_interval_hours| UpdaterSettings {
+            check_interval_hours,
+            ..UpdaterSettings::default()
+        };
+        assert!(with_hours(1).validate().is_ok());
+        assert!(with_hours(MAX_CHECK_INTERVAL_HOURS).validate().is_ok());
+        assert!(with_hours(0).validate().is_err());
+        assert_eq!(
+            with_hours(u64::MAX).validate().unwrap_err(),
+            format!("check_interval_hours must be between 1 and {MAX_CHECK_INTERVAL_HOURS}, got {}", u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_endpoint_selection_by_channel() {
+        assert_eq!(update_endpoint(UpdateChannel::Stable), STABLE_ENDPOINT);
+        assert_eq!(update_endpoint(UpdateChannel::Beta), BETA_ENDPOINT);
+        assert_ne!(STABLE_ENDPOINT, BETA_ENDPOINT);
+        assert!(reqwest::Url::parse(BETA_ENDPOINT).is_ok());
+    }
+
+    #[test]
+    fn test_channel_defaults_to_stable() {
+        assert_eq!(UpdaterSettings::default().channel, UpdateChannel::Stable);
+        let settings: UpdaterSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings.channel, UpdateChannel::Stable);
+    }
+
+    #[test]
+    fn test_channel_parsing() {
+        assert_eq!("beta".parse::<UpdateChannel>(), Ok(UpdateChannel::Beta));
+        assert_eq!(" Stable ".parse::<UpdateChannel>(), Ok(UpdateChannel::Stable));
+        assert!("nightly".parse::<UpdateChannel>().is_err());
+    }
+
+    #[test]
+    fn test_progress_speed_from_chunk_timestamps() {
+        let start = Instant::now();
+        let mut tracker = ProgressTracker::new(start);
+
+        let first = tracker.record(1_000, Some(4_000), start + Duration::from_millis(500));
+        assert_eq!(first.downloaded, 1_000);
+        assert_eq!(first.bytes_per_sec, 2_000.0);
+        assert_eq!(first.percent, 25.0);
+
+        let second = tracker.record(3_000, Some(4_000), start + Duration::from_millis(1_500));
+        assert_eq!(second.downloaded, 4_000);
+        assert_eq!(second.bytes_per_sec, 3_000.0);
+        assert_eq!(second.percent, 100.0);
+
+        let summary = tracker.finish(start + Duration::from_millis(2_000));
+        assert_eq!(summary.downloaded, 4_000);
+        assert_eq!(summary.elapsed_ms, 2_000);
+    }
+
+    #[test]
+    fn test_progress_keeps_speed_for_simultaneous_chunks() {
+        let start = Instant::now();
+        let mut tracker = ProgressTracker::new(start);
+        let at = start + Duration::from_secs(1);
+
+        tracker.record(500, None, at);
+        let progress = tracker.record(500, None, at);
+        assert_eq!(progress.bytes_per_sec, 500.0);
+    }
+
+    #[test]
+    fn test_progress_without_content_length() {
+        let start = Instant::now();
+        let mut tracker = ProgressTracker::new(start);
+
+        let progress = tracker.record(2_048, None, start + Duration::from_secs(2));
+        assert_eq!(progress.percent, 0.0);
+        assert_eq!(progress.total, None);
+        assert_eq!(progress.downloaded, 2_048);
+        assert_eq!(progress.bytes_per_sec, 1_024.0);
+    }
+
+    fn update_info(version: &str) -> UpdateInfo {
+        UpdateInfo {
+            version: version.to_string(),
+            current_version: "1.0.0".to_string(),
+            body: None,
+            date: None,
+            channel: UpdateChannel::Stable,
+            download_url: format!("https://example.com/side-{version}.msi"),
+        }
+    }
+
+    #[test]
+    fn test_dismissed_versions_are_filtered() {
+        let mut settings = UpdaterSettings::default();
+        settings.dismiss("1.2.0");
+
+        assert!(settings.filter_dismissed(Some(update_info("1.2.0"))).is_none());
+        let newer = settings.filter_dismissed(Some(update_info("1.3.0")));
+        assert_eq!(newer.map(|info| info.version), Some("1.3.0".to_string()));
+        assert!(settings.filter_dismissed(None).is_none());
+    }
+
+    #[test]
+    fn test_dismiss_is_idempotent() {
+        let mut settings = UpdaterSettings::default();
+        settings.dismiss("1.2.0");
+        settings.dismiss("1.2.0");
+        assert_eq!(settings.dismissed_versions, vec!["1.2.0".to_string()]);
+    }
+
+    #[test]
+    fn test_progress_callback_stops_reporting_after_cancel() {
+        let tracker = Arc::new(Mutex::new(ProgressTracker::new(Instant::now())));
+        let token = CancellationToken::new();
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let mut on_chunk = {
+            let reported = Arc::clone(&reported);
+            progress_callback(Arc::clone(&tracker), token.clone(), move |progress| {
+                reported.lock().unwrap().push(progress.downloaded);
+            })
+        };
+
+        on_chunk(100, Some(1_000));
+        on_chunk(100, Some(1_000));
+        token.cancel();
+        on_chunk(100, Some(1_000));
+
+        assert_eq!(*reported.lock().unwrap(), vec![100, 200]);
+        assert_eq!(tracker.lock().unwrap().finish(Instant::now()).downloaded, 200);
+    }
+}
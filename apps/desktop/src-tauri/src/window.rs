@@ -2,24 +2,113 @@
 
 use tauri::{Emitter, Manager};
 use crate::common;
+use crate::log_file;
 use crate::remote_access;
+use crate::server;
+use crate::updater;
 use tokio::sync::Mutex as TokioMutex;
 use serde_json::json;
 
 /// Global server handle for cleanup
 static SERVER_HANDLE: TokioMutex<Option<tokio::process::Child>> = TokioMutex::const_new(None);
 
+/// Captured output of the server started at launch
+static SERVER_LOGS: std::sync::OnceLock<server::LogBuffer> = std::sync::OnceLock::new();
+
 /// Label for the main window
-const WINDOW_LABEL: &str = "main";
+pub const WINDOW_LABEL: &str = "main";
 
 /// Delay before window initialization (milliseconds)
 const WINDOW_INIT_DELAY_MS: u64 = 500;
 
+/// How long to wait for the server to log the port it is listening on
+const SERVER_PORT_ANNOUNCE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// How long to wait for the server's health check after spawning it
+const SERVER_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(7500);
+
 /// Maximum number of parent directories to search for server
 const MAX_SERVER_SEARCH_DEPTH: usize = 10;
 
-/// Server download URL (GitHub Releases)
-const SERVER_DOWNLOAD_URL: &str = "https://github.com/S-IDE-studio/S-IDE/releases/download/v2.1.7/server-bundle.zip";
+/// Base URL of the GitHub release downloads
+const SERVER_RELEASES_URL: &str = "https://github.com/S-IDE-studio/S-IDE/releases/download";
+
+/// File name of the server bundle in each release
+const SERVER_BUNDLE_NAME: &str = "server-bundle.zip";
+
+/// Environment variable overriding the server download URL (e.g. staging)
+pub const SERVER_URL_ENV: &str = "S_IDE_SERVER_URL";
+
+/// Event emitted while the server bundle downloads
+const DOWNLOAD_PROGRESS_EVENT: &str = "server-download-progress";
+
+/// Event emitted while the server bundle is extracted
+const EXTRACT_PROGRESS_EVENT: &str = "server-extract-progress";
+
+/// Event emitted before retrying a failed server bundle download
+const DOWNLOAD_RETRY_EVENT: &str = "server-download-retry";
+
+/// Download attempts in total, the first one included
+pub const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
+
+/// Delay before the first download retry; doubled for each further retry
+const DOWNLOAD_RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Minimum number of bytes between two download progress events
+const DOWNLOAD_PROGRESS_INTERVAL_BYTES: u64 = 256 * 1024;
+
+/// Payload of [`DOWNLOAD_PROGRESS_EVENT`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DownloadProgress {
+    /// Bytes received so far
+    pub downloaded: u64,
+    /// Total size from `Content-Length`, omitted when unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+}
+
+/// Payload of [`DOWNLOAD_RETRY_EVENT`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DownloadRetry {
+    /// Number of the attempt about to start, so 2 for the first retry
+    pub attempt: u32,
+    /// Attempts in total, the first one included
+    pub max_attempts: u32,
+    /// Seconds until the retry starts
+    pub delay_secs: u64,
+    /// Error of the failed attempt
+    pub error: String,
+}
+
+/// Payload of [`EXTRACT_PROGRESS_EVENT`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExtractProgress {
+    /// Entries processed so far
+    pub extracted: usize,
+    /// Number of entries in the archive
+    pub total: usize,
+}
+
+/// Suffix of the checksum file published next to the server bundle
+const CHECKSUM_SUFFIX: &str = ".sha256";
+
+/// Expected bundle SHA-256 embedded at build time, used when the checksum
+/// file can't be downloaded
+const EMBEDDED_SERVER_SHA256: Option<&str> = option_env!("S_IDE_SERVER_BUNDLE_SHA256");
+
+/// Stops the server spawned at startup, if it is still running
+///
+/// Returns whether there was one to stop.
+pub async fn stop_startup_server() -> Result<bool, String> {
+    let mut handle = SERVER_HANDLE.lock().await;
+    match handle.take() {
+        Some(mut child) => {
+            server::terminate_gracefully(&mut child, server::SHUTDOWN_GRACE_PERIOD).await?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
 
 /// Setup the main window
 ///
@@ -31,27 +120,45 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     let window = match app.get_webview_window(WINDOW_LABEL) {
         Some(w) => w,
         None => {
-            eprintln!("[Desktop] WARNING: Main window '{}' not found during setup", WINDOW_LABEL);
+            tracing::warn!(target: "Desktop", "Main window '{}' not found during setup", WINDOW_LABEL);
             // Don't return error - let app continue
             return Ok(());
         }
     };
 
+    // Tray icon with server/tunnel toggles; the app works without one
+    if let Err(e) = crate::tray::setup(app) {
+        tracing::warn!(target: "Desktop", "Failed to create tray icon: {e}");
+    }
+
+    restore_window_state(&window);
+
     // Setup window behavior
-    let _app_handle_for_cleanup = app.handle().clone();
+    let app_handle_for_cleanup = app.handle().clone();
+    let window_for_close = window.clone();
     window.on_window_event(move |event| {
-        if let tauri::WindowEvent::CloseRequested { .. } = event {
-            // Stop server when window is closing
-            if let Err(e) = tauri::async_runtime::block_on(async move {
-                let mut handle = SERVER_HANDLE.lock().await;
-                if let Some(mut child) = handle.take() {
-                    child.kill().await.map_err(|e| format!("Failed to kill server: {e}"))
-                } else {
-                    Ok(())
+        if matches!(event, tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)) {
+            schedule_window_state_save(&window_for_close);
+        }
+        if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+            save_window_state(&window_for_close);
+
+            // Keep running in the background if the user asked for it
+            if crate::config::load().close_to_tray && crate::tray::is_available(&app_handle_for_cleanup) {
+                api.prevent_close();
+                let _ = window_for_close.hide();
+                return;
+            }
+
+            // Abort an update download so it isn't applied half-way through closing
+            if let Some(update) = app_handle_for_cleanup.try_state::<crate::UpdateState>() {
+                if let Some(token) = tauri::async_runtime::block_on(update.0.lock()).take() {
+                    token.cancel();
                 }
-            }) {
-                eprintln!("[Desktop] Error stopping server on close: {}", e);
             }
+
+            // Stop server, tunnel and Remote Access when window is closing
+            tauri::async_runtime::block_on(crate::commands::shutdown_everything(&app_handle_for_cleanup));
         }
     });
 
@@ -69,21 +176,26 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         tokio::time::sleep(tokio::time::Duration::from_millis(WINDOW_INIT_DELAY_MS)).await;
 
         // Log startup for debugging
-        eprintln!("[Desktop] App starting...");
-        eprintln!("[Desktop] Current exe: {:?}", std::env::current_exe());
+        tracing::info!(target: "Desktop", "App starting...");
+        tracing::debug!(target: "Desktop", "Current exe: {:?}", std::env::current_exe());
+
+        // A custom server script skips both the dev and production search
+        let server_script = server::server_path_override();
 
         // Check if we're in development mode
-        let is_dev = is_development_mode();
-        eprintln!("[Desktop] Development mode: {}", is_dev);
+        let is_dev = server_script.is_none() && is_development_mode();
+        tracing::info!(target: "Desktop", "Development mode: {}", is_dev);
 
-        let server_dir = if is_dev {
+        let server_dir = if let Some(script) = &server_script {
+            script.parent().map(|p| p.to_path_buf()).unwrap_or_default()
+        } else if is_dev {
             match find_server_directory() {
                 Ok(dir) => {
                     // In development, run server directly from source via npm script.
                     if dir.join("package.json").exists() && dir.join("src").join("index.ts").exists() {
                         dir
                     } else {
-                        eprintln!("[Desktop] Server source not found in apps/server");
+                        tracing::error!(target: "Desktop", "Server source not found in apps/server");
                         let _ = app_handle.emit("server-error", serde_json::json!({
                             "message": "Server source not found. Expected apps/server/src/index.ts"
                         }));
@@ -91,7 +203,7 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                     }
                 }
                 Err(e) => {
-                    eprintln!("[Desktop] Failed to find server directory: {e}");
+                    tracing::error!(target: "Desktop", "Failed to find server directory: {e}");
                     let _ = app_handle.emit("server-error", serde_json::json!({
                         "message": format!("Failed to find server directory: {e}")
                     }));
@@ -100,14 +212,14 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             }
         } else {
             // Production mode: try to find bundled server or download it
-            eprintln!("[Desktop] Production mode: getting server...");
-            match get_production_server_directory().await {
+            tracing::info!(target: "Desktop", "Production mode: getting server...");
+            match get_production_server_directory(&app_handle).await {
                 Ok(dir) => {
-                    eprintln!("[Desktop] Got server directory: {}", dir.display());
+                    tracing::info!(target: "Desktop", "Got server directory: {}", dir.display());
                     dir
                 }
                 Err(e) => {
-                    eprintln!("[Desktop] Failed to get production server: {e}");
+                    tracing::error!(target: "Desktop", "Failed to get production server: {e}");
                     let _ = app_handle.emit("server-error", serde_json::json!({
                         "message": format!("Failed to get production server: {e}")
                     }));
@@ -116,14 +228,14 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             }
         };
 
-        eprintln!("[Desktop] Finding Node.js executable...");
+        tracing::info!(target: "Desktop", "Finding Node.js executable...");
         let node_exe = match common::find_node_executable() {
             Ok(exe) => {
-                eprintln!("[Desktop] Found Node.js: {}", exe);
+                tracing::info!(target: "Desktop", "Found Node.js: {}", exe);
                 exe
             }
             Err(e) => {
-                eprintln!("[Desktop] Failed to find Node.js: {e}");
+                tracing::error!(target: "Desktop", "Failed to find Node.js: {e}");
                 let _ = app_handle.emit("server-error", serde_json::json!({
                     "message": format!("Failed to find Node.js: {e}\\n\\nPlease install Node.js from https://nodejs.org/")
                 }));
@@ -132,67 +244,63 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         };
 
         // Start the server with hidden console
-        eprintln!("[Desktop] Attempting to spawn server...");
-        let spawn_result = spawn_server(&node_exe, &server_dir, is_dev);
+        tracing::info!(target: "Desktop", "Attempting to spawn server...");
+        let spawn_result = spawn_server(&node_exe, &server_dir, server_script.as_deref(), is_dev);
 
         match spawn_result {
             Ok(child) => {
-                eprintln!("[Desktop] Server spawned successfully");
+                tracing::info!(target: "Desktop", "Server spawned successfully");
                 // Store server handle for cleanup
                 let mut handle = SERVER_HANDLE.lock().await;
                 *handle = Some(child);
 
                 // Wait for server to be ready and verify it's actually responding
-                eprintln!("[Desktop] Waiting for server to be ready...");
-                let port = read_server_port_from_settings().unwrap_or(crate::common::DEFAULT_PORT);
-                let server_url = format!("http://localhost:{}", port);
+                tracing::info!(target: "Desktop", "Waiting for server to be ready...");
+                let configured_port = read_server_port_from_settings().unwrap_or_else(server::last_known_port);
 
-                // Poll server health endpoint until it responds
-                let client = reqwest::Client::builder()
-                    .timeout(std::time::Duration::from_secs(2))
-                    .build()
-                    .ok();
-
-                let mut server_ready = false;
-                for attempt in 0..15 {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-                    if let Some(ref client) = client {
-                        if let Ok(resp) = client.get(&format!("{}/health", server_url)).send().await {
-                            if resp.status().is_success() {
-                                eprintln!("[Desktop] Server is responding!");
-                                server_ready = true;
-                                break;
-                            }
-                        }
-                    }
+                // The server picks another port if the configured one is taken;
+                // its startup line says which (dev output isn't captured)
+                let announced_port = if is_dev {
+                    None
+                } else {
+                    server::wait_for_listening_port(startup_server_logs(), SERVER_PORT_ANNOUNCE_TIMEOUT).await
+                };
+                let port = announced_port.unwrap_or(configured_port);
+                if port != configured_port {
+                    tracing::info!(target: "Desktop", "Server chose port {} instead of {}", port, configured_port);
+                }
+                if let Err(e) = server::save_last_port(port) {
+                    tracing::warn!(target: "Desktop", "Failed to remember server port: {e}");
+                }
 
-                    // Also check if port is in use as fallback
-                    use std::net::TcpListener;
-                    if TcpListener::bind(format!("0.0.0.0:{}", port)).is_err() {
-                        eprintln!("[Desktop] Server port {} is in use (attempt {})", port, attempt + 1);
-                        if attempt >= 5 {
-                            // After a few attempts, consider it ready even if health check fails
-                            server_ready = true;
-                            break;
-                        }
+                // Poll server health endpoint until it responds
+                let health_path = crate::config::load().health_path;
+                let readiness = server::wait_for_ready(port, &health_path, SERVER_READY_TIMEOUT).await;
+                let server_ready = if readiness.ready {
+                    tracing::info!(target: "Desktop", "Server is responding after {} ms", readiness.elapsed_ms);
+                    true
+                } else {
+                    // Fall back to the port being bound, for servers without a health endpoint
+                    let in_use = std::net::TcpListener::bind(format!("0.0.0.0:{}", port)).is_err();
+                    if in_use {
+                        tracing::info!(target: "Desktop", "Server port {} is in use", port);
                     }
-                }
+                    in_use
+                };
 
                 if server_ready {
-                    eprintln!("[Desktop] Server ready, notifying frontend");
-                    let _ = app_handle.emit("server-ready", ());
+                    tracing::info!(target: "Desktop", "Server ready, notifying frontend");
                 } else {
-                    eprintln!("[Desktop] WARNING: Server may not be fully ready");
+                    tracing::warn!(target: "Desktop", "Server may not be fully ready");
                     // Still emit server-ready so frontend can proceed
-                    let _ = app_handle.emit("server-ready", ());
                 }
+                let _ = app_handle.emit("server-ready", json!({ "port": port }));
 
                 // Auto-start Remote Access (HTTPS) if enabled in Desktop settings.
                 let ra_settings = remote_access::load_settings().await;
                 if ra_settings.auto_start {
-                    if let Err(e) = remote_access::start_https(port).await {
-                        eprintln!("[Desktop] Failed to auto-start Remote Access: {e}");
+                    if let Err(e) = remote_access::start_https(port, None, None).await {
+                        tracing::error!(target: "Desktop", "Failed to auto-start Remote Access: {e}");
                         let _ = app_handle.emit("remote-access-error", json!({
                             "message": format!("Failed to auto-start Remote Access: {e}")
                         }));
@@ -200,7 +308,7 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             Err(e) => {
-                eprintln!("[Desktop] Failed to start server: {e}");
+                tracing::error!(target: "Desktop", "Failed to start server: {e}");
                 let _ = app_handle.emit("server-error", serde_json::json!({
                     "message": format!("Failed to start backend server: {e}\\n\\nPlease make sure Node.js is installed.")
                 }));
@@ -208,20 +316,336 @@ pub fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    setup_deep_links(app);
+    updater::spawn_update_checks(app.handle().clone());
+
     Ok(())
 }
 
+/// Quiet period after the last move/resize before the window state is saved
+const WINDOW_STATE_SAVE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Bumped on every move/resize so only the last scheduled save runs
+static WINDOW_STATE_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Position and size of a window or monitor, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowBounds {
+    fn right(&self) -> i64 {
+        i64::from(self.x) + i64::from(self.width)
+    }
+
+    fn bottom(&self) -> i64 {
+        i64::from(self.y) + i64::from(self.height)
+    }
+
+    /// Area shared with `other`, zero if they don't overlap
+    fn overlap_area(&self, other: &WindowBounds) -> i64 {
+        let width = self.right().min(other.right()) - i64::from(self.x.max(other.x));
+        let height = self.bottom().min(other.bottom()) - i64::from(self.y.max(other.y));
+        width.max(0) * height.max(0)
+    }
+}
+
+/// Moves and shrinks `saved` so it fits on one of `monitors`
+///
+/// The window goes to the monitor it overlaps most, or the first monitor if
+/// it is entirely off-screen (e.g. saved on a display that is now
+/// disconnected). Returns `None` if there are no monitors or the saved size
+/// is empty, in which case the default placement should be kept.
+pub fn clamp_to_monitors(saved: WindowBounds, monitors: &[WindowBounds]) -> Option<WindowBounds> {
+    if saved.width == 0 || saved.height == 0 {
+        return None;
+    }
+    let monitor = monitors
+        .iter()
+        .filter(|monitor| saved.overlap_area(monitor) > 0)
+        .max_by_key(|monitor| saved.overlap_area(monitor))
+        .or_else(|| monitors.first())?;
+
+    let width = saved.width.min(monitor.width);
+    let height = saved.height.min(monitor.height);
+    let max_x = i64::from(monitor.x) + i64::from(monitor.width - width);
+    let max_y = i64::from(monitor.y) + i64::from(monitor.height - height);
+    Some(WindowBounds {
+        x: i64::from(saved.x).clamp(i64::from(monitor.x), max_x) as i32,
+        y: i64::from(saved.y).clamp(i64::from(monitor.y), max_y) as i32,
+        width,
+        height,
+    })
+}
+
+fn window_state_path() -> Result<std::path::PathBuf, String> {
+    Ok(common::home_dir()?.join(".side-ide").join("window-state.json"))
+}
+
+/// Loads saved window bounds from `path`, or `None` if missing or invalid
+pub fn load_window_state_from(path: &std::path::Path) -> Option<WindowBounds> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+/// Persists window bounds to `path`
+///
+/// # Errors
+///
+/// Returns an error if the state file can't be written
+pub fn save_window_state_to(path: &std::path::Path, bounds: &WindowBounds) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create state dir: {e}"))?;
+    }
+
+    let data = serde_json::to_string_pretty(bounds).map_err(|e| format!("Invalid JSON: {e}"))?;
+    std::fs::write(path, data).map_err(|e| format!("Failed to write window state: {e}"))
+}
+
+/// Applies the saved size and position, kept on one of the current monitors
+fn restore_window_state(window: &tauri::WebviewWindow) {
+    let Some(saved) = window_state_path().ok().and_then(|path| load_window_state_from(&path)) else {
+        return;
+    };
+    let monitors: Vec<WindowBounds> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| WindowBounds {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        })
+        .collect();
+    let Some(bounds) = clamp_to_monitors(saved, &monitors) else {
+        return;
+    };
+
+    let _ = window.set_size(tauri::PhysicalSize::new(bounds.width, bounds.height));
+    let _ = window.set_position(tauri::PhysicalPosition::new(bounds.x, bounds.y));
+}
+
+/// Saves the window state once it has stopped moving for a moment
+fn schedule_window_state_save(window: &tauri::WebviewWindow) {
+    use std::sync::atomic::Ordering;
+
+    let generation = WINDOW_STATE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(WINDOW_STATE_SAVE_DELAY).await;
+        if WINDOW_STATE_GENERATION.load(Ordering::SeqCst) == generation {
+            save_window_state(&window);
+        }
+    });
+}
+
+/// Saves the window's current size and position
+///
+/// The size is the inner size, which is what `set_size` restores; the
+/// position is the outer one, matching `set_position`. Minimized and
+/// maximized windows are skipped so the last normal bounds are kept.
+fn save_window_state(window: &tauri::WebviewWindow) {
+    if window.is_minimized().unwrap_or(true) || window.is_maximized().unwrap_or(true) {
+        return;
+    }
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let bounds = WindowBounds { x: position.x, y: position.y, width: size.width, height: size.height };
+    if let Err(e) = window_state_path().and_then(|path| save_window_state_to(&path, &bounds)) {
+        tracing::warn!(target: "Desktop", "Failed to save window state: {e}");
+    }
+}
+
+/// Event emitted with a [`DeepLink`] when a `side-ide://` URL is opened
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+/// URL scheme registered for deep links
+pub const DEEP_LINK_SCHEME: &str = "side-ide";
+
+/// Longest workspace path accepted from a deep link
+const MAX_DEEP_LINK_PATH_LEN: usize = 4096;
+
+/// Validated `side-ide://` link, the payload of [`DEEP_LINK_EVENT`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLink {
+    /// `side-ide://open?workspace=/path`: open a local workspace
+    Open { workspace: String },
+}
+
+/// Parses and validates a `side-ide://` URL
+///
+/// Only known actions and parameters are accepted, so a web page can't
+/// smuggle anything else to the frontend. Workspaces must be absolute local
+/// paths without `..` components; network (UNC) paths are rejected.
+///
+/// # Errors
+///
+/// Returns an error describing why the URL was rejected
+pub fn parse_deep_link(raw: &str) -> Result<DeepLink, String> {
+    let url = url::Url::parse(raw).map_err(|e| format!("Invalid deep link: {e}"))?;
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Unsupported deep link scheme '{}'", url.scheme()));
+    }
+
+    match url.host_str() {
+        Some("open") => {
+            let mut workspace = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "workspace" if workspace.is_none() => workspace = Some(value.into_owned()),
+                    "workspace" => return Err("Deep link has more than one workspace".to_string()),
+                    other => return Err(format!("Unsupported deep link parameter '{other}'")),
+                }
+            }
+            let workspace = workspace.ok_or("Deep link is missing the workspace parameter")?;
+            validate_deep_link_path(&workspace)?;
+            Ok(DeepLink::Open { workspace })
+        }
+        Some(action) => Err(format!("Unsupported deep link action '{action}'")),
+        None => Err("Deep link has no action".to_string()),
+    }
+}
+
+fn validate_deep_link_path(path: &str) -> Result<(), String> {
+    if path.is_empty() || path.len() > MAX_DEEP_LINK_PATH_LEN || path.contains('\0') {
+        return Err("Deep link workspace path is invalid".to_string());
+    }
+    if path.starts_with("//") || path.starts_with("\\\\") {
+        return Err("Deep link workspace must be a local path".to_string());
+    }
+
+    let bytes = path.as_bytes();
+    let is_unix_absolute = bytes[0] == b'/';
+    let is_windows_absolute =
+        bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && matches!(bytes[2], b'/' | b'\\');
+    if !is_unix_absolute && !is_windows_absolute {
+        return Err("Deep link workspace must be an absolute path".to_string());
+    }
+    if path.split(['/', '\\']).any(|component| component == "..") {
+        return Err("Deep link workspace must not contain '..'".to_string());
+    }
+    Ok(())
+}
+
+/// Deep links waiting for the frontend to pull them
+pub struct PendingDeepLinks(std::sync::Mutex<Vec<DeepLink>>);
+
+impl PendingDeepLinks {
+    pub const fn new() -> Self {
+        Self(std::sync::Mutex::new(Vec::new()))
+    }
+
+    pub fn push(&self, link: DeepLink) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).push(link);
+    }
+
+    /// Removes and returns the waiting links, oldest first
+    pub fn take(&self) -> Vec<DeepLink> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// The link that launched the app, kept until the frontend asks for it
+static LAUNCH_DEEP_LINKS: PendingDeepLinks = PendingDeepLinks::new();
+
+/// Deep links that arrived before the frontend was listening
+///
+/// The launch link is read during setup, before the page has registered
+/// its [`DEEP_LINK_EVENT`] listener, so the frontend pulls it once loaded.
+pub fn take_launch_deep_links() -> Vec<DeepLink> {
+    LAUNCH_DEEP_LINKS.take()
+}
+
+/// Brings the main window to the front, e.g. when a second launch is redirected here
+pub fn focus_main_window(app_handle: &tauri::AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(WINDOW_LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Forwards `side-ide://` URLs to the frontend as [`DEEP_LINK_EVENT`]
+///
+/// Installers register the scheme from `tauri.conf.json`; debug builds on
+/// Windows and Linux register it at runtime so links work without installing.
+/// The launch link is queued for [`take_launch_deep_links`] instead. Links
+/// opened while the app runs reach it through the single-instance plugin.
+fn setup_deep_links(app: &tauri::App) {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    #[cfg(all(debug_assertions, any(target_os = "windows", target_os = "linux")))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!(target: "Desktop", "Failed to register deep link scheme: {e}");
+    }
+
+    let app_handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            emit_deep_link(&app_handle, url.as_str());
+        }
+    });
+
+    // The link that launched the app, if any
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            if let Some(link) = validated_deep_link(url.as_str()) {
+                LAUNCH_DEEP_LINKS.push(link);
+            }
+        }
+    }
+}
+
+fn emit_deep_link(app_handle: &tauri::AppHandle, url: &str) {
+    if let Some(link) = validated_deep_link(url) {
+        let _ = app_handle.emit(DEEP_LINK_EVENT, link);
+    }
+}
+
+fn validated_deep_link(url: &str) -> Option<DeepLink> {
+    parse_deep_link(url)
+        .map_err(|e| tracing::warn!(target: "Desktop", "Ignoring deep link {url}: {e}"))
+        .ok()
+}
+
 /// Spawns the server process with hidden console on Windows
 fn spawn_server(
     node_exe: &str,
     server_dir: &std::path::Path,
+    server_script: Option<&std::path::Path>,
     is_dev: bool,
 ) -> Result<tokio::process::Child, String> {
-    eprintln!("[Desktop] Spawning server (is_dev = {})", is_dev);
+    tracing::debug!(target: "Desktop", "Spawning server (is_dev = {})", is_dev);
+
+    let mut cmd = startup_server_command(node_exe, server_dir, server_script, is_dev)?;
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to spawn server: {e}"))?;
+    startup_server_logs().capture(&mut child);
+    Ok(child)
+}
+
+/// Builds the command for the server started at launch
+///
+/// # Errors
+///
+/// Returns an error if the package manager or the server script can't be found
+pub fn startup_server_command(
+    node_exe: &str,
+    server_dir: &std::path::Path,
+    server_script: Option<&std::path::Path>,
+    is_dev: bool,
+) -> Result<tokio::process::Command, String> {
 
     let mut cmd = if is_dev {
         let npm_cmd = common::find_npm_command()?;
-        eprintln!("[Desktop] Dev server command: {} run dev", npm_cmd);
+        tracing::debug!(target: "Desktop", "Dev server command: {} run dev", npm_cmd);
 
         #[cfg(target_os = "windows")]
         {
@@ -240,8 +664,10 @@ fn spawn_server(
             c
         }
     } else {
-        let index_js = server_dir.join("index.js");
-        eprintln!("[Desktop] Production server command: {} {}", node_exe, index_js.display());
+        let index_js = server_script
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| server_dir.join("index.js"));
+        tracing::debug!(target: "Desktop", "Production server command: {} {}", node_exe, index_js.display());
         if !index_js.exists() {
             return Err(format!("Server index.js not found at: {}", index_js.display()));
         }
@@ -258,34 +684,28 @@ fn spawn_server(
         cmd.stdout(std::process::Stdio::inherit());
         cmd.stderr(std::process::Stdio::inherit());
     } else {
-        // Production: suppress all output
-        cmd.stdout(std::process::Stdio::null());
-        cmd.stderr(std::process::Stdio::null());
+        // Production: capture output for get_server_logs
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
     }
     cmd.stdin(std::process::Stdio::null());
 
-    // Windows: Hide console window completely
+    // Windows: hide the console window, keeping one that can receive the
+    // CTRL_BREAK sent by stop_startup_server (in dev too, so it isn't ours)
     #[cfg(target_os = "windows")]
-    if !is_dev {
-        #[allow(unused_imports)]
-        use std::os::windows::process::CommandExt;
-        // CREATE_NO_WINDOW: 0x08000000 - Prevents console window creation
-        // DETACHED_PROCESS: 0x00000008 - Detaches from parent console
-        // CREATE_NEW_PROCESS_GROUP: 0x00000200 - Creates new process group
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        const DETACHED_PROCESS: u32 = 0x00000008;
-        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
-        
-        cmd.creation_flags(CREATE_NO_WINDOW | DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
-        eprintln!("[Desktop] Applied Windows console hiding flags");
-    }
+    server::hide_console(&mut cmd);
+
+    Ok(cmd)
+}
 
-    cmd.spawn().map_err(|e| format!("Failed to spawn server: {e}"))
+/// Output captured from the server started at launch
+pub fn startup_server_logs() -> &'static server::LogBuffer {
+    SERVER_LOGS.get_or_init(|| server::LogBuffer::default().with_file(log_file::server_log_sink()))
 }
 
 
 /// Gets or downloads the production server directory
-async fn get_production_server_directory() -> Result<std::path::PathBuf, String> {
+async fn get_production_server_directory(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     // First, try to find bundled server
     if let Ok(dir) = find_bundled_server() {
         return Ok(dir);
@@ -297,7 +717,7 @@ async fn get_production_server_directory() -> Result<std::path::PathBuf, String>
     }
 
     // Download and extract server
-    download_and_extract_server().await
+    download_and_extract_server(app_handle).await
 }
 
 /// Finds the bundled server in resources directory
@@ -310,13 +730,13 @@ fn find_bundled_server() -> Result<std::path::PathBuf, String> {
 
     let server_path = exe_dir.join("resources").join("server");
 
-    eprintln!("[Desktop] Checking for bundled server at: {}", server_path.display());
+    tracing::debug!(target: "Desktop", "Checking for bundled server at: {}", server_path.display());
 
     if server_path.exists() && server_path.join("index.js").exists() {
-        eprintln!("[Desktop] Found bundled server");
+        tracing::info!(target: "Desktop", "Found bundled server");
         Ok(server_path)
     } else {
-        eprintln!("[Desktop] Bundled server not found, will try to download");
+        tracing::info!(target: "Desktop", "Bundled server not found, will try to download");
         Err("Bundled server not found".to_string())
     }
 }
@@ -331,15 +751,92 @@ fn find_downloaded_server() -> Result<std::path::PathBuf, String> {
         .join("S-IDE")
         .join("server");
 
-    if server_dir.exists() && server_dir.join("index.js").exists() {
-        Ok(server_dir)
-    } else {
-        Err("Downloaded server not found".to_string())
+    match check_cached_server(&server_dir) {
+        CachedServer::Valid => Ok(server_dir),
+        CachedServer::Missing => Err("Downloaded server not found".to_string()),
+        CachedServer::Corrupt(reason) => {
+            // Remove the partial download so it's fetched again from scratch
+            tracing::warn!(target: "Desktop", "Downloaded server is incomplete ({reason}), re-downloading");
+            let _ = std::fs::remove_dir_all(&server_dir);
+            Err(format!("Downloaded server is incomplete: {reason}"))
+        }
+    }
+}
+
+/// File written after a successful extraction, listing the bundle's top-level entries
+pub const BUNDLE_MANIFEST_FILE: &str = "bundle-manifest.json";
+
+/// Contents of [`BUNDLE_MANIFEST_FILE`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BundleManifest {
+    /// URL the bundle was downloaded from
+    pub source: String,
+    /// Top-level files and directories of the extracted bundle
+    pub files: Vec<String>,
+}
+
+/// State of a previously downloaded server directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CachedServer {
+    /// Nothing has been downloaded
+    Missing,
+    /// Complete and ready to use
+    Valid,
+    /// Interrupted or damaged download that must be re-downloaded
+    Corrupt(String),
+}
+
+/// Checks a downloaded server directory for completeness
+///
+/// `index.js` must be non-empty and every entry listed in the manifest must
+/// exist. The manifest is written last, so a missing manifest means the
+/// extraction didn't finish.
+pub fn check_cached_server(server_dir: &std::path::Path) -> CachedServer {
+    if !server_dir.exists() {
+        return CachedServer::Missing;
+    }
+
+    match std::fs::metadata(server_dir.join("index.js")) {
+        Ok(meta) if meta.len() > 0 => {}
+        Ok(_) => return CachedServer::Corrupt("index.js is empty".to_string()),
+        Err(_) => return CachedServer::Corrupt("index.js is missing".to_string()),
+    }
+
+    let manifest = match std::fs::read_to_string(server_dir.join(BUNDLE_MANIFEST_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<BundleManifest>(&raw).ok())
+    {
+        Some(manifest) => manifest,
+        None => return CachedServer::Corrupt(format!("{BUNDLE_MANIFEST_FILE} is missing or invalid")),
+    };
+
+    match manifest.files.iter().find(|f| !server_dir.join(f).exists()) {
+        Some(missing) => CachedServer::Corrupt(format!("{missing} is missing")),
+        None => CachedServer::Valid,
     }
 }
 
+/// Records the top-level entries of an extracted bundle in [`BUNDLE_MANIFEST_FILE`]
+fn write_bundle_manifest(server_dir: &std::path::Path, source: &str) -> Result<(), String> {
+    let mut files: Vec<String> = std::fs::read_dir(server_dir)
+        .map_err(|e| format!("Failed to read server directory: {e}"))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name != BUNDLE_MANIFEST_FILE)
+        .collect();
+    files.sort();
+
+    let manifest = BundleManifest { source: source.to_string(), files };
+    let data = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Invalid JSON: {e}"))?;
+    std::fs::write(server_dir.join(BUNDLE_MANIFEST_FILE), data)
+        .map_err(|e| format!("Failed to write bundle manifest: {e}"))
+}
+
 /// Downloads and extracts the server bundle
-async fn download_and_extract_server() -> Result<std::path::PathBuf, String> {
+///
+/// Emits [`DOWNLOAD_PROGRESS_EVENT`] and [`EXTRACT_PROGRESS_EVENT`] so the UI
+/// can show progress.
+async fn download_and_extract_server(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     let app_data = std::env::var("LOCALAPPDATA")
         .or_else(|_| std::env::var("HOME"))
         .map_err(|_| "Failed to get AppData directory".to_string())?;
@@ -353,51 +850,268 @@ async fn download_and_extract_server() -> Result<std::path::PathBuf, String> {
         .map_err(|e| format!("Failed to create server directory: {e}"))?;
 
     // Download server bundle
-    eprintln!("[Desktop] Downloading server bundle from: {}", SERVER_DOWNLOAD_URL);
+    let download_url = server_download_url();
+    tracing::info!(target: "Desktop", "Downloading server bundle from: {}", download_url);
 
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(60))
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
 
-    let response = client.get(SERVER_DOWNLOAD_URL)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download server: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Failed to download server: HTTP {}", response.status()));
-    }
+    let bytes = download_with_retries(
+        || download_bundle(&client, &download_url, app_handle),
+        |retry| {
+            tracing::warn!(
+                target: "Desktop",
+                "Download failed ({}), attempt {}/{} in {}s",
+                retry.error, retry.attempt, retry.max_attempts, retry.delay_secs
+            );
+            let _ = app_handle.emit(DOWNLOAD_RETRY_EVENT, retry);
+        },
+    )
+    .await?;
 
-    let bytes = response.bytes()
-        .await
-        .map_err(|e| format!("Failed to read response: {e}"))?;
+    tracing::info!(target: "Desktop", "Downloaded {} bytes, verifying...", bytes.len());
 
-    eprintln!("[Desktop] Downloaded {} bytes, extracting...", bytes.len());
+    let expected_sha256 = fetch_expected_checksum(&client, &download_url).await?;
 
     // Extract zip
+    // A stale manifest would vouch for a half-extracted directory
+    let _ = std::fs::remove_file(server_dir.join(BUNDLE_MANIFEST_FILE));
+
     // Create a temporary file for the zip
     let temp_zip = server_dir.join("server-bundle.zip");
     std::fs::write(&temp_zip, &bytes)
         .map_err(|e| format!("Failed to write zip file: {e}"))?;
 
-    // Extract the zip
-    extract_zip(&temp_zip, &server_dir)?;
+    // Verify, then extract the zip
+    let result = verify_sha256(&bytes, &expected_sha256)
+        .and_then(|()| {
+            extract_zip(&temp_zip, &server_dir, |extracted, total| {
+                let _ = app_handle.emit(EXTRACT_PROGRESS_EVENT, ExtractProgress { extracted, total });
+            })
+        });
 
-    // Clean up zip file
+    // Clean up zip file, whether or not extraction happened
     let _ = std::fs::remove_file(&temp_zip);
+    result?;
+
+    // Written last: its presence marks a complete extraction
+    write_bundle_manifest(&server_dir, &download_url)?;
 
-    eprintln!("[Desktop] Server setup complete");
+    tracing::info!(target: "Desktop", "Server setup complete");
 
     Ok(server_dir)
 }
 
+/// A failed download attempt
+pub struct DownloadFailure {
+    pub message: String,
+    /// Whether trying again might succeed (connection errors, 5xx)
+    pub retryable: bool,
+}
+
+/// Runs `download` up to [`MAX_DOWNLOAD_ATTEMPTS`] times with backoff
+///
+/// `on_retry` is called before waiting out each retry delay. Failures that
+/// aren't retryable end it right away.
+///
+/// # Errors
+///
+/// Returns the last failure once no attempts are left
+pub async fn download_with_retries<T, F, Fut>(
+    mut download: F,
+    mut on_retry: impl FnMut(DownloadRetry),
+) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, DownloadFailure>>,
+{
+    let mut attempt = 1;
+    loop {
+        match download().await {
+            Ok(value) => return Ok(value),
+            Err(failure) if failure.retryable && attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                let delay = download_retry_delay(attempt - 1);
+                attempt += 1;
+                on_retry(DownloadRetry {
+                    attempt,
+                    max_attempts: MAX_DOWNLOAD_ATTEMPTS,
+                    delay_secs: delay.as_secs(),
+                    error: failure.message,
+                });
+                tokio::time::sleep(delay).await;
+            }
+            Err(failure) => {
+                return Err(format!(
+                    "Failed to download server after {attempt} attempt(s): {}",
+                    failure.message
+                ));
+            }
+        }
+    }
+}
+
+/// Downloads the bundle once, emitting [`DOWNLOAD_PROGRESS_EVENT`]
+async fn download_bundle(
+    client: &reqwest::Client,
+    url: &str,
+    app_handle: &tauri::AppHandle,
+) -> Result<Vec<u8>, DownloadFailure> {
+    use futures_util::StreamExt;
+
+    let response = client.get(url)
+        .send()
+        .await
+        .map_err(|e| DownloadFailure {
+            message: format!("Failed to download server: {e}"),
+            retryable: true,
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(DownloadFailure {
+            message: format!("Failed to download server: HTTP {status}"),
+            retryable: is_retryable_status(status.as_u16()),
+        });
+    }
+
+    let total = response.content_length();
+    let mut bytes = Vec::with_capacity(total.unwrap_or(0) as usize);
+    let mut last_emitted = 0u64;
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| DownloadFailure {
+            message: format!("Failed to read response: {e}"),
+            retryable: true,
+        })?;
+        bytes.extend_from_slice(&chunk);
+
+        let downloaded = bytes.len() as u64;
+        if downloaded - last_emitted >= DOWNLOAD_PROGRESS_INTERVAL_BYTES {
+            last_emitted = downloaded;
+            let _ = app_handle.emit(DOWNLOAD_PROGRESS_EVENT, DownloadProgress { downloaded, total });
+        }
+    }
+
+    // Always report completion
+    let _ = app_handle.emit(
+        DOWNLOAD_PROGRESS_EVENT,
+        DownloadProgress { downloaded: bytes.len() as u64, total },
+    );
+
+    Ok(bytes)
+}
+
+/// Whether an HTTP error status is worth retrying (server errors only)
+pub fn is_retryable_status(status: u16) -> bool {
+    (500..600).contains(&status)
+}
+
+/// Delay before retry `attempt` (0-based): 1s, 2s, 4s, ...
+pub fn download_retry_delay(attempt: u32) -> std::time::Duration {
+    DOWNLOAD_RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt))
+}
+
+/// Server bundle URL for a release version
+pub fn server_download_url_for_version(version: &str) -> String {
+    let version = version.trim_start_matches('v');
+    format!("{SERVER_RELEASES_URL}/v{version}/{SERVER_BUNDLE_NAME}")
+}
+
+/// Checks that `url` is a valid `https://` URL
+pub fn validate_https_url(url: &str) -> Result<url::Url, String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL {url:?}: {e}"))?;
+    if parsed.scheme() != "https" {
+        return Err(format!("URL must use https: {url}"));
+    }
+    Ok(parsed)
+}
+
+/// Server bundle URL matching this app's version, unless overridden by a
+/// valid `https://` URL in [`SERVER_URL_ENV`]
+fn server_download_url() -> String {
+    if let Ok(url) = std::env::var(SERVER_URL_ENV) {
+        match validate_https_url(&url) {
+            Ok(_) => return url,
+            Err(e) => tracing::warn!(target: "Desktop", "Ignoring {SERVER_URL_ENV}: {e}"),
+        }
+    }
+    server_download_url_for_version(env!("CARGO_PKG_VERSION"))
+}
+
+/// Gets the expected SHA-256 of the bundle at `bundle_url`
+///
+/// Downloads the `.sha256` file published with the release, falling back to
+/// the checksum embedded at build time.
+async fn fetch_expected_checksum(client: &reqwest::Client, bundle_url: &str) -> Result<String, String> {
+    let checksum_url = format!("{bundle_url}{CHECKSUM_SUFFIX}");
+
+    let downloaded = match client.get(&checksum_url).send().await {
+        Ok(resp) if resp.status().is_success() => resp.text().await.ok(),
+        Ok(resp) => {
+            tracing::warn!(target: "Desktop", "Checksum download failed: HTTP {}", resp.status());
+            None
+        }
+        Err(e) => {
+            tracing::warn!(target: "Desktop", "Checksum download failed: {e}");
+            None
+        }
+    };
+
+    downloaded
+        .as_deref()
+        .and_then(parse_checksum_file)
+        .or_else(|| EMBEDDED_SERVER_SHA256.and_then(parse_checksum_file))
+        .ok_or_else(|| {
+            format!("No valid SHA-256 checksum available for the server bundle (tried {checksum_url})")
+        })
+}
+
+/// Extracts the hash from a `sha256sum`-style line (`<hex>  <file name>`)
+pub fn parse_checksum_file(content: &str) -> Option<String> {
+    let hash = content.split_whitespace().next()?;
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| hash.to_ascii_lowercase())
+}
+
+/// Lowercase hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Checks `bytes` against an expected hex SHA-256 (case-insensitive)
+pub fn verify_sha256(bytes: &[u8], expected: &str) -> Result<(), String> {
+    let actual = sha256_hex(bytes);
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Server bundle checksum mismatch (expected {}, got {actual}). The download may be corrupted or tampered with.",
+            expected.trim()
+        ))
+    }
+}
+
 /// Extracts a zip file to the destination directory
-fn extract_zip(zip_path: &std::path::Path, dest: &std::path::Path) -> Result<(), String> {
+///
+/// `on_progress` is called with `(extracted_count, total_files)` after each entry.
+///
+/// Symlink entries, absolute paths and paths containing `..` are skipped, and
+/// every file's parent directory is canonicalized to make sure it stays
+/// inside `dest`.
+pub fn extract_zip(
+    zip_path: &std::path::Path,
+    dest: &std::path::Path,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<(), String> {
     use zip::read::ZipArchive;
     use std::io::Read;
 
-    eprintln!("[Desktop] Extracting server files...");
+    tracing::info!(target: "Desktop", "Extracting server files...");
 
     let file = std::fs::File::open(zip_path)
         .map_err(|e| format!("Failed to open zip: {e}"))?;
@@ -405,6 +1119,11 @@ fn extract_zip(zip_path: &std::path::Path, dest: &std::path::Path) -> Result<(),
     let mut archive = ZipArchive::new(file)
         .map_err(|e| format!("Failed to read zip archive: {e}"))?;
 
+    std::fs::create_dir_all(dest)
+        .map_err(|e| format!("Failed to create directory {:?}: {e}", dest))?;
+    let canonical_dest = dest.canonicalize()
+        .map_err(|e| format!("Failed to resolve directory {:?}: {e}", dest))?;
+
     let total_files = archive.len();
     let mut extracted_count = 0;
 
@@ -413,7 +1132,13 @@ fn extract_zip(zip_path: &std::path::Path, dest: &std::path::Path) -> Result<(),
             .map_err(|e| format!("Failed to get file {i}: {e}"))?;
 
         // Use name() instead of mangled_name() for proper filename handling
-        let file_name = file.name();
+        let file_name = file.name().to_string();
+
+        // Symlinks could point anywhere on the filesystem
+        if file.is_symlink() {
+            tracing::warn!(target: "Desktop", "Skipping symlink entry: {}", file_name);
+            continue;
+        }
 
         // Remove "server/" prefix if present (zip bundle contains server/ directory)
         let relative_path = if file_name.starts_with("server/") {
@@ -421,7 +1146,7 @@ fn extract_zip(zip_path: &std::path::Path, dest: &std::path::Path) -> Result<(),
         } else if file_name.starts_with("server\\") {
             &file_name[8..] // Skip "server\" prefix (Windows paths in zip)
         } else {
-            file_name
+            &file_name
         };
 
         // Skip empty paths or the server directory entry itself
@@ -429,6 +1154,11 @@ fn extract_zip(zip_path: &std::path::Path, dest: &std::path::Path) -> Result<(),
             continue;
         }
 
+        if !is_safe_relative_path(relative_path) {
+            tracing::warn!(target: "Desktop", "Skipping unsafe zip entry: {}", file_name);
+            continue;
+        }
+
         let path = dest.join(relative_path);
 
         // Security check: prevent zip slip vulnerability
@@ -437,11 +1167,22 @@ fn extract_zip(zip_path: &std::path::Path, dest: &std::path::Path) -> Result<(),
                 // Directory
                 std::fs::create_dir_all(&path)
                     .map_err(|e| format!("Failed to create directory {:?}: {e}", path))?;
+
+                #[cfg(unix)]
+                apply_unix_mode(&path, DEFAULT_DIR_MODE)?;
             } else {
                 // File
                 if let Some(parent) = path.parent() {
                     std::fs::create_dir_all(parent)
                         .map_err(|e| format!("Failed to create parent directory: {e}"))?;
+
+                    // A pre-existing symlinked directory could redirect the write
+                    let canonical_parent = parent.canonicalize()
+                        .map_err(|e| format!("Failed to resolve directory {:?}: {e}", parent))?;
+                    if !canonical_parent.starts_with(&canonical_dest) {
+                        tracing::warn!(target: "Desktop", "Skipping zip entry outside destination: {}", file_name);
+                        continue;
+                    }
                 }
 
                 let mut output = std::fs::File::create(&path)
@@ -453,17 +1194,56 @@ fn extract_zip(zip_path: &std::path::Path, dest: &std::path::Path) -> Result<(),
 
                 std::io::Write::write_all(&mut output, &buffer)
                     .map_err(|e| format!("Failed to write file: {e}"))?;
+
+                // Keep executable bits of bundled helper binaries
+                #[cfg(unix)]
+                if let Some(mode) = file.unix_mode() {
+                    apply_unix_mode(&path, mode)?;
+                }
             }
             extracted_count += 1;
+            on_progress(extracted_count, total_files);
         }
     }
 
-    eprintln!("[Desktop] Extracted {} / {} files", extracted_count, total_files);
+    tracing::info!(target: "Desktop", "Extracted {} / {} files", extracted_count, total_files);
     Ok(())
 }
 
+/// Permissions for directories created during extraction
+#[cfg(unix)]
+const DEFAULT_DIR_MODE: u32 = 0o755;
+
+/// Applies the permission bits of `mode` (file type bits are ignored)
+#[cfg(unix)]
+fn apply_unix_mode(path: &std::path::Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777))
+        .map_err(|e| format!("Failed to set permissions on {:?}: {e}", path))
+}
+
+/// Whether a zip entry path is relative and stays below its root
+///
+/// Rejects absolute paths (`/x`, `\x`, `C:\x`) and any `..` component, using
+/// both separators since archives built on Windows may use backslashes.
+pub fn is_safe_relative_path(path: &str) -> bool {
+    if path.starts_with('/') || path.starts_with('\\') || std::path::Path::new(path).is_absolute() {
+        return false;
+    }
+    // Drive letters such as "C:" on any platform
+    if path.as_bytes().get(1) == Some(&b':') {
+        return false;
+    }
+    path.split(['/', '\\']).all(|component| component != "..")
+}
+
 /// Finds the server directory by searching for package.json
 fn find_server_directory() -> Result<std::path::PathBuf, String> {
+    if let Some(dir) = server::server_path_override().as_deref().and_then(|p| p.parent()) {
+        return Ok(dir.to_path_buf());
+    }
+
     let current_dir = std::env::current_dir()
         .map_err(|e| format!("Failed to get current dir: {e}"))?;
 
@@ -0,0 +1,445 @@
+//! Unit tests for window setup
+//!
+//! Tests server bundle URLs, download retries, checksum verification,
+//! zip extraction, cache validation, progress payloads, window state
+//! clamping and deep link parsing.
+
+use crate::server;
+use crate::window::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SHA-256 of the bytes `hello`
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(b"hello"), HELLO_SHA256);
+    }
+
+    #[test]
+    fn test_verify_sha256_matching() {
+        assert!(verify_sha256(b"hello", HELLO_SHA256).is_ok());
+        // Comparison ignores case and surrounding whitespace
+        assert!(verify_sha256(b"hello", &format!("{}\n", HELLO_SHA256.to_uppercase())).is_ok());
+    }
+
+    #[test]
+    fn test_verify_sha256_mismatching() {
+        let err = verify_sha256(b"hello!", HELLO_SHA256).unwrap_err();
+        assert!(err.contains("checksum mismatch"));
+        assert!(err.contains(HELLO_SHA256));
+    }
+
+    #[test]
+    fn test_parse_checksum_file() {
+        let line = format!("{HELLO_SHA256}  server-bundle.zip\n");
+        assert_eq!(parse_checksum_file(&line).as_deref(), Some(HELLO_SHA256));
+        assert_eq!(parse_checksum_file(HELLO_SHA256).as_deref(), Some(HELLO_SHA256));
+        assert!(parse_checksum_file("").is_none());
+        assert!(parse_checksum_file("not-a-hash server-bundle.zip").is_none());
+        assert!(parse_checksum_file("<html>Not Found</html>").is_none());
+    }
+
+    #[test]
+    fn test_download_progress_omits_unknown_total() {
+        let known = DownloadProgress { downloaded: 10, total: Some(100) };
+        let unknown = DownloadProgress { downloaded: 10, total: None };
+
+        assert_eq!(serde_json::to_value(known).unwrap(), serde_json::json!({ "downloaded": 10, "total": 100 }));
+        assert_eq!(serde_json::to_value(unknown).unwrap(), serde_json::json!({ "downloaded": 10 }));
+    }
+
+    #[test]
+    fn test_server_download_url_for_version() {
+        assert_eq!(
+            server_download_url_for_version("3.1.2"),
+            "https://github.com/S-IDE-studio/S-IDE/releases/download/v3.1.2/server-bundle.zip"
+        );
+        // A leading "v" is not doubled
+        assert_eq!(
+            server_download_url_for_version("v3.1.2"),
+            server_download_url_for_version("3.1.2")
+        );
+    }
+
+    #[test]
+    fn test_validate_https_url() {
+        assert!(validate_https_url("https://staging.example.com/server-bundle.zip").is_ok());
+        assert!(validate_https_url("http://staging.example.com/server-bundle.zip").is_err());
+        assert!(validate_https_url("file:///tmp/server-bundle.zip").is_err());
+        assert!(validate_https_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_download_retry_delays() {
+        let delays: Vec<_> = (0..3).map(download_retry_delay).collect();
+        assert_eq!(
+            delays,
+            vec![
+                std::time::Duration::from_secs(1),
+                std::time::Duration::from_secs(2),
+                std::time::Duration::from_secs(4),
+            ]
+        );
+        // Doesn't overflow for absurd attempt counts
+        download_retry_delay(u32::MAX);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_download_makes_three_attempts_in_total() {
+        let mut attempts = 0;
+        let mut retries = Vec::new();
+
+        let result: Result<(), String> = download_with_retries(
+            || {
+                attempts += 1;
+                async {
+                    Err(DownloadFailure {
+                        message: "HTTP 503".to_string(),
+                        retryable: true,
+                    })
+                }
+            },
+            |retry| retries.push((retry.attempt, retry.max_attempts, retry.delay_secs)),
+        )
+        .await;
+
+        assert_eq!(attempts, MAX_DOWNLOAD_ATTEMPTS);
+        assert_eq!(MAX_DOWNLOAD_ATTEMPTS, 3);
+        assert_eq!(retries, vec![(2, 3, 1), (3, 3, 2)]);
+        assert_eq!(result, Err("Failed to download server after 3 attempt(s): HTTP 503".to_string()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_download_stops_on_permanent_failure() {
+        let mut attempts = 0;
+
+        let result: Result<(), String> = download_with_retries(
+            || {
+                attempts += 1;
+                async {
+                    Err(DownloadFailure {
+                        message: "HTTP 404".to_string(),
+                        retryable: false,
+                    })
+                }
+            },
+            |_| panic!("a 404 shouldn't be retried"),
+        )
+        .await;
+
+        assert_eq!(attempts, 1);
+        assert_eq!(result, Err("Failed to download server after 1 attempt(s): HTTP 404".to_string()));
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(is_retryable_status(500));
+        assert!(is_retryable_status(503));
+        assert!(!is_retryable_status(404));
+        assert!(!is_retryable_status(403));
+        assert!(!is_retryable_status(200));
+    }
+
+
+    /// Writes a zip with the given file entries and optional symlink entry
+    fn write_zip(path: &std::path::Path, files: &[&str], symlink: Option<(&str, &str)>) {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        for name in files {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(b"console.log('hi');").unwrap();
+        }
+        if let Some((name, target)) = symlink {
+            writer.add_symlink(name, target, SimpleFileOptions::default()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_is_safe_relative_path() {
+        assert!(is_safe_relative_path("index.js"));
+        assert!(is_safe_relative_path("dist/app.js"));
+        assert!(!is_safe_relative_path("../evil.js"));
+        assert!(!is_safe_relative_path("dist/../../evil.js"));
+        assert!(!is_safe_relative_path("dist\\..\\..\\evil.js"));
+        assert!(!is_safe_relative_path("/etc/evil.js"));
+        assert!(!is_safe_relative_path("\\evil.js"));
+        assert!(!is_safe_relative_path("C:\\evil.js"));
+    }
+
+    #[test]
+    fn test_extract_zip_rejects_escaping_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let dest = root.join("server");
+        let zip_path = root.join("bundle.zip");
+        let absolute = root.join("absolute-evil.js");
+        write_zip(
+            &zip_path,
+            &["server/index.js", "../evil.js", "server/../../parent-evil.js", absolute.to_str().unwrap()],
+            Some(("server/link.js", "/etc/passwd")),
+        );
+
+        let mut progress = Vec::new();
+        extract_zip(&zip_path, &dest, |done, total| progress.push((done, total))).unwrap();
+
+        assert!(dest.join("index.js").is_file());
+        assert!(!root.join("evil.js").exists());
+        assert!(!root.join("parent-evil.js").exists());
+        assert!(!absolute.exists());
+        assert!(std::fs::symlink_metadata(dest.join("link.js")).is_err());
+        // Only the safe entry counts as extracted
+        assert_eq!(progress, vec![(1, 5)]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_zip_does_not_follow_existing_symlinked_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let dest = root.join("server");
+        let outside = root.join("outside");
+        std::fs::create_dir_all(&dest).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, dest.join("dist")).unwrap();
+
+        let zip_path = root.join("bundle.zip");
+        write_zip(&zip_path, &["server/dist/evil.js"], None);
+        extract_zip(&zip_path, &dest, |_, _| {}).unwrap();
+
+        assert!(!outside.join("evil.js").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_zip_preserves_executable_bit() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+        use zip::write::SimpleFileOptions;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let dest = root.join("server");
+        let zip_path = root.join("bundle.zip");
+
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        writer.add_directory("server/bin/", SimpleFileOptions::default()).unwrap();
+        writer
+            .start_file("server/bin/helper", SimpleFileOptions::default().unix_permissions(0o755))
+            .unwrap();
+        writer.write_all(b"#!/bin/sh").unwrap();
+        writer
+            .start_file("server/index.js", SimpleFileOptions::default().unix_permissions(0o644))
+            .unwrap();
+        writer.write_all(b"").unwrap();
+        writer.finish().unwrap();
+
+        extract_zip(&zip_path, &dest, |_, _| {}).unwrap();
+
+        let mode = |p: &str| std::fs::metadata(dest.join(p)).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode("bin/helper"), 0o755);
+        assert_eq!(mode("index.js"), 0o644);
+        assert_eq!(mode("bin"), 0o755);
+    }
+
+    #[test]
+    fn test_check_cached_server() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let server_dir = root.join("server");
+        assert_eq!(check_cached_server(&server_dir), CachedServer::Missing);
+
+        // Interrupted before index.js was written
+        std::fs::create_dir_all(server_dir.join("dist")).unwrap();
+        assert!(matches!(check_cached_server(&server_dir), CachedServer::Corrupt(_)));
+
+        // Interrupted while writing index.js
+        std::fs::write(server_dir.join("index.js"), "").unwrap();
+        assert!(matches!(check_cached_server(&server_dir), CachedServer::Corrupt(_)));
+
+        // index.js complete, but extraction never finished (no manifest)
+        std::fs::write(server_dir.join("index.js"), "console.log('hi');").unwrap();
+        assert!(matches!(check_cached_server(&server_dir), CachedServer::Corrupt(_)));
+
+        let manifest = BundleManifest {
+            source: "https://example.com/server-bundle.zip".to_string(),
+            files: vec!["dist".to_string(), "index.js".to_string(), "node_modules".to_string()],
+        };
+        std::fs::write(
+            server_dir.join(BUNDLE_MANIFEST_FILE),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+        // A listed top-level entry went missing
+        assert_eq!(
+            check_cached_server(&server_dir),
+            CachedServer::Corrupt("node_modules is missing".to_string())
+        );
+
+        std::fs::create_dir_all(server_dir.join("node_modules")).unwrap();
+        assert_eq!(check_cached_server(&server_dir), CachedServer::Valid);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_startup_server_stops_gracefully() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::time::{Duration, Instant};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let script = dir.join("index.js");
+        std::fs::write(&script, "exec sleep 30").unwrap();
+
+        // `sh` stands in for node, so the script runs as a shell script
+        let mut child = startup_server_command("sh", dir, Some(&script), false).unwrap().spawn().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // The forced kill would only happen after the full grace period
+        let started = Instant::now();
+        server::terminate_gracefully(&mut child, server::SHUTDOWN_GRACE_PERIOD).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(child.wait().await.unwrap().signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn test_parse_deep_link_open_workspace() {
+        assert_eq!(
+            parse_deep_link("side-ide://open?workspace=/home/user/project"),
+            Ok(DeepLink::Open { workspace: "/home/user/project".to_string() })
+        );
+        assert_eq!(
+            parse_deep_link("side-ide://open?workspace=C%3A%5CUsers%5Cme%5Cproject"),
+            Ok(DeepLink::Open { workspace: "C:\\Users\\me\\project".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_parse_deep_link_payload() {
+        let link = parse_deep_link("side-ide://open?workspace=%2Ftmp%2Fmy%20project").unwrap();
+        assert_eq!(
+            serde_json::to_value(link).unwrap(),
+            serde_json::json!({ "action": "open", "workspace": "/tmp/my project" })
+        );
+    }
+
+    #[test]
+    fn test_pending_deep_links_are_taken_once() {
+        let pending = PendingDeepLinks::new();
+        pending.push(parse_deep_link("side-ide://open?workspace=/a").unwrap());
+        pending.push(parse_deep_link("side-ide://open?workspace=/b").unwrap());
+
+        assert_eq!(
+            pending.take(),
+            vec![
+                DeepLink::Open { workspace: "/a".to_string() },
+                DeepLink::Open { workspace: "/b".to_string() },
+            ]
+        );
+        assert!(pending.take().is_empty());
+    }
+
+    #[test]
+    fn test_parse_deep_link_rejects_unknown_input() {
+        for url in [
+            "not a url",
+            "https://open?workspace=/tmp",
+            "side-ide://delete?workspace=/tmp",
+            "side-ide://open",
+            "side-ide://open?workspace=/tmp&command=rm",
+            "side-ide://open?workspace=/tmp&workspace=/etc",
+        ] {
+            assert!(parse_deep_link(url).is_err(), "{url} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_parse_deep_link_rejects_unsafe_paths() {
+        for workspace in [
+            "",
+            "relative/path",
+            "/tmp/../etc",
+            "C:%5Cwork%5C..%5Csecret",
+            "%2F%2Fserver%2Fshare",
+            "%5C%5Cserver%5Cshare",
+            "/tmp/a%00b",
+        ] {
+            let url = format!("side-ide://open?workspace={workspace}");
+            assert!(parse_deep_link(&url).is_err(), "{workspace} should be rejected");
+        }
+        let too_long = format!("side-ide://open?workspace=/{}", "a".repeat(5000));
+        assert!(parse_deep_link(&too_long).is_err());
+    }
+
+    fn bounds(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds { x, y, width, height }
+    }
+
+    #[test]
+    fn test_clamp_to_monitors_keeps_visible_window() {
+        let monitors = [bounds(0, 0, 1920, 1080), bounds(1920, 0, 2560, 1440)];
+        let saved = bounds(2000, 100, 1200, 800);
+        assert_eq!(clamp_to_monitors(saved, &monitors), Some(saved));
+    }
+
+    #[test]
+    fn test_clamp_to_monitors_moves_off_screen_window() {
+        // Saved on a display to the right that is no longer connected
+        let monitors = [bounds(0, 0, 1920, 1080)];
+        assert_eq!(
+            clamp_to_monitors(bounds(2500, 200, 1200, 800), &monitors),
+            Some(bounds(720, 200, 1200, 800))
+        );
+        // Negative coordinates, e.g. a display that used to be on the left
+        assert_eq!(
+            clamp_to_monitors(bounds(-1800, -50, 1200, 800), &monitors),
+            Some(bounds(0, 0, 1200, 800))
+        );
+    }
+
+    #[test]
+    fn test_clamp_to_monitors_prefers_largest_overlap() {
+        let monitors = [bounds(0, 0, 1920, 1080), bounds(1920, 0, 1920, 1080)];
+        // Mostly on the second monitor, hanging off its bottom edge
+        assert_eq!(
+            clamp_to_monitors(bounds(1800, 600, 1000, 800), &monitors),
+            Some(bounds(1920, 280, 1000, 800))
+        );
+    }
+
+    #[test]
+    fn test_clamp_to_monitors_shrinks_oversized_window() {
+        let monitors = [bounds(0, 0, 1280, 720)];
+        assert_eq!(
+            clamp_to_monitors(bounds(100, 100, 2560, 1440), &monitors),
+            Some(bounds(0, 0, 1280, 720))
+        );
+    }
+
+    #[test]
+    fn test_clamp_to_monitors_without_monitors_or_size() {
+        assert_eq!(clamp_to_monitors(bounds(0, 0, 800, 600), &[]), None);
+        assert_eq!(clamp_to_monitors(bounds(0, 0, 0, 600), &[bounds(0, 0, 1920, 1080)]), None);
+    }
+
+    #[test]
+    fn test_window_state_round_trip_and_corrupt_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join("window-state.json");
+        assert_eq!(load_window_state_from(&path), None);
+
+        let saved = bounds(-100, 50, 1024, 768);
+        save_window_state_to(&path, &saved).unwrap();
+        assert_eq!(load_window_state_from(&path), Some(saved));
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load_window_state_from(&path), None);
+    }
+}
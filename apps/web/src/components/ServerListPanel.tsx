@@ -253,6 +253,7 @@ function AdvancedScanResultItem({ result }: AdvancedScanResultProps) {
   const openPorts = result.ports.filter((p) => p.status === "Open");
   const closedPorts = result.ports.filter((p) => p.status === "Closed");
   const filteredPorts = result.ports.filter((p) => p.status === "Filtered");
+  const openFilteredPorts = result.ports.filter((p) => p.status === "OpenFiltered");
 
   return (
     <div className="advanced-scan-result-item">
@@ -269,6 +270,9 @@ function AdvancedScanResultItem({ result }: AdvancedScanResultProps) {
         {filteredPorts.length > 0 && (
           <span className="filtered-count">{filteredPorts.length} filtered</span>
         )}
+        {openFilteredPorts.length > 0 && (
+          <span className="filtered-count">{openFilteredPorts.length} open|filtered</span>
+        )}
       </div>
 
       {openPorts.length > 0 && (
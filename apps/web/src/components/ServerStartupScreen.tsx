@@ -76,10 +76,10 @@ export function ServerStartupScreen({ onComplete }: ServerStartupScreenProps) {
       try {
         const api = await getTauriCore();
 
-        const result = (await api.invoke("get_server_status")) as {
+        const [result] = (await api.invoke("get_server_status")) as Array<{
           running: boolean;
           port: number;
-        };
+        }>;
 
         if (!alive) return;
 
@@ -120,10 +120,10 @@ export function ServerStartupScreen({ onComplete }: ServerStartupScreenProps) {
         if (!alive) return;
 
         // Verify server is running and healthy
-        const verifyResult = (await api.invoke("get_server_status")) as {
+        const [verifyResult] = (await api.invoke("get_server_status")) as Array<{
           running: boolean;
           port: number;
-        };
+        }>;
 
         if (verifyResult.running) {
           // Also verify health endpoint responds
@@ -26,16 +26,18 @@ export interface LocalServersResult {
 
 export interface PortInfo {
   port: number;
-  status: "Open" | "Closed" | "Filtered";
+  status: "Open" | "Closed" | "Filtered" | "OpenFiltered";
   protocol: string;
   service?: string;
   version?: string;
+  address_family?: "ipv4" | "ipv6" | "dual" | null;
 }
 
 export interface ServiceInfo {
   name: string;
   version?: string;
   info?: string;
+  proxy?: string | null;
 }
 
 export interface ScanResult {
@@ -45,11 +47,23 @@ export interface ScanResult {
   services: ServiceInfo[];
 }
 
+export interface ScanSummary {
+  results: ScanResult[];
+  duration_ms: number;
+  ports_scanned: number;
+  open_count: number;
+}
+
 export interface ScanOptions {
   ports?: number[];
   osDetection?: boolean;
   versionDetection?: boolean;
   useNmap?: boolean;
+  includeIpv6?: boolean;
+  /** Per-port timeout in ms (10-10000, default 200) */
+  timeoutMs?: number;
+  /** Ports probed at once (1-1000, default 100) */
+  parallelism?: number;
 }
 
 // Import Tauri API at module level to avoid dynamic imports in component
@@ -197,13 +211,16 @@ export function useAdvancedScan(): AdvancedScanResult {
     try {
       const api = await getTauriCore();
       if (!api) throw new Error("Tauri not available");
-      const data = await api.invoke<ScanResult[]>("scan_local_servers_advanced", {
+      const data = await api.invoke<ScanSummary>("scan_local_servers_advanced", {
         ports: options.ports ?? null,
         os_detection: options.osDetection ?? false,
         version_detection: options.versionDetection ?? false,
         use_nmap: options.useNmap ?? false,
+        include_ipv6: options.includeIpv6 ?? false,
+        timeout_ms: options.timeoutMs ?? null,
+        parallelism: options.parallelism ?? null,
       });
-      setResults(data);
+      setResults(data.results);
       setIsScanning(false);
     } catch (err) {
       const errorMsg = err instanceof Error ? err.message : String(err);
@@ -217,15 +234,44 @@ export function useAdvancedScan(): AdvancedScanResult {
   return { results, isScanning, error, scan };
 }
 
+export interface NmapStatus {
+  available: boolean;
+  version: string | null;
+  install_hint: string;
+}
+
 /**
  * Check if nmap is available on the system
  */
 export async function checkNmapAvailable(): Promise<boolean> {
+  return (await getNmapStatus())?.available ?? false;
+}
+
+/**
+ * Get nmap availability, version and install hint (cached by the backend)
+ */
+export async function getNmapStatus(refresh = false): Promise<NmapStatus | null> {
   try {
     const tauri = await getTauriCore();
-    if (!tauri) return false;
-    return await tauri.invoke<boolean>("check_nmap_available");
+    if (!tauri) return null;
+    return await tauri.invoke<NmapStatus>(refresh ? "refresh_nmap_status" : "check_nmap_available");
   } catch {
-    return false;
+    return null;
   }
 }
+
+export interface ConnectivityResult {
+  reachable: boolean;
+  status_code: number | null;
+  latency_ms: number;
+  error: string | null;
+}
+
+/**
+ * Test whether an http(s) URL (e.g. a tunnel URL) is reachable from this device
+ */
+export async function testConnectivity(url: string): Promise<ConnectivityResult | null> {
+  const tauri = await getTauriCore();
+  if (!tauri) return null;
+  return await tauri.invoke<ConnectivityResult>("test_connectivity", { url });
+}
@@ -82,10 +82,10 @@ export function useServerStatus(): ServerStatus {
           return;
         }
 
-        const result = (await tauri.invoke("get_server_status")) as {
+        const [result] = (await tauri.invoke("get_server_status")) as Array<{
           running: boolean;
           port: number;
-        };
+        }>;
 
         if (signal.aborted) return;
 